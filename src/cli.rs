@@ -0,0 +1,374 @@
+//! Non-interactive subcommands (`youtui search|play|download`). They reuse the
+//! TUI's search and playback plumbing but never take over the terminal, so they
+//! can be used from scripts, shell aliases and cron jobs.
+
+use std::ffi::{OsStr, OsString};
+use std::io::{self, Write};
+use std::process::ExitCode;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use colored::Colorize;
+
+use crate::cleanup::{INTERRUPTED, setup_signal_handler};
+use crate::config::{Config, MAX_RESULTS_PER_PAGE, MIN_RESULTS_PER_PAGE};
+use crate::player::{self, PlaybackResult, detect_player, supports_background_playback};
+use crate::player_manager::PlayerManager;
+use crate::remote;
+use crate::search::{self, PaginatedSearch, SearchResult, check_ytdlp};
+
+/// Invalid command-line arguments.
+pub const EXIT_USAGE: u8 = 2;
+/// The search finished without a single result.
+pub const EXIT_NO_RESULTS: u8 = 3;
+/// Stopped by Ctrl-C (128 + SIGINT, matching shell convention).
+pub const EXIT_INTERRUPTED: u8 = 130;
+
+const PLAYBACK_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Search {
+        query: String,
+        json: bool,
+        limit: Option<usize>,
+    },
+    Play {
+        video_id: String,
+    },
+    Download {
+        video_id: String,
+    },
+    /// Send a command to the running TUI over its control socket.
+    Remote {
+        command: Vec<String>,
+    },
+}
+
+pub fn is_command(name: &OsStr) -> bool {
+    matches!(
+        name.to_str(),
+        Some("search" | "play" | "download" | "remote")
+    )
+}
+
+/// Parse the arguments that follow a subcommand name accepted by `is_command`.
+pub fn parse_command(name: &OsStr, args: &[OsString]) -> Result<Command> {
+    let args = args
+        .iter()
+        .map(|arg| {
+            arg.to_str()
+                .map(str::to_string)
+                .with_context(|| format!("argument `{}` is not valid UTF-8", arg.to_string_lossy()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    match name.to_str() {
+        Some("search") => parse_search(&args),
+        Some("play") => Ok(Command::Play {
+            video_id: parse_video_target("play", &args)?,
+        }),
+        Some("download") => Ok(Command::Download {
+            video_id: parse_video_target("download", &args)?,
+        }),
+        Some("remote") if args.is_empty() => {
+            bail!(
+                "`youtui remote` needs a command such as `toggle-pause`\n\nTry `youtui --help` for usage."
+            )
+        }
+        Some("remote") => Ok(Command::Remote { command: args }),
+        _ => bail!("unknown command `{}`", name.to_string_lossy()),
+    }
+}
+
+fn parse_search(args: &[String]) -> Result<Command> {
+    let mut words = Vec::new();
+    let mut json = false;
+    let mut limit = None;
+    let mut options_done = false;
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        if options_done || !arg.starts_with('-') || arg == "-" {
+            words.push(arg.as_str());
+            continue;
+        }
+        match arg.as_str() {
+            "--" => options_done = true,
+            "--json" => json = true,
+            "--limit" => {
+                let value = args
+                    .next()
+                    .context("`--limit` needs a value\n\nTry `youtui --help` for usage.")?;
+                limit = Some(parse_limit(value)?);
+            }
+            other => match other.strip_prefix("--limit=") {
+                Some(value) => limit = Some(parse_limit(value)?),
+                None => bail!("unknown search option `{other}`\n\nTry `youtui --help` for usage."),
+            },
+        }
+    }
+
+    let query = words.join(" ");
+    if query.trim().is_empty() {
+        bail!("`youtui search` needs a query\n\nTry `youtui --help` for usage.");
+    }
+    Ok(Command::Search { query, json, limit })
+}
+
+fn parse_limit(value: &str) -> Result<usize> {
+    match value.parse::<usize>() {
+        Ok(limit) if (MIN_RESULTS_PER_PAGE..=MAX_RESULTS_PER_PAGE).contains(&limit) => Ok(limit),
+        _ => bail!(
+            "`--limit` must be a number from {MIN_RESULTS_PER_PAGE} to {MAX_RESULTS_PER_PAGE}, got `{value}`"
+        ),
+    }
+}
+
+fn parse_video_target(command: &str, args: &[String]) -> Result<String> {
+    let [target] = args else {
+        bail!(
+            "`youtui {command}` takes exactly one video ID or URL\n\nTry `youtui --help` for usage."
+        );
+    };
+    search::parse_video_id(target)
+        .with_context(|| format!("`{target}` is not a YouTube video ID or URL"))
+}
+
+/// Run a subcommand to completion and report how it ended.
+pub fn run(command: Command) -> Result<ExitCode> {
+    // The running instance does the work; this only needs its socket.
+    if let Command::Remote { command } = &command {
+        return run_remote(command);
+    }
+
+    let mut config = Config::load_or_create()?;
+    check_ytdlp()?;
+    setup_signal_handler();
+
+    let outcome = match command {
+        Command::Search { query, json, limit } => run_search(&config, &query, json, limit),
+        Command::Play { video_id } => {
+            config.player = detect_player()?;
+            run_play(&config, &video_id)
+        }
+        Command::Download { video_id } => run_download(&config, &video_id),
+        Command::Remote { .. } => unreachable!("handled before loading the configuration"),
+    };
+
+    // Ctrl-C also reaches yt-dlp and mpv, so whatever error they produced is
+    // a consequence of the interruption rather than a failure to report.
+    if INTERRUPTED.load(Ordering::SeqCst) {
+        return Ok(ExitCode::from(EXIT_INTERRUPTED));
+    }
+    outcome
+}
+
+fn run_search(config: &Config, query: &str, json: bool, limit: Option<usize>) -> Result<ExitCode> {
+    let limit = limit.unwrap_or(config.results_per_page);
+    let mut search = PaginatedSearch::new(query, limit, !config.include_shorts);
+    search.ensure_results_with_cancel_and_progress(limit, &INTERRUPTED, |_| {})?;
+
+    let results = &search.results[..search.results.len().min(limit)];
+    match write_results(&mut io::stdout().lock(), results, json) {
+        Ok(()) => {}
+        // `youtui search ... | head` closing the pipe early is not a failure.
+        Err(error) if error.kind() == io::ErrorKind::BrokenPipe => {}
+        Err(error) => return Err(error).context("Failed to write search results"),
+    }
+
+    if results.is_empty() {
+        eprintln!("No results for \"{query}\"");
+        return Ok(ExitCode::from(EXIT_NO_RESULTS));
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Plain output is one tab-separated `id duration channel title` row per
+/// result; `--json` prints the same results as a JSON array.
+fn write_results(out: &mut impl Write, results: &[SearchResult], json: bool) -> io::Result<()> {
+    if json {
+        serde_json::to_writer_pretty(&mut *out, results)?;
+        writeln!(out)?;
+    } else {
+        for result in results {
+            writeln!(
+                out,
+                "{}\t{}\t{}\t{}",
+                result.id,
+                plain_field(&result.duration),
+                plain_field(&result.channel),
+                plain_field(&result.title)
+            )?;
+        }
+    }
+    out.flush()
+}
+
+fn plain_field(value: &str) -> String {
+    value
+        .chars()
+        .map(|character| {
+            if character.is_control() {
+                ' '
+            } else {
+                character
+            }
+        })
+        .collect()
+}
+
+fn run_play(config: &Config, video_id: &str) -> Result<ExitCode> {
+    if !supports_background_playback(config.player) {
+        bail!(
+            "`youtui play` needs mpv for headless playback ({} was detected)",
+            config.player
+        );
+    }
+
+    let result = search::fetch_video(video_id)?;
+    println!(
+        "{} {} [{}]",
+        "Playing:".green(),
+        result.title,
+        result.duration
+    );
+
+    let mut player = PlayerManager::new(config)?;
+    player.play(config, &result.url(), &result.title, &result.id)?;
+    while !INTERRUPTED.load(Ordering::SeqCst) {
+        std::thread::sleep(PLAYBACK_POLL_INTERVAL);
+        player.update_status()?;
+        if player.is_eof() {
+            return Ok(ExitCode::SUCCESS);
+        }
+    }
+    Ok(ExitCode::from(EXIT_INTERRUPTED))
+}
+
+/// Print the reply's data: JSON for structured answers, nothing otherwise.
+fn run_remote(command: &[String]) -> Result<ExitCode> {
+    let data = remote::send(&remote::socket_path(), command)?;
+    if !data.is_null() {
+        println!("{}", serde_json::to_string_pretty(&data)?);
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn run_download(config: &Config, video_id: &str) -> Result<ExitCode> {
+    let result = search::fetch_video(video_id)?;
+    match player::download_permanently(config, &result.title, &result.url())? {
+        // download_permanently has already reported the failure.
+        PlaybackResult::Error(_) => Ok(ExitCode::FAILURE),
+        _ => Ok(ExitCode::SUCCESS),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(name: &str, args: &[&str]) -> Result<Command> {
+        let args: Vec<OsString> = args.iter().map(OsString::from).collect();
+        parse_command(OsStr::new(name), &args)
+    }
+
+    #[test]
+    fn search_joins_words_and_reads_options_anywhere() {
+        assert_eq!(
+            parse("search", &["lofi", "--json", "hip", "--limit", "5", "hop"]).unwrap(),
+            Command::Search {
+                query: "lofi hip hop".to_string(),
+                json: true,
+                limit: Some(5),
+            }
+        );
+        assert_eq!(
+            parse("search", &["--limit=12", "--", "--not-an-option"]).unwrap(),
+            Command::Search {
+                query: "--not-an-option".to_string(),
+                json: false,
+                limit: Some(12),
+            }
+        );
+    }
+
+    #[test]
+    fn search_rejects_missing_queries_bad_limits_and_unknown_options() {
+        assert!(parse("search", &[]).is_err());
+        assert!(parse("search", &["--json"]).is_err());
+        assert!(parse("search", &["query", "--limit"]).is_err());
+        assert!(parse("search", &["query", "--limit", "0"]).is_err());
+        assert!(parse("search", &["query", "--limit=501"]).is_err());
+        assert!(parse("search", &["query", "--limit", "ten"]).is_err());
+        assert!(parse("search", &["query", "--wat"]).is_err());
+    }
+
+    #[test]
+    fn play_and_download_take_exactly_one_video_reference() {
+        assert_eq!(
+            parse("play", &["https://youtu.be/dQw4w9WgXcQ"]).unwrap(),
+            Command::Play {
+                video_id: "dQw4w9WgXcQ".to_string(),
+            }
+        );
+        assert_eq!(
+            parse("download", &["dQw4w9WgXcQ"]).unwrap(),
+            Command::Download {
+                video_id: "dQw4w9WgXcQ".to_string(),
+            }
+        );
+        assert!(parse("play", &[]).is_err());
+        assert!(parse("play", &["dQw4w9WgXcQ", "extra"]).is_err());
+        assert!(parse("download", &["not a video"]).is_err());
+    }
+
+    #[test]
+    fn remote_passes_its_arguments_through() {
+        assert_eq!(
+            parse("remote", &["seek", "-10"]).unwrap(),
+            Command::Remote {
+                command: vec!["seek".to_string(), "-10".to_string()],
+            }
+        );
+        assert!(parse("remote", &[]).is_err());
+    }
+
+    #[test]
+    fn plain_results_are_tab_separated_with_control_characters_removed() {
+        let result = SearchResult::from_line_parts(
+            "Line\tbreak\ntitle",
+            "3:20",
+            "Channel",
+            "1K views",
+            "",
+            "dQw4w9WgXcQ",
+        )
+        .unwrap();
+        let mut out = Vec::new();
+
+        write_results(&mut out, &[result], false).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "dQw4w9WgXcQ\t3:20\tChannel\tLine break title\n"
+        );
+    }
+
+    #[test]
+    fn json_results_are_a_parseable_array() {
+        let result =
+            SearchResult::from_line_parts("Title", "1:00", "Channel", "0 views", "", "id").unwrap();
+        let mut out = Vec::new();
+
+        write_results(&mut out, std::slice::from_ref(&result), true).unwrap();
+        let parsed: Vec<SearchResult> = serde_json::from_slice(&out).unwrap();
+
+        assert_eq!(parsed, [result]);
+
+        let mut empty = Vec::new();
+        write_results(&mut empty, &[], true).unwrap();
+        assert_eq!(String::from_utf8(empty).unwrap(), "[]\n");
+    }
+}
@@ -1,10 +1,14 @@
 use std::fs;
 use std::io::Write;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result, anyhow};
 use serde::{Deserialize, Serialize};
 
+use crate::keymap::{KeyBindings, Keymap};
 use crate::player::PlayerType;
+use crate::queue::RepeatMode;
+use crate::sponsorblock::{self, SegmentQuery};
 
 pub(crate) const MIN_RESULTS_PER_PAGE: usize = 1;
 // YouTube searches are intentionally capped at 500 entries. Keeping a page at
@@ -12,6 +16,7 @@ pub(crate) const MIN_RESULTS_PER_PAGE: usize = 1;
 pub(crate) const MAX_RESULTS_PER_PAGE: usize = 500;
 pub(crate) const MIN_SEEK_STEP: u64 = 1;
 pub(crate) const MAX_SEEK_STEP: u64 = 3600;
+pub(crate) const MAX_RESUME_MARGIN: u64 = 3600;
 
 pub(crate) fn clamp_results_per_page(value: usize) -> usize {
     value.clamp(MIN_RESULTS_PER_PAGE, MAX_RESULTS_PER_PAGE)
@@ -21,6 +26,38 @@ pub(crate) fn clamp_seek_step(value: u64) -> u64 {
     value.clamp(MIN_SEEK_STEP, MAX_SEEK_STEP)
 }
 
+pub(crate) fn clamp_resume_margin(value: u64) -> u64 {
+    value.min(MAX_RESUME_MARGIN)
+}
+
+/// Directory holding `config.toml` and the rest of youtui's persistent state.
+pub(crate) fn config_dir() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir().ok_or(anyhow::anyhow!("No config directory found"))?;
+    Ok(config_dir.join("youtui"))
+}
+
+/// Write beside the destination and atomically rename into place. This
+/// prevents an interruption from leaving a truncated file behind.
+pub(crate) fn write_atomically(path: &Path, contents: &[u8]) -> Result<()> {
+    let parent = path
+        .parent()
+        .ok_or_else(|| anyhow!("{} has no parent directory", path.display()))?;
+    fs::create_dir_all(parent)
+        .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+
+    let mut temp = tempfile::NamedTempFile::new_in(parent)
+        .with_context(|| format!("Failed to create temporary file in {}", parent.display()))?;
+    temp.write_all(contents)
+        .context("Failed to write temporary file")?;
+    temp.as_file()
+        .sync_all()
+        .context("Failed to flush temporary file")?;
+    temp.persist(path)
+        .map_err(|error| error.error)
+        .with_context(|| format!("Failed to replace {}", path.display()))?;
+    Ok(())
+}
+
 fn default_auto_play_queue() -> bool {
     true
 }
@@ -70,11 +107,48 @@ pub struct Config {
     pub results_per_page: usize,
     pub seek_step: u64,
     pub seek_step_large: u64,
+    /// A saved resume position is dropped once playback gets this many
+    /// seconds from the end.
+    pub resume_margin: u64,
     pub custom_format: String,
     #[serde(default = "default_auto_play_queue")]
     pub auto_play_queue: bool,
     #[serde(default)]
     pub video_render: VideoRenderMode,
+    pub repeat: RepeatMode,
+    /// Play the queue in a random order.
+    pub shuffle: bool,
+    /// Offer playback controls to the desktop over MPRIS (Linux only).
+    pub mpris: bool,
+    /// Show a desktop notification when a queue track starts while the
+    /// terminal is in the background.
+    pub notify: bool,
+    /// Shell command run for each notification instead of the desktop
+    /// notification service. It gets the track in `YOUTUI_TITLE`,
+    /// `YOUTUI_CHANNEL`, `YOUTUI_VIDEO_ID` and `YOUTUI_URL`.
+    pub notify_command: String,
+    /// Keep the queue going with the YouTube mix for its last track.
+    pub radio: bool,
+    /// Show the selected result's thumbnail beside the results.
+    pub thumbnails: bool,
+    /// Color theme: dark, light, high-contrast, no-color, or the name of a
+    /// theme file in `themes/` beside this config.
+    pub theme: String,
+    /// Skip SponsorBlock segments while playing.
+    pub sponsorblock: bool,
+    /// Which segments to skip: sponsor, selfpromo, intro, outro, and
+    /// music_offtopic.
+    pub sponsorblock_categories: Vec<String>,
+    /// SponsorBlock server to ask. When empty, yt-dlp reads the segments.
+    pub sponsorblock_api: String,
+    /// Show the current subtitle line beneath the player status.
+    pub subtitles: bool,
+    /// Subtitle language code, such as "en" or "pt-BR".
+    pub subtitle_language: String,
+    /// Rebound keys, by context and action. Unlisted actions keep their
+    /// default keys.
+    #[serde(skip_serializing_if = "KeyBindings::is_empty")]
+    pub keys: KeyBindings,
 }
 
 impl Config {
@@ -90,10 +164,18 @@ impl Config {
         }
     }
 
-    fn load_from_path(config_path: &std::path::Path) -> Result<Self> {
+    fn load_from_path(config_path: &Path) -> Result<Self> {
         let contents = fs::read_to_string(config_path).context("Failed to read config file")?;
         let mut config: Config =
             toml::from_str(&contents).context("Failed to parse config file")?;
+        // A typo in a key binding is reported now rather than leaving the
+        // user with keys that silently do nothing.
+        config
+            .keymap()
+            .context("Invalid [keys] table in config file")?;
+        config
+            .check_sponsorblock_categories()
+            .context("Invalid sponsorblock_categories in config file")?;
         config.normalize();
         config.player = PlayerType::Mpv; // Placeholder, set in main
         Ok(config)
@@ -113,36 +195,45 @@ impl Config {
         Ok(())
     }
 
-    fn save_to_path(&self, config_path: &std::path::Path) -> Result<()> {
-        let parent = config_path
-            .parent()
-            .ok_or_else(|| anyhow!("Config path has no parent directory"))?;
-        fs::create_dir_all(parent).context("Failed to create config directory")?;
-
+    fn save_to_path(&self, config_path: &Path) -> Result<()> {
         // Serialize a normalized clone so an invalid value assigned through a
         // public field never becomes persistent configuration.
         let mut normalized = self.clone();
         normalized.normalize();
         let toml_string = toml::to_string_pretty(&normalized)?;
 
-        // Write beside the destination and atomically rename into place. This
-        // prevents an interruption from leaving a truncated config file.
-        let mut temp = tempfile::NamedTempFile::new_in(parent)
-            .context("Failed to create temporary config file")?;
-        temp.write_all(toml_string.as_bytes())
-            .context("Failed to write temporary config file")?;
-        temp.as_file()
-            .sync_all()
-            .context("Failed to flush temporary config file")?;
-        temp.persist(config_path)
-            .map_err(|error| error.error)
-            .context("Failed to replace config file")?;
-        Ok(())
+        write_atomically(config_path, toml_string.as_bytes()).context("Failed to save config file")
     }
 
-    fn config_path() -> Result<std::path::PathBuf> {
-        let config_dir = dirs::config_dir().ok_or(anyhow::anyhow!("No config directory found"))?;
-        Ok(config_dir.join("youtui/config.toml"))
+    /// The default key bindings with the `[keys]` table applied.
+    pub fn keymap(&self) -> Result<Keymap> {
+        Keymap::new(&self.keys)
+    }
+
+    fn check_sponsorblock_categories(&self) -> Result<()> {
+        match self
+            .sponsorblock_categories
+            .iter()
+            .find(|category| !sponsorblock::CATEGORIES.contains(&category.as_str()))
+        {
+            Some(category) => Err(anyhow!(
+                "unknown category \"{category}\", expected one of {}",
+                sponsorblock::CATEGORIES.join(", ")
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// What to ask SponsorBlock for, or `None` when nothing is skipped.
+    pub fn sponsorblock_query(&self) -> Option<SegmentQuery> {
+        (self.sponsorblock && !self.sponsorblock_categories.is_empty()).then(|| SegmentQuery {
+            api: self.sponsorblock_api.trim().to_string(),
+            categories: self.sponsorblock_categories.clone(),
+        })
+    }
+
+    fn config_path() -> Result<PathBuf> {
+        Ok(config_dir()?.join("config.toml"))
     }
 
     pub fn toggle_audio_only(&mut self) -> Result<()> {
@@ -175,6 +266,51 @@ impl Config {
         self.save()
     }
 
+    pub fn toggle_shuffle(&mut self) -> Result<()> {
+        self.shuffle = !self.shuffle;
+        self.save()
+    }
+
+    pub fn cycle_repeat(&mut self) -> Result<()> {
+        self.repeat = self.repeat.cycle();
+        self.save()
+    }
+
+    pub fn toggle_notify(&mut self) -> Result<()> {
+        self.notify = !self.notify;
+        self.save()
+    }
+
+    pub fn toggle_radio(&mut self) -> Result<()> {
+        self.radio = !self.radio;
+        self.save()
+    }
+
+    pub fn toggle_thumbnails(&mut self) -> Result<()> {
+        self.thumbnails = !self.thumbnails;
+        self.save()
+    }
+
+    pub fn toggle_sponsorblock(&mut self) -> Result<()> {
+        self.sponsorblock = !self.sponsorblock;
+        self.save()
+    }
+
+    pub fn toggle_subtitles(&mut self) -> Result<()> {
+        self.subtitles = !self.subtitles;
+        self.save()
+    }
+
+    pub fn set_subtitle_language(&mut self, language: &str) -> Result<()> {
+        language.clone_into(&mut self.subtitle_language);
+        self.save()
+    }
+
+    pub fn set_theme(&mut self, theme: &str) -> Result<()> {
+        theme.clone_into(&mut self.theme);
+        self.save()
+    }
+
     pub fn cycle_video_render(&mut self) -> Result<()> {
         self.video_render = self.video_render.cycle();
         self.save()
@@ -192,10 +328,14 @@ impl Config {
         self.results_per_page = clamp_results_per_page(self.results_per_page);
         self.seek_step = clamp_seek_step(self.seek_step);
         self.seek_step_large = clamp_seek_step(self.seek_step_large);
+        self.resume_margin = clamp_resume_margin(self.resume_margin);
 
         if self.download_dir.trim().is_empty() {
             self.download_dir = Self::default().download_dir;
         }
+        if self.subtitle_language.trim().is_empty() {
+            self.subtitle_language = Self::default().subtitle_language;
+        }
     }
 }
 
@@ -209,16 +349,31 @@ impl Default for Config {
             include_shorts: false,
             download_mode: false,
             download_dir: dirs::home_dir()
-                .unwrap_or_else(|| std::path::PathBuf::from("."))
+                .unwrap_or_else(|| PathBuf::from("."))
                 .join("Downloads")
                 .to_string_lossy()
                 .to_string(),
             results_per_page: 20,
             seek_step: 5,
             seek_step_large: 60,
+            resume_margin: 30,
             custom_format: String::new(),
             auto_play_queue: true,
             video_render: VideoRenderMode::Auto,
+            repeat: RepeatMode::Off,
+            shuffle: false,
+            mpris: false,
+            notify: false,
+            notify_command: String::new(),
+            radio: false,
+            thumbnails: false,
+            theme: "dark".to_string(),
+            sponsorblock: false,
+            sponsorblock_categories: vec!["sponsor".to_string(), "selfpromo".to_string()],
+            sponsorblock_api: sponsorblock::DEFAULT_API.to_string(),
+            subtitles: false,
+            subtitle_language: "en".to_string(),
+            keys: KeyBindings::new(),
         }
     }
 }
@@ -257,7 +412,22 @@ mod tests {
         assert_eq!(config.results_per_page, 20);
         assert_eq!(config.seek_step, 5);
         assert_eq!(config.seek_step_large, 60);
+        assert_eq!(config.resume_margin, 30);
         assert!(config.auto_play_queue);
+        assert!(!config.mpris);
+        assert_eq!(config.repeat, RepeatMode::Off);
+        assert!(!config.shuffle);
+        assert!(!config.notify);
+        assert!(config.notify_command.is_empty());
+        assert!(!config.radio);
+        assert!(!config.thumbnails);
+        assert_eq!(config.theme, "dark");
+        assert!(!config.sponsorblock);
+        assert_eq!(config.sponsorblock_categories, ["sponsor", "selfpromo"]);
+        assert_eq!(config.sponsorblock_api, "https://sponsor.ajay.app");
+        assert!(!config.subtitles);
+        assert_eq!(config.subtitle_language, "en");
+        assert!(config.keys.is_empty());
         assert!(!config.download_dir.is_empty());
     }
 
@@ -337,12 +507,70 @@ mod tests {
     fn seek_steps_are_clamped_when_loaded() {
         let temp_dir = tempfile::tempdir().unwrap();
         let path = temp_dir.path().join("config.toml");
-        fs::write(&path, "seek_step = 0\nseek_step_large = 3601\n").unwrap();
+        fs::write(
+            &path,
+            "seek_step = 0\nseek_step_large = 3601\nresume_margin = 7200\n",
+        )
+        .unwrap();
 
         let config = Config::load_from_path(&path).unwrap();
 
         assert_eq!(config.seek_step, MIN_SEEK_STEP);
         assert_eq!(config.seek_step_large, MAX_SEEK_STEP);
+        assert_eq!(config.resume_margin, MAX_RESUME_MARGIN);
+    }
+
+    #[test]
+    fn key_bindings_round_trip_and_conflicts_fail_the_load() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("config.toml");
+        fs::write(
+            &path,
+            "radio = true\n\n[keys.queue]\nmark = \"y\"\nremove = [\"delete\", \"d\"]\n",
+        )
+        .unwrap();
+
+        let config = Config::load_from_path(&path).unwrap();
+        config.save_to_path(&path).unwrap();
+        let saved = Config::load_from_path(&path).unwrap();
+        assert_eq!(saved.keys, config.keys);
+        assert_eq!(saved.keys["queue"].len(), 2);
+
+        fs::write(&path, "[keys.queue]\nmark = \"c\"\n").unwrap();
+        let error = Config::load_from_path(&path).unwrap_err();
+        assert_eq!(
+            format!("{error:#}"),
+            "Invalid [keys] table in config file: keys.queue.mark: c is already bound to clear"
+        );
+    }
+
+    #[test]
+    fn sponsorblock_categories_are_checked_when_loaded() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("config.toml");
+        fs::write(
+            &path,
+            "sponsorblock = true\nsponsorblock_categories = [\"intro\", \"outro\"]\nsponsorblock_api = \"\"\n",
+        )
+        .unwrap();
+
+        let config = Config::load_from_path(&path).unwrap();
+        let query = config.sponsorblock_query().unwrap();
+        assert_eq!(query.categories, ["intro", "outro"]);
+        assert!(query.api.is_empty());
+        let disabled = Config {
+            sponsorblock: false,
+            ..config
+        };
+        assert_eq!(disabled.sponsorblock_query(), None);
+
+        fs::write(&path, "sponsorblock_categories = [\"sponsors\"]\n").unwrap();
+        let error = Config::load_from_path(&path).unwrap_err();
+        assert_eq!(
+            format!("{error:#}"),
+            "Invalid sponsorblock_categories in config file: unknown category \"sponsors\", \
+             expected one of sponsor, selfpromo, intro, outro, music_offtopic"
+        );
     }
 
     #[test]
@@ -0,0 +1,277 @@
+//! The details view: a video's description, likes, exact upload date, tags
+//! and chapters. Search results only carry a summary, so the full metadata
+//! is read with `yt-dlp --dump-json` on a worker thread when the view opens,
+//! and closing the view stops yt-dlp.
+
+use std::sync::atomic::AtomicBool;
+
+use anyhow::Result;
+use serde_json::Value;
+
+use crate::search::{self, SearchResult};
+use crate::worker::Job;
+
+pub(crate) type FetchDetails = fn(&str, &AtomicBool) -> Result<VideoDetails>;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VideoDetails {
+    pub description: String,
+    pub views: Option<u64>,
+    pub likes: Option<u64>,
+    /// "2026-07-30".
+    pub uploaded: Option<String>,
+    pub tags: Vec<String>,
+    pub chapters: Vec<Chapter>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chapter {
+    /// Seconds from the start of the video.
+    pub start: f64,
+    pub title: String,
+}
+
+impl VideoDetails {
+    /// Pick the details out of yt-dlp's metadata. Everything is optional;
+    /// missing fields are left empty.
+    pub fn from_json(metadata: &Value) -> Self {
+        let text = |key: &str| metadata.get(key).and_then(Value::as_str);
+        let strings = |key: &str| {
+            metadata
+                .get(key)
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .map(str::trim)
+                .filter(|text| !text.is_empty())
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+        let chapters = metadata
+            .get("chapters")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|chapter| {
+                Some(Chapter {
+                    start: chapter.get("start_time")?.as_f64()?.max(0.0),
+                    title: chapter.get("title")?.as_str()?.trim().to_string(),
+                })
+            })
+            .collect();
+
+        Self {
+            description: text("description").unwrap_or_default().trim().to_string(),
+            views: metadata.get("view_count").and_then(Value::as_u64),
+            likes: metadata.get("like_count").and_then(Value::as_u64),
+            uploaded: text("upload_date").and_then(search::format_upload_date),
+            tags: strings("tags"),
+            chapters,
+        }
+    }
+}
+
+pub fn fetch_details(video_id: &str, cancelled: &AtomicBool) -> Result<VideoDetails> {
+    search::fetch_video_metadata(video_id, &[], cancelled)
+        .map(|metadata| VideoDetails::from_json(&metadata))
+}
+
+/// "1234567" -> "1,234,567": the details view shows exact counts.
+pub fn group_digits(count: u64) -> String {
+    let digits = count.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DetailsState {
+    Loading,
+    Loaded(VideoDetails),
+    Failed(String),
+}
+
+/// The open details view for one track.
+pub struct DetailsView {
+    pub track: SearchResult,
+    pub state: DetailsState,
+    /// Lines scrolled off the top.
+    pub scroll: u16,
+    fetch: Option<Job<VideoDetails>>,
+    fetch_details: FetchDetails,
+}
+
+impl DetailsView {
+    pub fn new(track: SearchResult) -> Self {
+        Self::with_fetcher(track, fetch_details)
+    }
+
+    pub(crate) fn with_fetcher(track: SearchResult, fetch_details: FetchDetails) -> Self {
+        Self {
+            track,
+            state: DetailsState::Loading,
+            scroll: 0,
+            fetch: None,
+            fetch_details,
+        }
+    }
+
+    /// Start reading the details the first time this runs, then pick them
+    /// up once the worker is done. Returns whether the view changed.
+    pub fn update(&mut self) -> bool {
+        if self.state != DetailsState::Loading {
+            return false;
+        }
+        let Some(fetch) = &self.fetch else {
+            return self.spawn_fetch();
+        };
+        let Some(result) = fetch.poll() else {
+            return false;
+        };
+        self.fetch = None;
+        self.state = match result {
+            Ok(details) => DetailsState::Loaded(details),
+            Err(error) => DetailsState::Failed(error.to_string()),
+        };
+        true
+    }
+
+    fn spawn_fetch(&mut self) -> bool {
+        let fetch_details = self.fetch_details;
+        let video_id = self.track.id.clone();
+        match Job::spawn("details", move |cancelled| {
+            fetch_details(&video_id, cancelled)
+        }) {
+            Ok(fetch) => {
+                self.fetch = Some(fetch);
+                false
+            }
+            Err(error) => {
+                self.state = DetailsState::Failed(format!("{error:#}"));
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use anyhow::bail;
+
+    use super::*;
+    use crate::worker::wait_until;
+
+    #[test]
+    fn details_come_from_the_dump_json_metadata() {
+        let metadata = serde_json::json!({
+            "id": "abcdefghijk",
+            "description": "  Recorded live.\nThanks for watching!\n",
+            "view_count": 1234567,
+            "like_count": 8910,
+            "upload_date": "20240305",
+            "tags": ["live", " ", "jazz "],
+            "chapters": [
+                {"start_time": 0.0, "end_time": 95.5, "title": "Intro"},
+                {"start_time": 95.5, "end_time": 300.0, "title": " Take Five "},
+                {"end_time": 310.0, "title": "No start"}
+            ]
+        });
+
+        let details = VideoDetails::from_json(&metadata);
+        assert_eq!(details.description, "Recorded live.\nThanks for watching!");
+        assert_eq!(details.views, Some(1_234_567));
+        assert_eq!(details.likes, Some(8910));
+        assert_eq!(details.uploaded.as_deref(), Some("2024-03-05"));
+        assert_eq!(details.tags, ["live", "jazz"]);
+        assert_eq!(
+            details.chapters,
+            [
+                Chapter {
+                    start: 0.0,
+                    title: "Intro".to_string()
+                },
+                Chapter {
+                    start: 95.5,
+                    title: "Take Five".to_string()
+                },
+            ]
+        );
+
+        // Hidden like counts and missing fields leave the details empty.
+        let details = VideoDetails::from_json(&serde_json::json!({"like_count": null}));
+        assert_eq!(details, VideoDetails::default());
+    }
+
+    #[test]
+    fn counts_are_grouped_in_thousands() {
+        assert_eq!(group_digits(0), "0");
+        assert_eq!(group_digits(999), "999");
+        assert_eq!(group_digits(1000), "1,000");
+        assert_eq!(group_digits(1_234_567), "1,234,567");
+    }
+
+    /// Run `update` until the fetch it starts has been delivered.
+    fn wait_for_details(view: &mut DetailsView) {
+        wait_until("details never arrived", || {
+            view.update();
+            view.state != DetailsState::Loading
+        });
+    }
+
+    #[test]
+    fn details_are_read_on_a_worker_and_failures_are_shown() {
+        fn liked(video_id: &str, _: &AtomicBool) -> Result<VideoDetails> {
+            Ok(VideoDetails {
+                description: format!("About {video_id}"),
+                likes: Some(3),
+                ..VideoDetails::default()
+            })
+        }
+        fn unavailable(video_id: &str, _: &AtomicBool) -> Result<VideoDetails> {
+            bail!("{video_id} is private")
+        }
+
+        let mut view = DetailsView::with_fetcher(SearchResult::test_track("a"), liked);
+        wait_for_details(&mut view);
+        let DetailsState::Loaded(details) = &view.state else {
+            panic!("expected details, got {:?}", view.state);
+        };
+        assert_eq!(details.description, "About a");
+        assert!(!view.update());
+
+        let mut view = DetailsView::with_fetcher(SearchResult::test_track("b"), unavailable);
+        wait_for_details(&mut view);
+        assert_eq!(view.state, DetailsState::Failed("b is private".to_string()));
+    }
+
+    #[test]
+    fn closing_the_view_cancels_the_read() {
+        static CANCELLED: AtomicBool = AtomicBool::new(false);
+        fn slow(_: &str, cancelled: &AtomicBool) -> Result<VideoDetails> {
+            let deadline = Instant::now() + Duration::from_secs(5);
+            while !cancelled.load(Ordering::Relaxed) && Instant::now() < deadline {
+                thread::sleep(Duration::from_millis(5));
+            }
+            CANCELLED.store(cancelled.load(Ordering::Relaxed), Ordering::Relaxed);
+            bail!("cancelled")
+        }
+
+        let mut view = DetailsView::with_fetcher(SearchResult::test_track("a"), slow);
+        view.update();
+        drop(view);
+
+        wait_until("the read was not cancelled", || {
+            CANCELLED.load(Ordering::Relaxed)
+        });
+    }
+}
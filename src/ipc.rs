@@ -56,7 +56,6 @@ impl IpcClient {
         Ok(response.get("data").cloned())
     }
 
-    #[allow(dead_code)]
     pub fn get_property(&mut self, property: &str) -> Result<Value> {
         let values = self.get_properties(&[property])?;
         values
@@ -0,0 +1,763 @@
+//! Named actions and the key chords that trigger them. Every action has a
+//! default binding; the `[keys]` table in `config.toml` rebinds actions per
+//! context, and conflicting bindings are rejected when the config loads.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use anyhow::{Result, anyhow, bail};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+
+/// Where a binding applies. Global bindings work outside the search bar in
+/// every panel and in the video view, so they shadow panel bindings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Context {
+    Global,
+    Video,
+    Results,
+    Queue,
+    Playlists,
+    History,
+    Search,
+}
+
+impl Context {
+    pub const ALL: [Self; 7] = [
+        Self::Global,
+        Self::Video,
+        Self::Results,
+        Self::Queue,
+        Self::Playlists,
+        Self::History,
+        Self::Search,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Global => "global",
+            Self::Video => "video",
+            Self::Results => "results",
+            Self::Queue => "queue",
+            Self::Playlists => "playlists",
+            Self::History => "history",
+            Self::Search => "search",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    Settings,
+    ToggleVideo,
+    Shuffle,
+    Repeat,
+    Pause,
+    Rewind,
+    FastForward,
+    SeekBack,
+    SeekForward,
+    SeekBackLarge,
+    SeekForwardLarge,
+    Resume,
+    JumpToTime,
+    VolumeUp,
+    VolumeDown,
+    Mute,
+    NextChapter,
+    PreviousChapter,
+    Chapters,
+    Subtitles,
+    SubtitleLanguage,
+    Transcript,
+    NextTrack,
+    PreviousTrack,
+    Help,
+    Up,
+    Down,
+    First,
+    Last,
+    NextPage,
+    PreviousPage,
+    Play,
+    Add,
+    AddPage,
+    PlayNext,
+    QueueAll,
+    Filters,
+    Search,
+    Remove,
+    MoveUp,
+    MoveDown,
+    Grab,
+    Mark,
+    ClearMarks,
+    Clear,
+    NewPlaylist,
+    AddQueue,
+    Rename,
+    Import,
+    Export,
+    Submit,
+    Cancel,
+    OlderSearch,
+    NewerSearch,
+    AcceptSuggestion,
+    FindSearch,
+    Details,
+}
+
+impl Action {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Quit => "quit",
+            Self::Settings => "settings",
+            Self::ToggleVideo => "video",
+            Self::Shuffle => "shuffle",
+            Self::Repeat => "repeat",
+            Self::Pause => "pause",
+            Self::Rewind => "rewind",
+            Self::FastForward => "fast_forward",
+            Self::SeekBack => "seek_back",
+            Self::SeekForward => "seek_forward",
+            Self::SeekBackLarge => "seek_back_large",
+            Self::SeekForwardLarge => "seek_forward_large",
+            Self::Resume => "resume",
+            Self::JumpToTime => "jump_to_time",
+            Self::VolumeUp => "volume_up",
+            Self::VolumeDown => "volume_down",
+            Self::Mute => "mute",
+            Self::NextChapter => "next_chapter",
+            Self::PreviousChapter => "previous_chapter",
+            Self::Chapters => "chapters",
+            Self::Subtitles => "subtitles",
+            Self::SubtitleLanguage => "subtitle_language",
+            Self::Transcript => "transcript",
+            Self::NextTrack => "next_track",
+            Self::PreviousTrack => "previous_track",
+            Self::Help => "help",
+            Self::Up => "up",
+            Self::Down => "down",
+            Self::First => "first",
+            Self::Last => "last",
+            Self::NextPage => "next_page",
+            Self::PreviousPage => "previous_page",
+            Self::Play => "play",
+            Self::Add => "add",
+            Self::AddPage => "add_page",
+            Self::PlayNext => "play_next",
+            Self::QueueAll => "queue_all",
+            Self::Filters => "filters",
+            Self::Search => "search",
+            Self::Remove => "remove",
+            Self::MoveUp => "move_up",
+            Self::MoveDown => "move_down",
+            Self::Grab => "grab",
+            Self::Mark => "mark",
+            Self::ClearMarks => "clear_marks",
+            Self::Clear => "clear",
+            Self::NewPlaylist => "new",
+            Self::AddQueue => "add_queue",
+            Self::Rename => "rename",
+            Self::Import => "import",
+            Self::Export => "export",
+            Self::Submit => "submit",
+            Self::Cancel => "cancel",
+            Self::OlderSearch => "older_search",
+            Self::NewerSearch => "newer_search",
+            Self::AcceptSuggestion => "accept_suggestion",
+            Self::FindSearch => "find_search",
+            Self::Details => "details",
+        }
+    }
+}
+
+/// Every action each context offers, with its default chords. The first
+/// chord is the one footers show.
+const DEFAULTS: &[(Context, Action, &[&str])] = &[
+    (Context::Global, Action::Quit, &["q", "Q"]),
+    (Context::Global, Action::Settings, &["S", "f2"]),
+    (Context::Global, Action::ToggleVideo, &["v"]),
+    (Context::Global, Action::Shuffle, &["z"]),
+    (Context::Global, Action::Repeat, &["o"]),
+    (Context::Global, Action::Pause, &["space"]),
+    (Context::Global, Action::Rewind, &["<"]),
+    (Context::Global, Action::FastForward, &[">"]),
+    (Context::Global, Action::SeekBack, &["left"]),
+    (Context::Global, Action::SeekForward, &["right"]),
+    (Context::Global, Action::SeekBackLarge, &["shift+left"]),
+    (Context::Global, Action::SeekForwardLarge, &["shift+right"]),
+    (Context::Global, Action::Resume, &["R"]),
+    (Context::Global, Action::JumpToTime, &["t"]),
+    (Context::Global, Action::VolumeUp, &["+", "="]),
+    (Context::Global, Action::VolumeDown, &["-"]),
+    (Context::Global, Action::Mute, &["m"]),
+    (Context::Global, Action::NextChapter, &["]"]),
+    (Context::Global, Action::PreviousChapter, &["["]),
+    (Context::Global, Action::Chapters, &["C"]),
+    (Context::Global, Action::Subtitles, &["l"]),
+    (Context::Global, Action::SubtitleLanguage, &["L"]),
+    (Context::Global, Action::Transcript, &["T"]),
+    (Context::Video, Action::NextTrack, &["n"]),
+    (Context::Video, Action::PreviousTrack, &["p"]),
+    (Context::Video, Action::Details, &["i"]),
+    (Context::Video, Action::Help, &["?", "h"]),
+    (Context::Results, Action::Up, &["up", "k"]),
+    (Context::Results, Action::Down, &["down", "j"]),
+    (Context::Results, Action::First, &["home", "g"]),
+    (Context::Results, Action::Last, &["end", "G"]),
+    (Context::Results, Action::NextPage, &["n", "pagedown"]),
+    (Context::Results, Action::PreviousPage, &["p", "pageup"]),
+    (Context::Results, Action::Play, &["enter"]),
+    (Context::Results, Action::Add, &["a"]),
+    (Context::Results, Action::AddPage, &["e"]),
+    (Context::Results, Action::PlayNext, &["N"]),
+    (Context::Results, Action::QueueAll, &["A"]),
+    (Context::Results, Action::Filters, &["f"]),
+    (Context::Results, Action::Details, &["i"]),
+    (Context::Results, Action::Search, &["/", "s"]),
+    (Context::Results, Action::Help, &["?", "h"]),
+    (Context::Queue, Action::Up, &["up", "k"]),
+    (Context::Queue, Action::Down, &["down", "j"]),
+    (Context::Queue, Action::First, &["home", "g"]),
+    (Context::Queue, Action::Last, &["end", "G"]),
+    (Context::Queue, Action::Play, &["enter"]),
+    (Context::Queue, Action::Remove, &["delete", "backspace"]),
+    (Context::Queue, Action::MoveUp, &["K"]),
+    (Context::Queue, Action::MoveDown, &["J"]),
+    (Context::Queue, Action::Grab, &["M"]),
+    (Context::Queue, Action::Mark, &["x"]),
+    (Context::Queue, Action::ClearMarks, &["X"]),
+    (Context::Queue, Action::Clear, &["c"]),
+    (Context::Queue, Action::Details, &["i"]),
+    (Context::Queue, Action::NextTrack, &["n"]),
+    (Context::Queue, Action::PreviousTrack, &["p"]),
+    (Context::Queue, Action::Search, &["/", "s"]),
+    (Context::Queue, Action::Help, &["?", "h"]),
+    (Context::Playlists, Action::Up, &["up", "k"]),
+    (Context::Playlists, Action::Down, &["down", "j"]),
+    (Context::Playlists, Action::First, &["home", "g"]),
+    (Context::Playlists, Action::Last, &["end", "G"]),
+    (Context::Playlists, Action::QueueAll, &["enter"]),
+    (Context::Playlists, Action::NewPlaylist, &["N"]),
+    (Context::Playlists, Action::AddQueue, &["a"]),
+    (Context::Playlists, Action::Rename, &["r"]),
+    (Context::Playlists, Action::Remove, &["d", "delete"]),
+    (Context::Playlists, Action::Import, &["i"]),
+    (Context::Playlists, Action::Export, &["e"]),
+    (Context::Playlists, Action::Search, &["/", "s"]),
+    (Context::Playlists, Action::Help, &["?", "h"]),
+    (Context::History, Action::Up, &["up", "k"]),
+    (Context::History, Action::Down, &["down", "j"]),
+    (Context::History, Action::First, &["home", "g"]),
+    (Context::History, Action::Last, &["end", "G"]),
+    (Context::History, Action::Play, &["enter"]),
+    (Context::History, Action::Add, &["a"]),
+    (
+        Context::History,
+        Action::Remove,
+        &["d", "delete", "backspace"],
+    ),
+    (Context::History, Action::Search, &["/", "s"]),
+    (Context::History, Action::Help, &["?", "h"]),
+    (Context::Search, Action::Submit, &["enter"]),
+    (Context::Search, Action::Cancel, &["esc"]),
+    (Context::Search, Action::OlderSearch, &["up"]),
+    (Context::Search, Action::NewerSearch, &["down"]),
+    (Context::Search, Action::AcceptSuggestion, &["right", "end"]),
+    (Context::Search, Action::FindSearch, &["ctrl+r"]),
+];
+
+/// The `[keys]` table: context name, then action name, then one chord or a
+/// list of them. An empty list unbinds the action.
+pub type KeyBindings = BTreeMap<String, BTreeMap<String, Chords>>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Chords {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Chords {
+    fn as_slice(&self) -> &[String] {
+        match self {
+            Self::One(chord) => std::slice::from_ref(chord),
+            Self::Many(chords) => chords,
+        }
+    }
+}
+
+/// A key with the modifiers that matter. Shift is folded into the character
+/// for printable keys, so `shift+j` and `J` are the same chord.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let mut modifiers =
+            modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        let code = match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            KeyCode::BackTab => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::BackTab
+            }
+            code => code,
+        };
+        Self { code, modifiers }
+    }
+
+    pub fn from_event(key: &KeyEvent) -> Self {
+        Self::new(key.code, key.modifiers)
+    }
+
+    /// Parse chords like `x`, `J`, `space`, `ctrl+r`, `shift+left` or `f2`.
+    /// Named keys and modifiers are case-insensitive; characters are not.
+    pub fn parse(text: &str) -> Result<Self> {
+        let (prefix, key) = match text.strip_suffix("++") {
+            Some(prefix) => (prefix, "+"),
+            None if text == "+" => ("", "+"),
+            None => text.rsplit_once('+').unwrap_or(("", text)),
+        };
+        let mut modifiers = KeyModifiers::NONE;
+        if !prefix.is_empty() {
+            for modifier in prefix.split('+') {
+                modifiers |= match modifier.to_ascii_lowercase().as_str() {
+                    "ctrl" | "control" => KeyModifiers::CONTROL,
+                    "alt" => KeyModifiers::ALT,
+                    "shift" => KeyModifiers::SHIFT,
+                    _ => bail!("unknown modifier \"{modifier}\" in \"{text}\""),
+                };
+            }
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            // With Ctrl or Alt held, a letter's case comes from Shift, so
+            // "ctrl+R" means the same as "ctrl+r".
+            (Some(c), None) if modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => {
+                KeyCode::Char(c.to_ascii_lowercase())
+            }
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key.to_ascii_lowercase().as_str() {
+                "space" => KeyCode::Char(' '),
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" | "bksp" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                name => match name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(number @ 1..=12) => KeyCode::F(number),
+                    _ => bail!("unknown key \"{text}\""),
+                },
+            },
+        };
+        Ok(Self::new(code, modifiers))
+    }
+
+    /// A printable character typed without Ctrl or Alt.
+    pub fn is_text(&self) -> bool {
+        matches!(self.code, KeyCode::Char(_))
+            && !self
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+    }
+
+    /// Keys the app handles itself in every context: focus cycling and the
+    /// emergency exit.
+    fn is_reserved(&self) -> bool {
+        matches!(self.code, KeyCode::Tab | KeyCode::BackTab)
+            || (self.code == KeyCode::Char('c') && self.modifiers == KeyModifiers::CONTROL)
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Ctrl+R reads better than Ctrl+r, so with a modifier held the
+        // case of a letter is spelled out as Shift instead.
+        let modified = !self.modifiers.is_empty();
+        let shifted = modified && matches!(self.code, KeyCode::Char(c) if c.is_ascii_uppercase());
+        for (modifier, label) in [
+            (KeyModifiers::CONTROL, "Ctrl+"),
+            (KeyModifiers::ALT, "Alt+"),
+            (KeyModifiers::SHIFT, "Shift+"),
+        ] {
+            if self.modifiers.contains(modifier) || (shifted && modifier == KeyModifiers::SHIFT) {
+                f.write_str(label)?;
+            }
+        }
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("Space"),
+            KeyCode::Char(c) if modified => write!(f, "{}", c.to_ascii_uppercase()),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::Enter => f.write_str("Enter"),
+            KeyCode::Esc => f.write_str("Esc"),
+            KeyCode::Tab => f.write_str("Tab"),
+            KeyCode::BackTab => f.write_str("Shift+Tab"),
+            KeyCode::Backspace => f.write_str("Bksp"),
+            KeyCode::Delete => f.write_str("Del"),
+            KeyCode::Insert => f.write_str("Ins"),
+            KeyCode::Home => f.write_str("Home"),
+            KeyCode::End => f.write_str("End"),
+            KeyCode::PageUp => f.write_str("PgUp"),
+            KeyCode::PageDown => f.write_str("PgDn"),
+            KeyCode::Up => f.write_str("↑"),
+            KeyCode::Down => f.write_str("↓"),
+            KeyCode::Left => f.write_str("←"),
+            KeyCode::Right => f.write_str("→"),
+            KeyCode::F(number) => write!(f, "F{number}"),
+            code => write!(f, "{code:?}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    actions: HashMap<(Context, KeyChord), Action>,
+    chords: HashMap<(Context, Action), Vec<KeyChord>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new(&KeyBindings::new()).expect("the default key bindings do not conflict")
+    }
+}
+
+impl Keymap {
+    /// The default bindings with `overrides` applied. Unknown contexts,
+    /// actions or keys are errors, as is any chord that would be ambiguous.
+    pub fn new(overrides: &KeyBindings) -> Result<Self> {
+        let mut chords = HashMap::new();
+        for &(context, action, defaults) in DEFAULTS {
+            let parsed = defaults
+                .iter()
+                .map(|chord| KeyChord::parse(chord))
+                .collect::<Result<Vec<_>>>()?;
+            chords.insert((context, action), parsed);
+        }
+
+        for (context_name, actions) in overrides {
+            let context = Context::ALL
+                .into_iter()
+                .find(|context| context.name() == context_name)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "unknown key context \"{context_name}\" \
+                         (expected global, video, results, queue, playlists, history or search)"
+                    )
+                })?;
+            for (action_name, bound) in actions {
+                let action = DEFAULTS
+                    .iter()
+                    .find(|(c, action, _)| *c == context && action.name() == action_name)
+                    .map(|&(_, action, _)| action)
+                    .ok_or_else(|| {
+                        anyhow!("keys.{context_name}: unknown action \"{action_name}\"")
+                    })?;
+                let parsed = bound
+                    .as_slice()
+                    .iter()
+                    .map(|chord| {
+                        KeyChord::parse(chord)
+                            .map_err(|error| anyhow!("keys.{context_name}.{action_name}: {error}"))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                chords.insert((context, action), parsed);
+            }
+        }
+
+        let mut keymap = Self {
+            actions: HashMap::new(),
+            chords,
+        };
+        // Check the rebound actions last so a conflict is reported against
+        // the user's binding rather than the default it collides with.
+        let mut entries: Vec<_> = DEFAULTS
+            .iter()
+            .map(|&(context, action, _)| (context, action))
+            .collect();
+        entries.sort_by_key(|&(context, action)| {
+            overrides
+                .get(context.name())
+                .is_some_and(|actions| actions.contains_key(action.name()))
+        });
+        for (context, action) in entries {
+            for &chord in &keymap.chords[&(context, action)] {
+                keymap.check(context, action, chord)?;
+                keymap.actions.insert((context, chord), action);
+            }
+        }
+        Ok(keymap)
+    }
+
+    fn check(&self, context: Context, action: Action, chord: KeyChord) -> Result<()> {
+        let name = format!("keys.{}.{}", context.name(), action.name());
+        if chord.is_reserved() {
+            bail!("{name}: {chord} is reserved for switching panels and quitting");
+        }
+        if chord.code == KeyCode::Esc && context != Context::Search {
+            bail!("{name}: Esc always goes back and cannot be bound here");
+        }
+        // Global bindings run before the results panel sees the key.
+        if matches!(context, Context::Global | Context::Results)
+            && chord.modifiers.is_empty()
+            && matches!(chord.code, KeyCode::Char(c) if c.is_ascii_digit())
+        {
+            bail!("{name}: {chord} is reserved for typing result numbers");
+        }
+        if context == Context::Search && (chord.is_text() || chord.code == KeyCode::Backspace) {
+            bail!("{name}: {chord} is needed for typing in the search bar");
+        }
+        if let Some(other) = self.actions.get(&(context, chord)) {
+            bail!("{name}: {chord} is already bound to {}", other.name());
+        }
+        // A global binding swallows the same chord in every panel, except
+        // that the search bar only listens for Settings.
+        let shadows =
+            |panel: Context, global: Action| panel != Context::Search || global == Action::Settings;
+        if context == Context::Global {
+            for panel in Context::ALL.into_iter().filter(|&c| c != Context::Global) {
+                if let Some(&other) = self.actions.get(&(panel, chord))
+                    && shadows(panel, action)
+                {
+                    bail!(
+                        "{name}: {chord} is already bound to {} {}",
+                        panel.name(),
+                        other.name()
+                    );
+                }
+            }
+        } else if let Some(&global) = self.actions.get(&(Context::Global, chord))
+            && shadows(context, global)
+        {
+            bail!(
+                "{name}: {chord} is already bound to global {}",
+                global.name()
+            );
+        }
+        Ok(())
+    }
+
+    /// The action `key` triggers in `context`, if any.
+    pub fn action(&self, context: Context, key: &KeyEvent) -> Option<Action> {
+        self.actions
+            .get(&(context, KeyChord::from_event(key)))
+            .copied()
+    }
+
+    pub fn chords(&self, context: Context, action: Action) -> &[KeyChord] {
+        self.chords
+            .get(&(context, action))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Every chord bound to `action`, separated by spaces, for the help
+    /// overlay.
+    pub fn label(&self, context: Context, action: Action) -> String {
+        self.chords(context, action)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// The first chord bound to each of `actions`, joined with slashes, for
+    /// footers. Empty when none of them is bound.
+    pub fn short_label(&self, context: Context, actions: &[Action]) -> String {
+        actions
+            .iter()
+            .filter_map(|&action| self.chords(context, action).first())
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    fn bindings(context: &str, action: &str, chords: &[&str]) -> KeyBindings {
+        let chords = Chords::Many(chords.iter().map(ToString::to_string).collect());
+        BTreeMap::from([(
+            context.to_string(),
+            BTreeMap::from([(action.to_string(), chords)]),
+        )])
+    }
+
+    #[test]
+    fn chords_parse_and_print_like_the_footer_shows_them() {
+        for (text, label) in [
+            ("x", "x"),
+            ("J", "J"),
+            ("shift+j", "J"),
+            ("space", "Space"),
+            ("Ctrl+R", "Ctrl+R"),
+            ("ctrl+shift+r", "Ctrl+Shift+R"),
+            ("shift+Left", "Shift+←"),
+            ("pagedown", "PgDn"),
+            ("F2", "F2"),
+            ("+", "+"),
+            ("alt++", "Alt++"),
+        ] {
+            assert_eq!(KeyChord::parse(text).unwrap().to_string(), label, "{text}");
+        }
+        assert!(KeyChord::parse("hyper+x").is_err());
+        assert!(KeyChord::parse("f13").is_err());
+        assert!(KeyChord::parse("spacebar").is_err());
+    }
+
+    #[test]
+    fn defaults_match_terminal_key_events() {
+        let keymap = Keymap::default();
+        let shifted = key(KeyCode::Char('J'), KeyModifiers::SHIFT);
+        assert_eq!(
+            keymap.action(Context::Queue, &shifted),
+            Some(Action::MoveDown)
+        );
+        let ctrl_r = key(KeyCode::Char('r'), KeyModifiers::CONTROL);
+        assert_eq!(
+            keymap.action(Context::Search, &ctrl_r),
+            Some(Action::FindSearch)
+        );
+        let left = key(KeyCode::Left, KeyModifiers::SHIFT);
+        assert_eq!(
+            keymap.action(Context::Global, &left),
+            Some(Action::SeekBackLarge)
+        );
+        assert_eq!(
+            keymap.short_label(Context::Results, &[Action::Up, Action::Down]),
+            "↑/↓"
+        );
+    }
+
+    #[test]
+    fn overrides_replace_only_the_named_action() {
+        let keymap = Keymap::new(&bindings("queue", "mark", &[])).unwrap();
+        assert_eq!(keymap.label(Context::Queue, Action::Mark), "");
+        assert_eq!(keymap.label(Context::Queue, Action::Remove), "Del Bksp");
+
+        let keymap = Keymap::new(&bindings("queue", "mark", &["ctrl+x", "y"])).unwrap();
+        let y = key(KeyCode::Char('y'), KeyModifiers::NONE);
+        assert_eq!(keymap.action(Context::Queue, &y), Some(Action::Mark));
+        let x = key(KeyCode::Char('x'), KeyModifiers::NONE);
+        assert_eq!(keymap.action(Context::Queue, &x), None);
+    }
+
+    #[test]
+    fn conflicting_bindings_are_rejected() {
+        for (context, action, chords, message) in [
+            (
+                "queue",
+                "mark",
+                &["c"][..],
+                "keys.queue.mark: c is already bound to clear",
+            ),
+            (
+                "results",
+                "add",
+                &["m"],
+                "keys.results.add: m is already bound to global mute",
+            ),
+            (
+                "global",
+                "mute",
+                &["a"],
+                "keys.global.mute: a is already bound to results add",
+            ),
+            (
+                "search",
+                "submit",
+                &["x"],
+                "keys.search.submit: x is needed for typing in the search bar",
+            ),
+            (
+                "search",
+                "submit",
+                &["f2"],
+                "keys.search.submit: F2 is already bound to global settings",
+            ),
+            (
+                "results",
+                "play",
+                &["5"],
+                "keys.results.play: 5 is reserved for typing result numbers",
+            ),
+            (
+                "global",
+                "mute",
+                &["d"],
+                "keys.global.mute: d is already bound to playlists remove",
+            ),
+            (
+                "global",
+                "shuffle",
+                &["r"],
+                "keys.global.shuffle: r is already bound to playlists rename",
+            ),
+            (
+                "playlists",
+                "rename",
+                &["R"],
+                "keys.playlists.rename: R is already bound to global resume",
+            ),
+            (
+                "global",
+                "mute",
+                &["5"],
+                "keys.global.mute: 5 is reserved for typing result numbers",
+            ),
+            (
+                "global",
+                "quit",
+                &["tab"],
+                "keys.global.quit: Tab is reserved for switching panels and quitting",
+            ),
+            (
+                "queue",
+                "clear",
+                &["nope"],
+                "keys.queue.clear: unknown key \"nope\"",
+            ),
+            (
+                "queue",
+                "filters",
+                &["f"],
+                "keys.queue: unknown action \"filters\"",
+            ),
+        ] {
+            let error = Keymap::new(&bindings(context, action, chords)).unwrap_err();
+            assert_eq!(error.to_string(), message);
+        }
+    }
+
+    #[test]
+    fn contexts_that_never_overlap_may_share_keys() {
+        // The video view and the queue are never active together.
+        Keymap::new(&bindings("video", "help", &["x"])).unwrap();
+        // The search bar only sees global Settings.
+        Keymap::new(&bindings("search", "older_search", &["left"])).unwrap();
+    }
+}
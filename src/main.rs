@@ -1,17 +1,35 @@
 mod cleanup;
+mod cli;
 mod config;
 mod deps;
+mod details;
 mod display;
 mod ipc;
+mod keymap;
 mod kitty_shm;
+#[cfg(target_os = "linux")]
+mod mpris;
+mod notify;
 mod player;
 mod player_manager;
+mod playlists;
 mod queue;
+mod radio;
+mod remote;
+mod resume;
 mod search;
+mod search_history;
+mod session;
+mod sponsorblock;
+mod subtitles;
+mod thumbnails;
 mod ui;
 mod video;
+mod watch_history;
+mod worker;
 
 use std::ffi::OsString;
+use std::process::ExitCode;
 
 use anyhow::{Result, bail};
 
@@ -21,17 +39,25 @@ use player::detect_player;
 use search::{PaginatedSearch, check_ytdlp};
 use ui::FocusedPanel;
 
-fn main() -> Result<()> {
-    match parse_cli_args(std::env::args_os().skip(1))? {
+fn main() -> Result<ExitCode> {
+    let action = match parse_cli_args(std::env::args_os().skip(1)) {
+        Ok(action) => action,
+        Err(error) => {
+            eprintln!("Error: {error:#}");
+            return Ok(ExitCode::from(cli::EXIT_USAGE));
+        }
+    };
+    match action {
         CliAction::Run => {}
         CliAction::Help => {
             print_help();
-            return Ok(());
+            return Ok(ExitCode::SUCCESS);
         }
         CliAction::Version => {
             println!("youtui {}", env!("CARGO_PKG_VERSION"));
-            return Ok(());
+            return Ok(ExitCode::SUCCESS);
         }
+        CliAction::Command(command) => return cli::run(command),
     }
 
     // Check and install dependencies if needed
@@ -66,19 +92,59 @@ fn main() -> Result<()> {
     if let Some(picker) = picker {
         app.video.set_picker(picker);
     }
+    match ui::theme::Theme::load(&app.config.theme) {
+        Ok(theme) => app.theme = theme,
+        Err(error) => app.status_message = Some(format!("Could not load theme: {error:#}")),
+    }
+    match session::QueueSnapshot::load() {
+        Ok(snapshot) => app.restore_prompt = snapshot,
+        Err(error) => app.status_message = Some(format!("Could not load saved queue: {error}")),
+    }
+    match playlists::PlaylistStore::load() {
+        Ok((store, unreadable)) => {
+            app.playlists = store;
+            if let Some(path) = unreadable.first() {
+                app.status_message = Some(format!(
+                    "Skipped {} unreadable playlist file(s), e.g. {}",
+                    unreadable.len(),
+                    path.display()
+                ));
+            }
+        }
+        Err(error) => app.status_message = Some(format!("Could not load playlists: {error}")),
+    }
+    match watch_history::WatchHistory::load() {
+        Ok(history) => app.watch_history = history,
+        Err(error) => {
+            app.status_message = Some(format!("Could not load watch history: {error}"));
+        }
+    }
+    match resume::ResumePositions::load() {
+        Ok(positions) => app.resume_positions = positions,
+        Err(error) => {
+            app.status_message = Some(format!("Could not load resume positions: {error}"));
+        }
+    }
+    match search_history::SearchHistory::load() {
+        Ok(history) => app.search_history = history,
+        Err(error) => {
+            app.status_message = Some(format!("Could not load search history: {error}"));
+        }
+    }
 
     // Run TUI loop
     let result = ui::run_app(terminal_guard.get_mut(), app, &mut search, &mut temp_dir);
 
     drop(terminal_guard);
-    result
+    result.map(|()| ExitCode::SUCCESS)
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 enum CliAction {
     Run,
     Help,
     Version,
+    Command(cli::Command),
 }
 
 fn parse_cli_args(args: impl IntoIterator<Item = OsString>) -> Result<CliAction> {
@@ -87,6 +153,9 @@ fn parse_cli_args(args: impl IntoIterator<Item = OsString>) -> Result<CliAction>
         [] => Ok(CliAction::Run),
         [arg] if arg == "-h" || arg == "--help" => Ok(CliAction::Help),
         [arg] if arg == "-V" || arg == "--version" => Ok(CliAction::Version),
+        [command, rest @ ..] if cli::is_command(command) => {
+            cli::parse_command(command, rest).map(CliAction::Command)
+        }
         [arg] => bail!(
             "unknown argument `{}`\n\nTry `youtui --help` for usage.",
             arg.to_string_lossy()
@@ -95,9 +164,26 @@ fn parse_cli_args(args: impl IntoIterator<Item = OsString>) -> Result<CliAction>
     }
 }
 
+const USAGE: &str = "\
+Usage: youtui [COMMAND]
+
+Commands:
+  search <QUERY>... [--json] [--limit N]  Print search results and exit
+  play <ID|URL>                           Play one video through mpv without the TUI
+  download <ID|URL>                       Download one video to the configured directory
+  remote <COMMAND> [ARG]...               Control the running TUI: toggle-pause, pause, play,
+                                          next, previous, seek <S> [absolute], volume <N> [relative],
+                                          enqueue <ID|URL>..., get-status, get-queue
+
+Options:
+  -h, --help       Print help
+  -V, --version    Print version
+
+Exit status: 0 success, 1 failure, 2 usage error, 3 no search results, 130 interrupted";
+
 fn print_help() {
     println!(
-        "youtui {version}\n{description}\n\nUsage: youtui\n\nOptions:\n  -h, --help       Print help\n  -V, --version    Print version",
+        "youtui {version}\n{description}\n\n{USAGE}",
         version = env!("CARGO_PKG_VERSION"),
         description = env!("CARGO_PKG_DESCRIPTION"),
     );
@@ -129,4 +215,18 @@ mod tests {
         assert!(parse_cli_args([OsString::from("--wat")]).is_err());
         assert!(parse_cli_args([OsString::from("--help"), OsString::from("extra")]).is_err());
     }
+
+    #[test]
+    fn cli_dispatches_subcommands_with_their_arguments() {
+        assert_eq!(
+            parse_cli_args(["search", "lofi", "--json"].map(OsString::from)).unwrap(),
+            CliAction::Command(cli::Command::Search {
+                query: "lofi".to_string(),
+                json: true,
+                limit: None,
+            })
+        );
+        assert!(parse_cli_args([OsString::from("search")]).is_err());
+        assert!(parse_cli_args(["watch", "dQw4w9WgXcQ"].map(OsString::from)).is_err());
+    }
 }
@@ -0,0 +1,602 @@
+//! MPRIS2 service on the D-Bus session bus, so media keys, desktop widgets
+//! and `playerctl` can see and control playback. Calls become
+//! [`RemoteCommand`]s that the UI loop carries out like socket requests.
+
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use zbus::blocking::Connection;
+use zbus::blocking::connection::Builder;
+use zbus::interface;
+use zbus::object_server::SignalEmitter;
+use zbus::zvariant::{ObjectPath, OwnedValue, Value};
+
+use crate::remote::RemoteCommand;
+use crate::search::video_url;
+
+const BUS_NAME: &str = "org.mpris.MediaPlayer2.youtui";
+const OBJECT_PATH: &str = "/org/mpris/MediaPlayer2";
+/// The track ID the spec reserves for "nothing loaded".
+const NO_TRACK: &str = "/org/mpris/MediaPlayer2/TrackList/NoTrack";
+/// A position further than this many seconds from where playback should have
+/// got is announced as a seek.
+const SEEK_THRESHOLD: f64 = 2.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Track {
+    pub video_id: String,
+    pub title: String,
+    pub channel: String,
+    /// Seconds, or 0 while mpv does not know yet.
+    pub length: f64,
+}
+
+/// What the desktop is shown, refreshed from the player every tick.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlayerState {
+    /// `None` while nothing is loaded.
+    pub track: Option<Track>,
+    /// Seconds into the track.
+    pub position: f64,
+    pub paused: bool,
+    /// 0 to 100, as mpv reports it.
+    pub volume: i32,
+    /// Queued tracks, including the playing one.
+    pub queue_length: usize,
+    /// Whether a played track can be brought back.
+    pub has_previous: bool,
+}
+
+impl PlayerState {
+    fn playback_status(&self) -> &'static str {
+        match self.track {
+            None => "Stopped",
+            Some(_) if self.paused => "Paused",
+            Some(_) => "Playing",
+        }
+    }
+
+    fn can_go_next(&self) -> bool {
+        self.queue_length > 1
+    }
+
+    /// Previous also restarts the playing track.
+    fn can_go_previous(&self) -> bool {
+        self.track.is_some() || self.has_previous
+    }
+
+    /// Play also starts an idle queue.
+    fn can_play(&self) -> bool {
+        self.track.is_some() || self.queue_length > 0
+    }
+
+    fn can_seek(&self) -> bool {
+        self.track.as_ref().is_some_and(|track| track.length > 0.0)
+    }
+}
+
+pub struct MprisService {
+    connection: Connection,
+    commands: Receiver<RemoteCommand>,
+    /// When the published position was taken, to tell seeks from playback.
+    updated_at: Instant,
+}
+
+impl MprisService {
+    /// Register on the user's session bus, or return `None` when there is
+    /// none to register on (a console or SSH login).
+    pub fn start() -> Result<Option<Self>> {
+        let has_bus = std::env::var_os("DBUS_SESSION_BUS_ADDRESS").is_some()
+            || dirs::runtime_dir().is_some_and(|dir| dir.join("bus").exists());
+        if !has_bus {
+            return Ok(None);
+        }
+        let builder = Builder::session().context("Failed to connect to the session bus")?;
+        Self::start_with(builder).map(Some)
+    }
+
+    fn start_with(builder: Builder<'_>) -> Result<Self> {
+        let (tx, rx) = mpsc::channel();
+        let player = Player {
+            state: PlayerState::default(),
+            commands: tx,
+        };
+        let connection = builder
+            .serve_at(OBJECT_PATH, MediaPlayer)?
+            .serve_at(OBJECT_PATH, player)?
+            .build()
+            .context("Failed to connect to the session bus")?;
+        // A second youtui takes an instance name, as the spec suggests.
+        if connection.request_name(BUS_NAME).is_err() {
+            let name = format!("{BUS_NAME}.instance{}", std::process::id());
+            connection
+                .request_name(name.as_str())
+                .with_context(|| format!("Failed to register {name}"))?;
+        }
+        Ok(Self {
+            connection,
+            commands: rx,
+            updated_at: Instant::now(),
+        })
+    }
+
+    /// The next command received since the last call, if any.
+    pub fn try_recv(&self) -> Option<RemoteCommand> {
+        self.commands.try_recv().ok()
+    }
+
+    /// Publish `state`, signalling the properties that changed and any jump
+    /// in position.
+    pub fn update(&mut self, state: PlayerState) -> Result<()> {
+        let player = self
+            .connection
+            .object_server()
+            .interface::<_, Player>(OBJECT_PATH)?;
+        let previous = std::mem::replace(&mut player.get_mut().state, state.clone());
+        let elapsed = self.updated_at.elapsed().as_secs_f64();
+        self.updated_at = Instant::now();
+        if previous == state {
+            return Ok(());
+        }
+
+        let same_track = state.track.is_some()
+            && previous.track.as_ref().map(|track| &track.video_id)
+                == state.track.as_ref().map(|track| &track.video_id);
+        let expected = previous.position + if previous.paused { 0.0 } else { elapsed };
+        let seeked = same_track && (state.position - expected).abs() > SEEK_THRESHOLD;
+
+        let emitter = player.signal_emitter();
+        let iface = player.get();
+        zbus::block_on(async {
+            if previous.track != state.track {
+                iface.metadata_changed(emitter).await?;
+            }
+            if previous.playback_status() != state.playback_status() {
+                iface.playback_status_changed(emitter).await?;
+            }
+            if previous.volume != state.volume {
+                iface.volume_changed(emitter).await?;
+            }
+            if previous.can_go_next() != state.can_go_next() {
+                iface.can_go_next_changed(emitter).await?;
+            }
+            if previous.can_go_previous() != state.can_go_previous() {
+                iface.can_go_previous_changed(emitter).await?;
+            }
+            if previous.can_play() != state.can_play() {
+                iface.can_play_changed(emitter).await?;
+            }
+            if previous.track.is_some() != state.track.is_some() {
+                iface.can_pause_changed(emitter).await?;
+            }
+            if previous.can_seek() != state.can_seek() {
+                iface.can_seek_changed(emitter).await?;
+            }
+            if seeked {
+                Player::seeked(emitter, micros(state.position)).await?;
+            }
+            zbus::Result::Ok(())
+        })
+        .context("Failed to publish playback state")
+    }
+}
+
+/// The `org.mpris.MediaPlayer2` root interface; youtui has no window to raise
+/// and is quit from the terminal.
+struct MediaPlayer;
+
+#[interface(name = "org.mpris.MediaPlayer2")]
+impl MediaPlayer {
+    fn raise(&self) {}
+
+    fn quit(&self) {}
+
+    #[zbus(property)]
+    fn can_quit(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn can_raise(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn has_track_list(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn identity(&self) -> &str {
+        "youtui"
+    }
+
+    #[zbus(property)]
+    fn supported_uri_schemes(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    #[zbus(property)]
+    fn supported_mime_types(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+struct Player {
+    state: PlayerState,
+    commands: Sender<RemoteCommand>,
+}
+
+impl Player {
+    fn send(&self, command: RemoteCommand) {
+        // The receiver only goes away while youtui is exiting.
+        let _ = self.commands.send(command);
+    }
+}
+
+#[interface(name = "org.mpris.MediaPlayer2.Player")]
+impl Player {
+    fn next(&self) {
+        self.send(RemoteCommand::Next);
+    }
+
+    fn previous(&self) {
+        self.send(RemoteCommand::Previous);
+    }
+
+    fn pause(&self) {
+        self.send(RemoteCommand::Pause);
+    }
+
+    fn play_pause(&self) {
+        self.send(RemoteCommand::TogglePause);
+    }
+
+    /// Stopping would drop the track from the queue; pausing keeps it.
+    fn stop(&self) {
+        self.send(RemoteCommand::Pause);
+    }
+
+    fn play(&self) {
+        self.send(RemoteCommand::Play);
+    }
+
+    fn seek(&self, offset: i64) {
+        self.send(RemoteCommand::Seek {
+            seconds: seconds(offset),
+            absolute: false,
+        });
+    }
+
+    /// Ignored for a track that is no longer playing or a position outside
+    /// it, as the spec requires.
+    fn set_position(&self, track_id: ObjectPath<'_>, position: i64) {
+        let Some(track) = &self.state.track else {
+            return;
+        };
+        let position = seconds(position);
+        if track_id.as_str() == track_path(&track.video_id)
+            && position >= 0.0
+            && (track.length <= 0.0 || position <= track.length)
+        {
+            self.send(RemoteCommand::Seek {
+                seconds: position,
+                absolute: true,
+            });
+        }
+    }
+
+    fn open_uri(&self, _uri: &str) -> zbus::fdo::Result<()> {
+        Err(zbus::fdo::Error::NotSupported(
+            "youtui does not open URIs".to_string(),
+        ))
+    }
+
+    #[zbus(signal)]
+    async fn seeked(emitter: &SignalEmitter<'_>, position: i64) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn playback_status(&self) -> &str {
+        self.state.playback_status()
+    }
+
+    #[zbus(property)]
+    fn rate(&self) -> f64 {
+        1.0
+    }
+
+    #[zbus(property)]
+    fn minimum_rate(&self) -> f64 {
+        1.0
+    }
+
+    #[zbus(property)]
+    fn maximum_rate(&self) -> f64 {
+        1.0
+    }
+
+    #[zbus(property)]
+    fn metadata(&self) -> HashMap<String, OwnedValue> {
+        metadata(self.state.track.as_ref())
+    }
+
+    #[zbus(property)]
+    fn volume(&self) -> f64 {
+        f64::from(self.state.volume) / 100.0
+    }
+
+    #[zbus(property)]
+    fn set_volume(&mut self, volume: f64) {
+        self.send(RemoteCommand::Volume {
+            level: (volume.clamp(0.0, 1.0) * 100.0).round() as i32,
+            relative: false,
+        });
+    }
+
+    /// Clients read the position when they need it and follow `Seeked`.
+    #[zbus(property(emits_changed_signal = "false"))]
+    fn position(&self) -> i64 {
+        micros(self.state.position)
+    }
+
+    #[zbus(property)]
+    fn can_go_next(&self) -> bool {
+        self.state.can_go_next()
+    }
+
+    #[zbus(property)]
+    fn can_go_previous(&self) -> bool {
+        self.state.can_go_previous()
+    }
+
+    #[zbus(property)]
+    fn can_play(&self) -> bool {
+        self.state.can_play()
+    }
+
+    #[zbus(property)]
+    fn can_pause(&self) -> bool {
+        self.state.track.is_some()
+    }
+
+    #[zbus(property)]
+    fn can_seek(&self) -> bool {
+        self.state.can_seek()
+    }
+
+    #[zbus(property(emits_changed_signal = "const"))]
+    fn can_control(&self) -> bool {
+        true
+    }
+}
+
+/// MPRIS track IDs are object paths, which cannot hold the `-` of video IDs.
+fn track_path(video_id: &str) -> String {
+    let hex: Vec<String> = video_id.bytes().map(|byte| format!("{byte:02x}")).collect();
+    format!("/org/youtui/track/v{}", hex.concat())
+}
+
+fn metadata(track: Option<&Track>) -> HashMap<String, OwnedValue> {
+    let Some(track) = track else {
+        return [(
+            "mpris:trackid",
+            Value::from(ObjectPath::from_static_str_unchecked(NO_TRACK)),
+        )]
+        .into_iter()
+        .filter_map(owned_entry)
+        .collect();
+    };
+    let mut entries = vec![
+        (
+            "mpris:trackid",
+            Value::from(ObjectPath::from_string_unchecked(track_path(
+                &track.video_id,
+            ))),
+        ),
+        ("xesam:title", Value::from(track.title.clone())),
+        ("xesam:url", Value::from(video_url(&track.video_id))),
+        (
+            "mpris:artUrl",
+            Value::from(format!(
+                "https://i.ytimg.com/vi/{}/hqdefault.jpg",
+                track.video_id
+            )),
+        ),
+    ];
+    if track.length > 0.0 {
+        entries.push(("mpris:length", Value::from(micros(track.length))));
+    }
+    if !track.channel.is_empty() {
+        entries.push(("xesam:artist", Value::from(vec![track.channel.clone()])));
+    }
+    entries.into_iter().filter_map(owned_entry).collect()
+}
+
+fn owned_entry((key, value): (&str, Value<'_>)) -> Option<(String, OwnedValue)> {
+    // Only values holding file descriptors fail to convert.
+    Some((key.to_string(), OwnedValue::try_from(value).ok()?))
+}
+
+fn micros(seconds: f64) -> i64 {
+    (seconds * 1_000_000.0) as i64
+}
+
+fn seconds(micros: i64) -> f64 {
+    micros as f64 / 1_000_000.0
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::io::{BufRead, BufReader};
+    use std::process::{Child, Command, Stdio};
+
+    use zbus::blocking::Proxy;
+    use zbus::blocking::proxy::Builder as ProxyBuilder;
+    use zbus::proxy::CacheProperties;
+
+    use super::*;
+
+    /// A private `dbus-daemon`, so tests never touch the user's session bus.
+    pub(crate) struct Bus {
+        daemon: Child,
+        address: String,
+    }
+
+    impl Bus {
+        pub(crate) fn start() -> Option<Self> {
+            let daemon = Command::new("dbus-daemon")
+                .args(["--session", "--nofork", "--print-address"])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()
+                .ok()?;
+            let mut bus = Self {
+                daemon,
+                address: String::new(),
+            };
+            let stdout = bus.daemon.stdout.take()?;
+            BufReader::new(stdout).read_line(&mut bus.address).ok()?;
+            bus.address = bus.address.trim().to_string();
+            (!bus.address.is_empty()).then_some(bus)
+        }
+
+        pub(crate) fn connect(&self) -> Builder<'_> {
+            Builder::address(self.address.as_str()).unwrap()
+        }
+    }
+
+    impl Drop for Bus {
+        fn drop(&mut self) {
+            let _ = self.daemon.kill();
+            let _ = self.daemon.wait();
+        }
+    }
+
+    fn proxy<'a>(client: &Connection, interface: &'a str) -> Proxy<'a> {
+        ProxyBuilder::new(client)
+            .destination(BUS_NAME)
+            .unwrap()
+            .path(OBJECT_PATH)
+            .unwrap()
+            .interface(interface)
+            .unwrap()
+            .cache_properties(CacheProperties::No)
+            .build()
+            .unwrap()
+    }
+
+    fn playing(position: f64) -> PlayerState {
+        PlayerState {
+            track: Some(Track {
+                video_id: "dQw4w9-WgXc".to_string(),
+                title: "Song".to_string(),
+                channel: "Artist".to_string(),
+                length: 212.0,
+            }),
+            position,
+            paused: false,
+            volume: 80,
+            queue_length: 2,
+            has_previous: false,
+        }
+    }
+
+    #[test]
+    fn state_and_controls_round_trip_over_a_private_session_bus() {
+        let Some(bus) = Bus::start() else {
+            eprintln!("dbus-daemon is not available; skipping");
+            return;
+        };
+        let mut service = MprisService::start_with(bus.connect()).unwrap();
+        let client = bus.connect().build().unwrap();
+        let root = proxy(&client, "org.mpris.MediaPlayer2");
+        let player = proxy(&client, "org.mpris.MediaPlayer2.Player");
+
+        assert_eq!(root.get_property::<String>("Identity").unwrap(), "youtui");
+        assert_eq!(
+            player.get_property::<String>("PlaybackStatus").unwrap(),
+            "Stopped"
+        );
+        assert!(!player.get_property::<bool>("CanPlay").unwrap());
+
+        service.update(playing(42.0)).unwrap();
+        assert_eq!(
+            player.get_property::<String>("PlaybackStatus").unwrap(),
+            "Playing"
+        );
+        assert_eq!(player.get_property::<i64>("Position").unwrap(), 42_000_000);
+        assert_eq!(player.get_property::<f64>("Volume").unwrap(), 0.8);
+        assert!(player.get_property::<bool>("CanGoNext").unwrap());
+        let metadata: HashMap<String, OwnedValue> = player.get_property("Metadata").unwrap();
+        assert_eq!(
+            String::try_from(metadata["xesam:title"].clone()).unwrap(),
+            "Song"
+        );
+        assert_eq!(
+            i64::try_from(metadata["mpris:length"].clone()).unwrap(),
+            212_000_000
+        );
+        assert_eq!(
+            String::try_from(metadata["xesam:url"].clone()).unwrap(),
+            "https://www.youtube.com/watch?v=dQw4w9-WgXc"
+        );
+
+        assert!(player.get_property::<bool>("CanGoPrevious").unwrap());
+
+        player.call_method("PlayPause", &()).unwrap();
+        player.call_method("Previous", &()).unwrap();
+        player.call_method("Seek", &(-10_000_000i64)).unwrap();
+        let current = ObjectPath::try_from(track_path("dQw4w9-WgXc")).unwrap();
+        player
+            .call_method("SetPosition", &(current, 90_000_000i64))
+            .unwrap();
+        let stale = ObjectPath::try_from(track_path("other")).unwrap();
+        player.call_method("SetPosition", &(stale, 1i64)).unwrap();
+        player.set_property("Volume", 0.5).unwrap();
+        assert!(
+            player
+                .call_method("OpenUri", &("https://youtu.be/x",))
+                .is_err()
+        );
+
+        let commands: Vec<RemoteCommand> = std::iter::from_fn(|| service.try_recv()).collect();
+        assert_eq!(
+            commands,
+            [
+                RemoteCommand::TogglePause,
+                RemoteCommand::Previous,
+                RemoteCommand::Seek {
+                    seconds: -10.0,
+                    absolute: false,
+                },
+                RemoteCommand::Seek {
+                    seconds: 90.0,
+                    absolute: true,
+                },
+                RemoteCommand::Volume {
+                    level: 50,
+                    relative: false,
+                },
+            ]
+        );
+
+        // A second instance still gets on the bus under its own name.
+        let second = MprisService::start_with(bus.connect()).unwrap();
+        drop(second);
+    }
+
+    #[test]
+    fn idle_metadata_names_the_reserved_no_track_id() {
+        let metadata = metadata(None);
+        assert_eq!(
+            ObjectPath::try_from(metadata["mpris:trackid"].clone())
+                .unwrap()
+                .as_str(),
+            NO_TRACK
+        );
+        assert_eq!(metadata.len(), 1);
+        assert!(ObjectPath::try_from(track_path("a-b_C")).is_ok());
+    }
+}
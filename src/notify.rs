@@ -0,0 +1,309 @@
+//! Desktop notifications for tracks that start while the terminal is in the
+//! background. They go to a configured command, or on Linux to the
+//! freedesktop notification service over D-Bus. A worker thread sends them
+//! so a slow notification daemon never holds up the UI loop.
+
+use std::process::Command;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, bail};
+
+use crate::search::{SearchResult, video_url};
+
+/// Tracks skipped through faster than this share one notification, for the
+/// track that was left playing.
+const MIN_INTERVAL: Duration = Duration::from_secs(5);
+
+pub struct Notifier {
+    tracks: Sender<SearchResult>,
+    errors: Receiver<String>,
+}
+
+impl Notifier {
+    /// Send through `command` (run with `sh -c`), or over D-Bus when it is
+    /// empty.
+    pub fn start(command: &str) -> Result<Self> {
+        let delivery = if command.trim().is_empty() {
+            Delivery::dbus()?
+        } else {
+            Delivery::Command(command.to_string())
+        };
+        Self::spawn(delivery, MIN_INTERVAL)
+    }
+
+    fn spawn(delivery: Delivery, interval: Duration) -> Result<Self> {
+        let (tracks_tx, tracks_rx) = mpsc::channel();
+        let (errors_tx, errors_rx) = mpsc::channel();
+        thread::Builder::new()
+            .name("youtui-notify".to_string())
+            .spawn(move || deliver_tracks(delivery, interval, &tracks_rx, &errors_tx))
+            .context("Failed to start the notification worker")?;
+        Ok(Self {
+            tracks: tracks_tx,
+            errors: errors_rx,
+        })
+    }
+
+    /// Announce that `track` started playing.
+    pub fn track_started(&self, track: &SearchResult) {
+        // The worker only stops once this sender is dropped.
+        let _ = self.tracks.send(track.clone());
+    }
+
+    /// The next delivery failure since the last call, if any.
+    pub fn try_recv_error(&self) -> Option<String> {
+        self.errors.try_recv().ok()
+    }
+}
+
+enum Delivery {
+    Command(String),
+    #[cfg(target_os = "linux")]
+    DBus {
+        connection: Option<zbus::blocking::Connection>,
+        /// The previous notification, replaced rather than stacked.
+        id: u32,
+    },
+}
+
+impl Delivery {
+    #[cfg(target_os = "linux")]
+    fn dbus() -> Result<Self> {
+        Ok(Self::DBus {
+            connection: None,
+            id: 0,
+        })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn dbus() -> Result<Self> {
+        bail!("set notify_command in config.toml to receive notifications on this platform")
+    }
+
+    fn send(&mut self, track: &SearchResult) -> Result<()> {
+        match self {
+            Self::Command(command) => {
+                let status = Command::new("sh")
+                    .arg("-c")
+                    .arg(command.as_str())
+                    .env("YOUTUI_TITLE", &track.title)
+                    .env("YOUTUI_CHANNEL", &track.channel)
+                    .env("YOUTUI_VIDEO_ID", &track.id)
+                    .env("YOUTUI_URL", video_url(&track.id))
+                    .status()
+                    .context("Failed to run notify_command")?;
+                if !status.success() {
+                    bail!("notify_command exited with {status}");
+                }
+                Ok(())
+            }
+            #[cfg(target_os = "linux")]
+            Self::DBus { connection, id } => {
+                let connection = match connection {
+                    Some(connection) => connection,
+                    None => connection.insert(
+                        zbus::blocking::Connection::session()
+                            .context("Failed to connect to the session bus")?,
+                    ),
+                };
+                *id = dbus_notify(connection, *id, track)?;
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn dbus_notify(
+    connection: &zbus::blocking::Connection,
+    replaces_id: u32,
+    track: &SearchResult,
+) -> Result<u32> {
+    use std::collections::HashMap;
+
+    use zbus::zvariant::Value;
+
+    let hints: HashMap<&str, Value<'_>> = HashMap::from([("urgency", Value::from(0u8))]);
+    let reply = connection
+        .call_method(
+            Some("org.freedesktop.Notifications"),
+            "/org/freedesktop/Notifications",
+            Some("org.freedesktop.Notifications"),
+            "Notify",
+            &(
+                "youtui",
+                replaces_id,
+                "",
+                track.title.as_str(),
+                track.channel.as_str(),
+                Vec::<&str>::new(),
+                hints,
+                -1i32,
+            ),
+        )
+        .context("The notification service rejected the notification")?;
+    Ok(reply.body().deserialize()?)
+}
+
+fn deliver_tracks(
+    mut delivery: Delivery,
+    interval: Duration,
+    tracks: &Receiver<SearchResult>,
+    errors: &Sender<String>,
+) {
+    let mut sent_at: Option<Instant> = None;
+    while let Ok(mut track) = tracks.recv() {
+        if let Some(sent_at) = sent_at {
+            let ready = sent_at + interval;
+            loop {
+                match tracks.recv_timeout(ready.saturating_duration_since(Instant::now())) {
+                    Ok(newer) => track = newer,
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            }
+        }
+        sent_at = Some(Instant::now());
+        if let Err(error) = delivery.send(&track) {
+            let _ = errors.send(format!("{error:#}"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::worker::wait_until;
+
+    fn track(id: &str, title: &str) -> SearchResult {
+        SearchResult {
+            title: title.to_string(),
+            ..SearchResult::test_track(id)
+        }
+    }
+
+    fn wait_for(path: &std::path::Path, lines: usize) -> String {
+        wait_until("the hook did not run", || {
+            fs::read_to_string(path).is_ok_and(|contents| contents.lines().count() >= lines)
+        });
+        fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn command_hook_gets_the_track_and_bursts_collapse_to_the_last_track() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let log = temp_dir.path().join("notifications.log");
+        let command = format!(
+            r#"printf '%s|%s|%s\n' "$YOUTUI_TITLE" "$YOUTUI_CHANNEL" "$YOUTUI_URL" >> '{}'"#,
+            log.display()
+        );
+        let interval = Duration::from_millis(300);
+        let notifier = Notifier::spawn(Delivery::Command(command), interval).unwrap();
+
+        notifier.track_started(&track("first", "First $HOME"));
+        assert_eq!(
+            wait_for(&log, 1),
+            "First $HOME|Channel|https://www.youtube.com/watch?v=first\n"
+        );
+        notifier.track_started(&track("second", "Second"));
+        notifier.track_started(&track("third", "Third"));
+        let contents = wait_for(&log, 2);
+        assert_eq!(
+            contents.lines().nth(1).unwrap().split('|').next(),
+            Some("Third")
+        );
+        // Nothing else was held back for the next interval.
+        thread::sleep(interval);
+        assert_eq!(fs::read_to_string(&log).unwrap().lines().count(), 2);
+        assert!(notifier.try_recv_error().is_none());
+    }
+
+    #[test]
+    fn failing_hooks_are_reported() {
+        let notifier =
+            Notifier::spawn(Delivery::Command("exit 3".to_string()), Duration::ZERO).unwrap();
+        notifier.track_started(&track("id", "Title"));
+
+        let mut error = None;
+        wait_until("no error reported", || {
+            error = notifier.try_recv_error();
+            error.is_some()
+        });
+        let error = error.unwrap();
+        assert!(error.contains("exited"), "{error}");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn dbus_notifications_replace_the_previous_one() {
+        use std::collections::HashMap;
+        use std::sync::{Arc, Mutex};
+
+        use zbus::zvariant::OwnedValue;
+
+        use crate::mpris::tests::Bus;
+
+        type Received = Arc<Mutex<Vec<(u32, String, String)>>>;
+
+        struct FakeNotifications {
+            received: Received,
+        }
+
+        #[zbus::interface(name = "org.freedesktop.Notifications")]
+        impl FakeNotifications {
+            #[allow(clippy::too_many_arguments)]
+            fn notify(
+                &self,
+                _app_name: &str,
+                replaces_id: u32,
+                _app_icon: &str,
+                summary: &str,
+                body: &str,
+                _actions: Vec<String>,
+                _hints: HashMap<String, OwnedValue>,
+                _expire_timeout: i32,
+            ) -> u32 {
+                let mut received = self.received.lock().unwrap();
+                received.push((replaces_id, summary.to_string(), body.to_string()));
+                7
+            }
+        }
+
+        let Some(bus) = Bus::start() else {
+            eprintln!("dbus-daemon is not available; skipping");
+            return;
+        };
+        let received = Received::default();
+        let _server = bus
+            .connect()
+            .name("org.freedesktop.Notifications")
+            .unwrap()
+            .serve_at(
+                "/org/freedesktop/Notifications",
+                FakeNotifications {
+                    received: Arc::clone(&received),
+                },
+            )
+            .unwrap()
+            .build()
+            .unwrap();
+        let mut delivery = Delivery::DBus {
+            connection: Some(bus.connect().build().unwrap()),
+            id: 0,
+        };
+
+        delivery.send(&track("a", "First")).unwrap();
+        delivery.send(&track("b", "Second")).unwrap();
+        assert_eq!(
+            *received.lock().unwrap(),
+            [
+                (0, "First".to_string(), "Channel".to_string()),
+                (7, "Second".to_string(), "Channel".to_string()),
+            ]
+        );
+    }
+}
@@ -93,7 +93,11 @@ pub fn play_video(
     }
 }
 
-fn download_permanently(config: &Config, video_title: &str, url: &str) -> Result<PlaybackResult> {
+pub(crate) fn download_permanently(
+    config: &Config,
+    video_title: &str,
+    url: &str,
+) -> Result<PlaybackResult> {
     ensure_download_capabilities(config)?;
     println!("{} {}", "Downloading:".blue(), video_title);
 
@@ -122,7 +126,10 @@ fn download_permanently(config: &Config, video_title: &str, url: &str) -> Result
 
     if !status.success() {
         eprintln!("{} Download failed.", "Error:".red());
-        return Ok(PlaybackResult::ReturnToMenu);
+        return Ok(PlaybackResult::Error(format!(
+            "yt-dlp download failed with exit code {}",
+            status.code().unwrap_or(-1)
+        )));
     }
 
     println!("{} {}", "Downloaded to:".green(), config.download_dir);
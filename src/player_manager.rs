@@ -6,11 +6,26 @@ use anyhow::{Context, Result, bail};
 use serde_json::Value;
 use tempfile::TempDir;
 
+use crate::details::Chapter;
+use crate::sponsorblock::{self, FetchSegments, Segment};
+use crate::worker::Job;
 use crate::{config::Config, ipc::IpcClient};
 
 const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
 const CONNECT_RETRY_DELAY: Duration = Duration::from_millis(25);
-const STATUS_PROPERTIES: [&str; 5] = ["time-pos", "duration", "pause", "volume", "eof-reached"];
+const STATUS_PROPERTIES: [&str; 6] = [
+    "time-pos",
+    "duration",
+    "pause",
+    "volume",
+    "eof-reached",
+    "chapter",
+];
+/// Going back within this many seconds of a chapter's start goes to the
+/// chapter before it instead of restarting it.
+const CHAPTER_RESTART_THRESHOLD: f64 = 3.0;
+/// A segment with less than this left to play is not worth a seek.
+const MIN_SKIP: f64 = 0.5;
 
 pub struct PlayerManager {
     process: Child,
@@ -21,6 +36,16 @@ pub struct PlayerManager {
     pub status: PlaybackStatus,
     pub current_video_id: Option<String>,
     current_playlist_entry_id: Option<i64>,
+    /// Absolute position to seek to once the loading file reports a duration.
+    pending_seek: Option<f64>,
+    /// SponsorBlock segments being read for the loaded track.
+    segment_fetch: Option<Job<Vec<Segment>>>,
+    fetch_segments: FetchSegments,
+    /// Indexes into `status.segments` already skipped. Each is skipped once,
+    /// so seeking back into one plays it.
+    skipped_segments: Vec<usize>,
+    /// The last segment skipped, until the UI takes it to report.
+    last_skip: Option<Segment>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -47,6 +72,12 @@ pub struct PlaybackStatus {
     pub volume: i32,
     pub title: String,
     pub eof_reached: bool,
+    /// The loaded file's chapters, read once mpv reports it has any.
+    pub chapters: Vec<Chapter>,
+    /// Index into `chapters` of the one playing; `None` before the first.
+    pub chapter: Option<usize>,
+    /// SponsorBlock segments of the loaded file, in playback order.
+    pub segments: Vec<Segment>,
 }
 
 impl Default for PlaybackStatus {
@@ -59,6 +90,9 @@ impl Default for PlaybackStatus {
             volume: 100,
             title: String::new(),
             eof_reached: false,
+            chapters: Vec::new(),
+            chapter: None,
+            segments: Vec::new(),
         }
     }
 }
@@ -106,6 +140,30 @@ impl PlaybackStatus {
         {
             self.eof_reached = eof;
         }
+
+        // mpv reports -1 before the first chapter and nothing at all for a
+        // file without chapters.
+        self.chapter = values
+            .get(5)
+            .and_then(Option::as_ref)
+            .and_then(Value::as_i64)
+            .and_then(|chapter| usize::try_from(chapter).ok());
+    }
+
+    /// The chapter the next/previous-chapter keys go to. Going back restarts
+    /// the playing chapter unless it has only just begun.
+    fn adjacent_chapter(&self, forward: bool) -> Option<usize> {
+        if forward {
+            let next = self.chapter.map_or(0, |index| index + 1);
+            return (next < self.chapters.len()).then_some(next);
+        }
+        let current = self.chapter?;
+        let started = self.chapters.get(current)?.start;
+        if self.time_pos - started > CHAPTER_RESTART_THRESHOLD || current == 0 {
+            Some(current)
+        } else {
+            Some(current - 1)
+        }
     }
 
     fn mark_transport_error(&mut self) {
@@ -144,6 +202,11 @@ impl PlayerManager {
             status: PlaybackStatus::default(),
             current_video_id: None,
             current_playlist_entry_id: None,
+            pending_seek: None,
+            segment_fetch: None,
+            fetch_segments: sponsorblock::fetch_segments,
+            skipped_segments: Vec::new(),
+            last_skip: None,
         })
     }
 
@@ -163,6 +226,11 @@ impl PlayerManager {
             },
             current_video_id: Some("video-id".to_string()),
             current_playlist_entry_id: Some(1),
+            pending_seek: None,
+            segment_fetch: None,
+            fetch_segments: sponsorblock::fetch_segments,
+            skipped_segments: Vec::new(),
+            last_skip: None,
         }
     }
 
@@ -211,8 +279,12 @@ impl PlayerManager {
         self.status.eof_reached = false;
         self.status.time_pos = 0.0;
         self.status.duration = 0.0;
+        self.status.chapters.clear();
+        self.status.chapter = None;
         self.current_video_id = Some(video_id.to_string());
         self.current_playlist_entry_id = playlist_entry_id(response.as_ref());
+        self.pending_seek = None;
+        self.fetch_segments_for(config, video_id);
 
         Ok(())
     }
@@ -241,12 +313,49 @@ impl PlayerManager {
         self.status.eof_reached = false;
         self.status.time_pos = 0.0;
         self.status.duration = 0.0;
+        self.status.chapters.clear();
+        self.status.chapter = None;
         self.current_video_id = Some(video_id.to_string());
         self.current_playlist_entry_id = playlist_entry_id(response.as_ref());
+        self.pending_seek = None;
+        self.fetch_segments_for(config, video_id);
 
         Ok(())
     }
 
+    /// Start reading the SponsorBlock segments of the track just loaded, if
+    /// skipping is on. Segments are best effort: a track whose segments
+    /// cannot be read simply plays through.
+    fn fetch_segments_for(&mut self, config: &Config, video_id: &str) {
+        self.status.segments.clear();
+        self.skipped_segments.clear();
+        self.last_skip = None;
+        self.segment_fetch = config
+            .sponsorblock_query()
+            .and_then(|query| sponsorblock::spawn_fetch(video_id, query, self.fetch_segments).ok());
+    }
+
+    /// The next segment to skip at the current position: one the position is
+    /// in, that has not been skipped before and has enough left to play.
+    fn segment_to_skip(&self) -> Option<usize> {
+        let position = self.status.time_pos;
+        self.status
+            .segments
+            .iter()
+            .enumerate()
+            .find(|(index, segment)| {
+                segment.contains(position)
+                    && segment.end - position >= MIN_SKIP
+                    && !self.skipped_segments.contains(index)
+            })
+            .map(|(index, _)| index)
+    }
+
+    /// The segment skipped since the last call, if any.
+    pub fn take_skipped(&mut self) -> Option<Segment> {
+        self.last_skip.take()
+    }
+
     fn apply_runtime_config(&mut self, config: &Config) -> Result<()> {
         let desired = PlaybackOptions::from(config);
         if self.options != desired {
@@ -274,6 +383,9 @@ impl PlayerManager {
         self.status = PlaybackStatus::default();
         self.current_video_id = None;
         self.current_playlist_entry_id = None;
+        self.pending_seek = None;
+        self.segment_fetch = None;
+        self.skipped_segments.clear();
 
         Ok(())
     }
@@ -309,6 +421,39 @@ impl PlayerManager {
         Ok(())
     }
 
+    /// Jump to the start of `chapter`.
+    pub fn seek_chapter(&mut self, chapter: usize) -> Result<()> {
+        let Some(start) = self
+            .status
+            .chapters
+            .get(chapter)
+            .map(|chapter| chapter.start)
+        else {
+            bail!("No chapter {}", chapter + 1);
+        };
+        self.seek_absolute(start)?;
+        // Until the next poll, so that pressing the key again keeps going.
+        self.status.time_pos = start;
+        self.status.chapter = Some(chapter);
+        Ok(())
+    }
+
+    /// Jump to the next or previous chapter. Returns false when there is
+    /// none to go to.
+    pub fn step_chapter(&mut self, forward: bool) -> Result<bool> {
+        match self.status.adjacent_chapter(forward) {
+            Some(chapter) => self.seek_chapter(chapter).map(|()| true),
+            None => Ok(false),
+        }
+    }
+
+    /// Seek to `seconds` as soon as the track that was just loaded is ready.
+    /// mpv rejects seeks while a URL is still being resolved, so the request
+    /// is held until `update_status` sees a duration.
+    pub fn seek_when_loaded(&mut self, seconds: f64) {
+        self.pending_seek = (seconds.is_finite() && seconds > 0.0).then_some(seconds);
+    }
+
     pub fn set_volume(&mut self, volume: i32) -> Result<()> {
         let volume = volume.clamp(0, 100);
         self.reconnect_for_active_track()?;
@@ -341,6 +486,22 @@ impl PlayerManager {
                     if reached_eof {
                         self.status.mark_eof();
                     }
+                    // The list does not change while a file plays, so it is
+                    // read once, when the chapter first reports a value. A
+                    // failed read is retried on the next poll.
+                    let has_chapters = values
+                        .get(5)
+                        .and_then(Option::as_ref)
+                        .is_some_and(Value::is_i64);
+                    if has_chapters && self.status.chapters.is_empty() {
+                        match ipc.get_property("chapter-list") {
+                            Ok(list) => self.status.chapters = parse_chapter_list(&list),
+                            // As below, a timed-out read may have left part
+                            // of a reply on the socket.
+                            Err(error) if IpcClient::is_read_timeout(&error) => self.ipc = None,
+                            Err(_) => {}
+                        }
+                    }
                 }
                 Err(error) if IpcClient::is_read_timeout(&error) => {
                     // Loading a YouTube URL can briefly block mpv's command
@@ -367,6 +528,28 @@ impl PlayerManager {
                 }
             }
         }
+
+        if self.status.duration > 0.0
+            && let Some(position) = self.pending_seek.take()
+        {
+            self.seek_absolute(position)?;
+            self.status.time_pos = position;
+        }
+
+        if let Some(result) = self.segment_fetch.as_ref().and_then(Job::poll) {
+            self.segment_fetch = None;
+            self.status.segments = result.unwrap_or_default();
+        }
+        // Like a pending seek, skipping waits until mpv can seek.
+        if self.status.duration > 0.0
+            && let Some(index) = self.segment_to_skip()
+        {
+            let segment = self.status.segments[index].clone();
+            self.seek_absolute(segment.end)?;
+            self.status.time_pos = segment.end;
+            self.skipped_segments.push(index);
+            self.last_skip = Some(segment);
+        }
         Ok(())
     }
 
@@ -408,6 +591,26 @@ fn loadfile_command(url: &str, paused: bool) -> Vec<&str> {
     }
 }
 
+/// mpv's `chapter-list`: `[{"title": "Intro", "time": 0.0}, ...]`.
+/// Untitled chapters are numbered.
+fn parse_chapter_list(list: &Value) -> Vec<Chapter> {
+    list.as_array()
+        .into_iter()
+        .flatten()
+        .enumerate()
+        .filter_map(|(index, chapter)| {
+            let start = chapter.get("time")?.as_f64()?.max(0.0);
+            let title = chapter
+                .get("title")
+                .and_then(Value::as_str)
+                .map(str::trim)
+                .filter(|title| !title.is_empty())
+                .map_or_else(|| format!("Chapter {}", index + 1), str::to_string);
+            Some(Chapter { start, title })
+        })
+        .collect()
+}
+
 fn playlist_entry_id(data: Option<&Value>) -> Option<i64> {
     data.and_then(|value| value.get("playlist_entry_id"))
         .and_then(Value::as_i64)
@@ -442,8 +645,13 @@ mod tests {
     use serde_json::json;
     use std::io::{BufRead, BufReader, Write};
     use std::os::unix::net::{UnixListener, UnixStream};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
     use std::thread;
 
+    use crate::sponsorblock::SegmentQuery;
+    use crate::worker::wait_until;
+
     fn command_args(config: &Config) -> Vec<String> {
         build_mpv_command(Path::new("/tmp/test.sock"), config)
             .get_args()
@@ -477,6 +685,242 @@ mod tests {
         assert_eq!(server.join().unwrap(), json!(["seek", "83.5", "absolute"]));
     }
 
+    #[test]
+    fn deferred_seek_waits_for_the_loaded_track_duration() {
+        let (client_stream, server_stream) = UnixStream::pair().unwrap();
+        let server = thread::spawn(move || {
+            let mut reader = BufReader::new(server_stream.try_clone().unwrap());
+            let mut writer = server_stream;
+            let mut commands = Vec::new();
+            for duration in [Value::Null, json!(300.0)] {
+                for property in STATUS_PROPERTIES {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    let request: Value = serde_json::from_str(&line).unwrap();
+                    let data = match property {
+                        "duration" => duration.clone(),
+                        _ => Value::Null,
+                    };
+                    writeln!(
+                        writer,
+                        "{}",
+                        json!({"request_id": request["request_id"], "error": "success", "data": data})
+                    )
+                    .unwrap();
+                }
+            }
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let request: Value = serde_json::from_str(&line).unwrap();
+            writeln!(
+                writer,
+                "{}",
+                json!({"request_id": request["request_id"], "error": "success"})
+            )
+            .unwrap();
+            commands.push(request["command"].clone());
+            commands
+        });
+        let mut manager = PlayerManager::from_test_stream(client_stream);
+        manager.seek_when_loaded(95.0);
+
+        manager.update_status().unwrap();
+        assert_eq!(manager.pending_seek, Some(95.0));
+
+        manager.update_status().unwrap();
+        assert_eq!(manager.pending_seek, None);
+        assert_eq!(manager.status.time_pos, 95.0);
+        assert_eq!(server.join().unwrap(), [json!(["seek", "95", "absolute"])]);
+    }
+
+    fn segment(start: f64, end: f64, category: &str) -> Segment {
+        Segment {
+            start,
+            end,
+            category: category.to_string(),
+        }
+    }
+
+    fn chapter(start: f64, title: &str) -> Chapter {
+        Chapter {
+            start,
+            title: title.to_string(),
+        }
+    }
+
+    #[test]
+    fn chapter_list_is_read_once_mpv_reports_a_chapter() {
+        let (client_stream, server_stream) = UnixStream::pair().unwrap();
+        let server = thread::spawn(move || {
+            let mut reader = BufReader::new(server_stream.try_clone().unwrap());
+            let mut writer = server_stream;
+            let mut reply = |data: Value| {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let request: Value = serde_json::from_str(&line).unwrap();
+                writeln!(
+                    writer,
+                    "{}",
+                    json!({"request_id": request["request_id"], "error": "success", "data": data})
+                )
+                .unwrap();
+                request["command"].clone()
+            };
+            let mut commands = Vec::new();
+            for chapter in [json!(1), json!(2)] {
+                for property in STATUS_PROPERTIES {
+                    let data = match property {
+                        "time-pos" => json!(130.0),
+                        "duration" => json!(600.0),
+                        "chapter" => chapter.clone(),
+                        _ => Value::Null,
+                    };
+                    reply(data);
+                }
+                if chapter == json!(1) {
+                    commands.push(reply(json!([
+                        {"title": "Intro", "time": 0.0},
+                        {"title": "", "time": 60.0},
+                        {"title": "Outro", "time": 500.0}
+                    ])));
+                }
+            }
+            commands
+        });
+        let mut manager = PlayerManager::from_test_stream(client_stream);
+
+        manager.update_status().unwrap();
+        assert_eq!(
+            manager.status.chapters,
+            [
+                chapter(0.0, "Intro"),
+                chapter(60.0, "Chapter 2"),
+                chapter(500.0, "Outro")
+            ]
+        );
+        assert_eq!(manager.status.chapter, Some(1));
+
+        // Only the chapter index is polled from then on.
+        manager.update_status().unwrap();
+        assert_eq!(manager.status.chapter, Some(2));
+        assert_eq!(
+            server.join().unwrap(),
+            [json!(["get_property", "chapter-list"])]
+        );
+    }
+
+    #[test]
+    fn sponsorblock_segments_are_read_in_the_background_and_skipped_once() {
+        fn canned(video_id: &str, query: &SegmentQuery, _: &AtomicBool) -> Result<Vec<Segment>> {
+            assert_eq!(video_id, "abcdefghijk");
+            assert_eq!(query.categories, ["sponsor", "outro"]);
+            Ok(vec![
+                segment(15.0, 45.0, "sponsor"),
+                segment(290.0, 300.0, "outro"),
+            ])
+        }
+        let (client_stream, server_stream) = UnixStream::pair().unwrap();
+        // The playback position mpv reports, in milliseconds.
+        let position = Arc::new(AtomicU64::new(5_000));
+        let server_position = Arc::clone(&position);
+        let server = thread::spawn(move || {
+            let mut reader = BufReader::new(server_stream.try_clone().unwrap());
+            let mut writer = server_stream;
+            let mut commands = Vec::new();
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 0 {
+                let request: Value = serde_json::from_str(&line).unwrap();
+                line.clear();
+                let command = &request["command"];
+                let data = match command[1].as_str() {
+                    _ if command[0] != "get_property" => {
+                        commands.push(command.clone());
+                        Value::Null
+                    }
+                    Some("time-pos") => {
+                        json!(server_position.load(Ordering::Relaxed) as f64 / 1000.0)
+                    }
+                    Some("duration") => json!(300.0),
+                    _ => Value::Null,
+                };
+                writeln!(
+                    writer,
+                    "{}",
+                    json!({"request_id": request["request_id"], "error": "success", "data": data})
+                )
+                .unwrap();
+            }
+            commands
+        });
+        let mut manager = PlayerManager::from_test_stream(client_stream);
+        manager.fetch_segments = canned;
+        let config = Config {
+            sponsorblock: true,
+            sponsorblock_categories: vec!["sponsor".to_string(), "outro".to_string()],
+            ..Config::default()
+        };
+        manager.fetch_segments_for(&config, "abcdefghijk");
+
+        wait_until("segments never arrived", || {
+            manager.update_status().unwrap();
+            !manager.status.segments.is_empty()
+        });
+        assert_eq!(manager.take_skipped(), None);
+
+        position.store(20_000, Ordering::Relaxed);
+        manager.update_status().unwrap();
+        assert_eq!(manager.status.time_pos, 45.0);
+        assert_eq!(manager.take_skipped(), Some(segment(15.0, 45.0, "sponsor")));
+        assert_eq!(manager.take_skipped(), None);
+
+        // Seeking back into a skipped segment plays it, and a segment that
+        // is nearly over is left to finish.
+        position.store(30_000, Ordering::Relaxed);
+        manager.update_status().unwrap();
+        assert_eq!(manager.status.time_pos, 30.0);
+        position.store(299_800, Ordering::Relaxed);
+        manager.update_status().unwrap();
+        assert_eq!(manager.take_skipped(), None);
+
+        // Turning SponsorBlock off stops reading segments for the next track.
+        manager.fetch_segments_for(&Config::default(), "abcdefghijk");
+        assert!(manager.segment_fetch.is_none());
+        assert!(manager.status.segments.is_empty());
+
+        drop(manager);
+        assert_eq!(server.join().unwrap(), [json!(["seek", "45", "absolute"])]);
+    }
+
+    #[test]
+    fn chapter_keys_step_through_chapters_and_restart_the_current_one() {
+        let mut status = PlaybackStatus {
+            chapters: vec![chapter(0.0, "A"), chapter(60.0, "B"), chapter(120.0, "C")],
+            chapter: Some(1),
+            time_pos: 90.0,
+            ..PlaybackStatus::default()
+        };
+        assert_eq!(status.adjacent_chapter(true), Some(2));
+        assert_eq!(status.adjacent_chapter(false), Some(1));
+
+        status.time_pos = 61.0;
+        assert_eq!(status.adjacent_chapter(false), Some(0));
+
+        status.chapter = Some(2);
+        assert_eq!(status.adjacent_chapter(true), None);
+        status.chapter = Some(0);
+        status.time_pos = 1.0;
+        assert_eq!(status.adjacent_chapter(false), Some(0));
+
+        // Before the first chapter only going forward makes sense.
+        status.chapter = None;
+        assert_eq!(status.adjacent_chapter(true), Some(0));
+        assert_eq!(status.adjacent_chapter(false), None);
+
+        // mpv reports -1 before the first chapter.
+        status.apply_property_values(&[None, None, None, None, None, Some(json!(-1))]);
+        assert_eq!(status.chapter, None);
+    }
+
     #[test]
     fn mpv_command_respects_video_and_format_configuration() {
         let video = Config {
@@ -686,6 +1130,11 @@ mod tests {
             },
             current_video_id: Some("video-id".to_string()),
             current_playlist_entry_id: Some(7),
+            pending_seek: None,
+            segment_fetch: None,
+            fetch_segments: sponsorblock::fetch_segments,
+            skipped_segments: Vec::new(),
+            last_skip: None,
         };
 
         manager.update_status().unwrap();
@@ -0,0 +1,636 @@
+//! Named playlists saved under `<config dir>/playlists/`, one JSON file each,
+//! plus import from and export to M3U/M3U8 and JSON files.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{config_dir, write_atomically};
+use crate::search::{SearchResult, is_video_id, parse_video_id};
+
+const MAX_NAME_LEN: usize = 100;
+
+/// A saved playlist. This is also the JSON import/export format.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Playlist {
+    pub name: String,
+    pub tracks: Vec<SearchResult>,
+}
+
+/// JSON imports may be a full playlist or a bare array of tracks, such as the
+/// output of `youtui search --json`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonImport {
+    Playlist(Playlist),
+    Tracks(Vec<SearchResult>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FileFormat {
+    M3u,
+    Json,
+}
+
+impl FileFormat {
+    fn from_path(path: &Path) -> Result<Self> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+        match extension.as_deref() {
+            Some("m3u" | "m3u8") => Ok(Self::M3u),
+            Some("json") => Ok(Self::Json),
+            _ => bail!(
+                "`{}` needs a .m3u, .m3u8 or .json extension",
+                path.display()
+            ),
+        }
+    }
+}
+
+/// Outcome of importing a playlist file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Imported {
+    /// Position of the new playlist in the store.
+    pub index: usize,
+    /// Entries that were not YouTube videos.
+    pub skipped: usize,
+}
+
+struct StoredPlaylist {
+    playlist: Playlist,
+    file_name: String,
+}
+
+/// The saved playlists, sorted by name. A store without a directory (the
+/// default) keeps its playlists in memory only.
+#[derive(Default)]
+pub struct PlaylistStore {
+    dir: Option<PathBuf>,
+    playlists: Vec<StoredPlaylist>,
+}
+
+impl PlaylistStore {
+    /// Load every saved playlist. Files that cannot be read or parsed are
+    /// left alone and returned so the caller can report them.
+    pub fn load() -> Result<(Self, Vec<PathBuf>)> {
+        Self::open(config_dir()?.join("playlists"))
+    }
+
+    fn open(dir: PathBuf) -> Result<(Self, Vec<PathBuf>)> {
+        let mut store = Self {
+            dir: None,
+            playlists: Vec::new(),
+        };
+        let mut unreadable = Vec::new();
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => Some(entries),
+            Err(error) if error.kind() == ErrorKind::NotFound => None,
+            Err(error) => return Err(error).context("Failed to read playlists directory"),
+        };
+
+        for entry in entries.into_iter().flatten() {
+            let path = entry.context("Failed to read playlists directory")?.path();
+            if path.extension().and_then(|extension| extension.to_str()) != Some("json") {
+                continue;
+            }
+            let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+                unreadable.push(path);
+                continue;
+            };
+            let parsed = fs::read_to_string(&path)
+                .ok()
+                .and_then(|contents| serde_json::from_str::<Playlist>(&contents).ok());
+            match parsed {
+                Some(mut playlist) => {
+                    if playlist.name.trim().is_empty() {
+                        playlist.name = file_name.trim_end_matches(".json").to_string();
+                    }
+                    store.playlists.push(StoredPlaylist {
+                        playlist,
+                        file_name: file_name.to_string(),
+                    });
+                }
+                None => unreadable.push(path),
+            }
+        }
+
+        store.sort();
+        store.dir = Some(dir);
+        Ok((store, unreadable))
+    }
+
+    pub fn len(&self) -> usize {
+        self.playlists.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.playlists.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Playlist> {
+        self.playlists.get(index).map(|stored| &stored.playlist)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Playlist> {
+        self.playlists.iter().map(|stored| &stored.playlist)
+    }
+
+    /// Save a new playlist and return its position.
+    pub fn create(&mut self, name: &str, tracks: Vec<SearchResult>) -> Result<usize> {
+        let name = self.check_name(name, None)?;
+        let stored = StoredPlaylist {
+            file_name: file_name_for(&name),
+            playlist: Playlist { name, tracks },
+        };
+        self.write(&stored)?;
+        self.playlists.push(stored);
+        Ok(self.sort_and_find(self.playlists.len() - 1))
+    }
+
+    pub fn append(&mut self, index: usize, tracks: &[SearchResult]) -> Result<()> {
+        let Some(stored) = self.playlists.get(index) else {
+            bail!("No such playlist");
+        };
+        let mut playlist = stored.playlist.clone();
+        playlist.tracks.extend_from_slice(tracks);
+        let updated = StoredPlaylist {
+            playlist,
+            file_name: stored.file_name.clone(),
+        };
+        self.write(&updated)?;
+        self.playlists[index] = updated;
+        Ok(())
+    }
+
+    /// Rename a playlist and return its new position.
+    pub fn rename(&mut self, index: usize, new_name: &str) -> Result<usize> {
+        if index >= self.playlists.len() {
+            bail!("No such playlist");
+        }
+        let name = self.check_name(new_name, Some(index))?;
+        let old_file_name = self.playlists[index].file_name.clone();
+        let renamed = StoredPlaylist {
+            file_name: file_name_for(&name),
+            playlist: Playlist {
+                name,
+                tracks: self.playlists[index].playlist.tracks.clone(),
+            },
+        };
+        // Write the new file before removing the old one so a failure never
+        // loses the playlist.
+        self.write(&renamed)?;
+        if renamed.file_name != old_file_name {
+            self.remove_file(&old_file_name)?;
+        }
+        self.playlists[index] = renamed;
+        Ok(self.sort_and_find(index))
+    }
+
+    pub fn delete(&mut self, index: usize) -> Result<Playlist> {
+        let Some(stored) = self.playlists.get(index) else {
+            bail!("No such playlist");
+        };
+        self.remove_file(&stored.file_name)?;
+        Ok(self.playlists.remove(index).playlist)
+    }
+
+    /// Import an M3U/M3U8 or JSON file as a new playlist. The name comes from
+    /// the file (or its file name) and is made unique if it is already taken.
+    pub fn import(&mut self, path: &Path) -> Result<Imported> {
+        let format = FileFormat::from_path(path)?;
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read `{}`", path.display()))?;
+        let (name, tracks, skipped) = match format {
+            FileFormat::M3u => parse_m3u(&contents),
+            FileFormat::Json => parse_json(&contents)?,
+        };
+        if tracks.is_empty() {
+            bail!("`{}` contains no YouTube videos", path.display());
+        }
+
+        let base_name = name
+            .filter(|name| !name.trim().is_empty())
+            .or_else(|| {
+                path.file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+            })
+            .unwrap_or_else(|| "Imported".to_string());
+        let name = self.unique_name(&base_name);
+        let index = self.create(&name, tracks)?;
+        Ok(Imported { index, skipped })
+    }
+
+    pub fn export(&self, index: usize, path: &Path) -> Result<()> {
+        let format = FileFormat::from_path(path)?;
+        let Some(playlist) = self.get(index) else {
+            bail!("No such playlist");
+        };
+        let contents = match format {
+            FileFormat::M3u => to_m3u(playlist),
+            FileFormat::Json => serde_json::to_string_pretty(playlist)? + "\n",
+        };
+        write_atomically(path, contents.as_bytes())
+            .with_context(|| format!("Failed to write `{}`", path.display()))
+    }
+
+    fn check_name(&self, name: &str, renaming: Option<usize>) -> Result<String> {
+        let name = name.trim();
+        if name.is_empty() {
+            bail!("Playlist name cannot be empty");
+        }
+        if name.chars().count() > MAX_NAME_LEN {
+            bail!("Playlist names are limited to {MAX_NAME_LEN} characters");
+        }
+        let file_name = file_name_for(name);
+        let taken = self.playlists.iter().enumerate().find(|(index, stored)| {
+            Some(*index) != renaming
+                && (stored.file_name == file_name
+                    || file_name_for(&stored.playlist.name) == file_name)
+        });
+        if let Some((_, existing)) = taken {
+            bail!(
+                "A playlist named \"{}\" already exists",
+                existing.playlist.name
+            );
+        }
+        Ok(name.to_string())
+    }
+
+    fn unique_name(&self, base_name: &str) -> String {
+        let base_name: String = base_name.trim().chars().take(MAX_NAME_LEN - 5).collect();
+        (1..)
+            .map(|attempt| match attempt {
+                1 => base_name.clone(),
+                n => format!("{base_name} ({n})"),
+            })
+            .find(|candidate| self.check_name(candidate, None).is_ok())
+            .expect("an unused playlist name")
+    }
+
+    fn write(&self, stored: &StoredPlaylist) -> Result<()> {
+        let Some(dir) = self.dir.as_ref() else {
+            return Ok(());
+        };
+        let json = serde_json::to_string_pretty(&stored.playlist)?;
+        write_atomically(&dir.join(&stored.file_name), json.as_bytes())
+            .context("Failed to save playlist")
+    }
+
+    fn remove_file(&self, file_name: &str) -> Result<()> {
+        let Some(dir) = self.dir.as_ref() else {
+            return Ok(());
+        };
+        match fs::remove_file(dir.join(file_name)) {
+            Err(error) if error.kind() != ErrorKind::NotFound => {
+                Err(error).context("Failed to remove playlist file")
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn sort(&mut self) {
+        self.playlists.sort_by_cached_key(|stored| {
+            (
+                stored.playlist.name.to_lowercase(),
+                stored.playlist.name.clone(),
+            )
+        });
+    }
+
+    /// Re-sort after an edit and return where the edited playlist ended up.
+    fn sort_and_find(&mut self, index: usize) -> usize {
+        let file_name = self.playlists[index].file_name.clone();
+        self.sort();
+        self.playlists
+            .iter()
+            .position(|stored| stored.file_name == file_name)
+            .unwrap_or(0)
+    }
+}
+
+/// Expand a leading `~/` in a path typed into the TUI.
+pub fn expand_home(input: &str) -> PathBuf {
+    match (input.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(input),
+    }
+}
+
+/// A portable file name: lowercase, with anything but letters, digits, `-`
+/// and `_` collapsed to `-`.
+fn file_name_for(name: &str) -> String {
+    let mut stem = String::new();
+    for character in name.trim().chars().flat_map(char::to_lowercase) {
+        if character.is_alphanumeric() || character == '_' {
+            stem.push(character);
+        } else if !stem.is_empty() && !stem.ends_with('-') {
+            stem.push('-');
+        }
+    }
+    let stem = stem.trim_end_matches('-');
+    if stem.is_empty() {
+        "playlist.json".to_string()
+    } else {
+        format!("{stem}.json")
+    }
+}
+
+fn to_m3u(playlist: &Playlist) -> String {
+    let mut out = String::from("#EXTM3U\n");
+    out.push_str(&format!("#PLAYLIST:{}\n", single_line(&playlist.name)));
+    for track in &playlist.tracks {
+        let seconds = duration_seconds(&track.duration).map_or(-1, |seconds| seconds as i64);
+        out.push_str(&format!(
+            "#EXTINF:{seconds},{} - {}\n{}\n",
+            single_line(&track.channel),
+            single_line(&track.title),
+            track.url()
+        ));
+    }
+    out
+}
+
+/// Parse a JSON playlist or track list into its name, the tracks with valid
+/// video IDs and the number of tracks dropped for not having one.
+fn parse_json(contents: &str) -> Result<(Option<String>, Vec<SearchResult>, usize)> {
+    let (name, tracks) =
+        match serde_json::from_str(contents).context("Not a youtui playlist file")? {
+            JsonImport::Playlist(playlist) => (Some(playlist.name), playlist.tracks),
+            JsonImport::Tracks(tracks) => (None, tracks),
+        };
+    let total = tracks.len();
+    let tracks: Vec<_> = tracks
+        .into_iter()
+        .filter(|track| is_video_id(&track.id))
+        .collect();
+    let skipped = total - tracks.len();
+    Ok((name, tracks, skipped))
+}
+
+/// Parse an M3U/M3U8 playlist into its `#PLAYLIST` name, the YouTube tracks
+/// it references and the number of entries that were not YouTube videos.
+fn parse_m3u(contents: &str) -> (Option<String>, Vec<SearchResult>, usize) {
+    let mut name = None;
+    let mut tracks = Vec::new();
+    let mut skipped = 0;
+    let mut info: Option<(Option<u64>, &str)> = None;
+
+    for line in contents.lines().map(str::trim) {
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(value) = line.strip_prefix("#PLAYLIST:") {
+            name = Some(value.trim().to_string());
+        } else if let Some(value) = line.strip_prefix("#EXTINF:") {
+            // `#EXTINF:<seconds> [attributes],<display title>`
+            let (head, display) = value.split_once(',').unwrap_or((value, ""));
+            let seconds = head
+                .split_whitespace()
+                .next()
+                .and_then(|seconds| seconds.parse::<i64>().ok())
+                .and_then(|seconds| u64::try_from(seconds).ok());
+            info = Some((seconds, display.trim()));
+        } else if line.starts_with('#') {
+            continue;
+        } else {
+            let entry_info = info.take();
+            let Some(id) = parse_video_id(line) else {
+                skipped += 1;
+                continue;
+            };
+            let (seconds, display) = entry_info.unwrap_or((None, ""));
+            let (channel, title) = match display.split_once(" - ") {
+                Some((channel, title)) if !channel.is_empty() && !title.is_empty() => {
+                    (channel, title)
+                }
+                _ => ("Unknown", display),
+            };
+            let title = if title.is_empty() { id.as_str() } else { title };
+            let duration = seconds.map_or_else(|| "N/A".to_string(), format_seconds);
+            tracks.extend(SearchResult::from_line_parts(
+                title, &duration, channel, "0 views", "", &id,
+            ));
+        }
+    }
+    (name, tracks, skipped)
+}
+
+fn single_line(value: &str) -> String {
+    value
+        .chars()
+        .map(|character| {
+            if character.is_control() {
+                ' '
+            } else {
+                character
+            }
+        })
+        .collect()
+}
+
+/// Seconds in a `h:mm:ss` or `m:ss` duration string.
+fn duration_seconds(duration: &str) -> Option<u64> {
+    duration.split(':').try_fold(0u64, |total, part| {
+        let part: u64 = part.trim().parse().ok()?;
+        total.checked_mul(60)?.checked_add(part)
+    })
+}
+
+pub(crate) fn format_seconds(seconds: u64) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds % 3600 / 60, seconds % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(id: &str, title: &str) -> SearchResult {
+        SearchResult::from_line_parts(title, "4:05", "Channel", "1K views", "", id).unwrap()
+    }
+
+    fn open(dir: &Path) -> PlaylistStore {
+        let (store, unreadable) = PlaylistStore::open(dir.to_path_buf()).unwrap();
+        assert!(unreadable.is_empty(), "{unreadable:?}");
+        store
+    }
+
+    #[test]
+    fn playlists_persist_through_create_append_rename_and_delete() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path().join("playlists");
+        let mut store = open(&dir);
+
+        assert_eq!(
+            store
+                .create("Road trip", vec![track("aaaaaaaaaaa", "A")])
+                .unwrap(),
+            0
+        );
+        assert_eq!(
+            store
+                .create("focus", vec![track("bbbbbbbbbbb", "B")])
+                .unwrap(),
+            0
+        );
+        store.append(1, &[track("ccccccccccc", "C")]).unwrap();
+        assert!(store.create("ROAD TRIP", Vec::new()).is_err());
+        assert!(store.create("  ", Vec::new()).is_err());
+
+        let reopened = open(&dir);
+        let names: Vec<&str> = reopened
+            .iter()
+            .map(|playlist| playlist.name.as_str())
+            .collect();
+        assert_eq!(names, ["focus", "Road trip"]);
+        assert_eq!(reopened.get(1).unwrap().tracks.len(), 2);
+
+        assert_eq!(store.rename(1, "Commute").unwrap(), 0);
+        assert!(store.rename(0, "Focus").is_err());
+        store.delete(1).unwrap();
+
+        let reopened = open(&dir);
+        assert_eq!(reopened.len(), 1);
+        assert_eq!(reopened.get(0).unwrap().name, "Commute");
+        assert_eq!(reopened.get(0).unwrap().tracks.len(), 2);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn unreadable_playlist_files_are_reported_and_left_in_place() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let bad = temp_dir.path().join("broken.json");
+        fs::write(&bad, "{not json").unwrap();
+        fs::write(temp_dir.path().join("notes.txt"), "ignored").unwrap();
+
+        let (store, unreadable) = PlaylistStore::open(temp_dir.path().to_path_buf()).unwrap();
+
+        assert!(store.is_empty());
+        assert_eq!(unreadable, std::slice::from_ref(&bad));
+        assert!(bad.exists());
+    }
+
+    #[test]
+    fn m3u_export_round_trips_titles_channels_and_durations() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut store = PlaylistStore::default();
+        let mut long = track("dQw4w9WgXcQ", "Title - with dash");
+        long.duration = "1:02:03".to_string();
+        store
+            .create("Mix", vec![long, track("aaaaaaaaaaa", "Short")])
+            .unwrap();
+        let path = temp_dir.path().join("mix.m3u8");
+
+        store.export(0, &path).unwrap();
+        let exported = fs::read_to_string(&path).unwrap();
+        assert!(
+            exported
+                .starts_with("#EXTM3U\n#PLAYLIST:Mix\n#EXTINF:3723,Channel - Title - with dash\n")
+        );
+        assert!(exported.contains("https://www.youtube.com/watch?v=dQw4w9WgXcQ\n"));
+
+        let imported = store.import(&path).unwrap();
+        assert_eq!(
+            imported,
+            Imported {
+                index: 1,
+                skipped: 0
+            }
+        );
+        let copy = store.get(1).unwrap();
+        assert_eq!(copy.name, "Mix (2)");
+        assert_eq!(copy.tracks[0].title, "Title - with dash");
+        assert_eq!(copy.tracks[0].channel, "Channel");
+        assert_eq!(copy.tracks[0].duration, "1:02:03");
+        assert_eq!(copy.tracks[1].duration, "4:05");
+    }
+
+    #[test]
+    fn m3u_import_accepts_bare_urls_and_skips_other_media() {
+        let (name, tracks, skipped) = parse_m3u(
+            "#EXTM3U\n\
+             https://youtu.be/dQw4w9WgXcQ\n\
+             #EXTINF:-1,Local file\n\
+             /music/song.mp3\n\
+             #EXTINF:90 tvg-id=\"x\",Just a title\n\
+             https://www.youtube.com/watch?v=aaaaaaaaaaa&list=PL1\n",
+        );
+
+        assert_eq!(name, None);
+        assert_eq!(skipped, 1);
+        assert_eq!(tracks.len(), 2);
+        assert_eq!(tracks[0].title, "dQw4w9WgXcQ");
+        assert_eq!(tracks[0].duration, "N/A");
+        assert_eq!(tracks[1].title, "Just a title");
+        assert_eq!(tracks[1].channel, "Unknown");
+        assert_eq!(tracks[1].duration, "1:30");
+    }
+
+    #[test]
+    fn json_export_and_import_keep_every_field() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut store = PlaylistStore::default();
+        let mut original = track("dQw4w9WgXcQ", "Song");
+        original.published = "2 days ago".to_string();
+        store.create("Shared", vec![original.clone()]).unwrap();
+        let path = temp_dir.path().join("shared.JSON");
+
+        store.export(0, &path).unwrap();
+        store.delete(0).unwrap();
+        store.import(&path).unwrap();
+
+        assert_eq!(
+            store.get(0),
+            Some(&Playlist {
+                name: "Shared".to_string(),
+                tracks: vec![original.clone()],
+            })
+        );
+
+        let search_output = temp_dir.path().join("results.json");
+        let mut forged = track("aaaaaaaaaaa", "Forged");
+        forged.id = "--exec=sh".to_string();
+        fs::write(
+            &search_output,
+            serde_json::to_string(&[&original, &forged]).unwrap(),
+        )
+        .unwrap();
+        let imported = store.import(&search_output).unwrap();
+        assert_eq!(imported.skipped, 1);
+        let copy = store.get(imported.index).unwrap();
+        assert_eq!(copy.name, "results");
+        assert_eq!(copy.tracks, [original]);
+    }
+
+    #[test]
+    fn import_and_export_require_a_known_extension() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut store = PlaylistStore::default();
+        store.create("A", vec![track("aaaaaaaaaaa", "A")]).unwrap();
+
+        assert!(store.export(0, &temp_dir.path().join("a.txt")).is_err());
+        assert!(store.import(&temp_dir.path().join("a")).is_err());
+
+        let empty = temp_dir.path().join("empty.m3u");
+        fs::write(&empty, "#EXTM3U\n/music/song.mp3\n").unwrap();
+        assert!(store.import(&empty).is_err());
+        assert_eq!(store.len(), 1);
+    }
+
+    #[test]
+    fn file_names_are_portable() {
+        assert_eq!(file_name_for("Road Trip!"), "road-trip.json");
+        assert_eq!(file_name_for("../../etc/passwd"), "etc-passwd.json");
+        assert_eq!(file_name_for("Café  Mix"), "café-mix.json");
+        assert_eq!(file_name_for("???"), "playlist.json");
+    }
+}
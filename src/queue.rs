@@ -1,26 +1,122 @@
 use crate::search::SearchResult;
+use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// What happens to the front track once it has played.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RepeatMode {
+    /// Played tracks leave the queue.
+    #[default]
+    Off,
+    /// Played tracks go back to the tail.
+    All,
+    /// A track that ends plays again; skipping still moves on.
+    One,
+}
+
+impl RepeatMode {
+    pub fn cycle(self) -> Self {
+        match self {
+            Self::Off => Self::All,
+            Self::All => Self::One,
+            Self::One => Self::Off,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::All => "all",
+            Self::One => "one",
+        }
+    }
+}
+
+/// SplitMix64: tiny, and the same seed always gives the same shuffle.
+#[derive(Debug, Clone)]
+struct ShuffleRng(u64);
+
+impl ShuffleRng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..bound`; `bound` must not be zero.
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+}
+
+/// Played tracks remembered for going back, most recent last.
+const PLAYED_CAPACITY: usize = 100;
+
+/// A track moved past by [`Queue::advance`].
+struct Played {
+    track: SearchResult,
+    /// Whether repeat-all also sent it to the tail.
+    requeued: bool,
+}
+
+/// A queued track and whether it is marked for a bulk remove or move.
+struct Entry {
+    track: SearchResult,
+    marked: bool,
+}
+
+impl Entry {
+    fn new(track: SearchResult) -> Self {
+        Self {
+            track,
+            marked: false,
+        }
+    }
+}
 
 pub struct Queue {
-    tracks: VecDeque<SearchResult>,
+    tracks: VecDeque<Entry>,
     pub selected_index: usize,
+    rng: ShuffleRng,
+    played: VecDeque<Played>,
 }
 
 impl Queue {
     pub fn new() -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos() as u64);
+        Self::with_seed(seed)
+    }
+
+    /// An empty queue whose shuffle order follows from `seed`.
+    pub fn with_seed(seed: u64) -> Self {
         Self {
             tracks: VecDeque::new(),
             selected_index: 0,
+            rng: ShuffleRng(seed),
+            played: VecDeque::new(),
         }
     }
 
     pub fn push_back(&mut self, track: SearchResult) {
-        self.tracks.push_back(track);
+        self.tracks.push_back(Entry::new(track));
+        self.normalize_selection();
+    }
+
+    /// Put `track` at `index`, or at the tail when `index` is past it.
+    pub fn insert(&mut self, index: usize, track: SearchResult) {
+        let index = index.min(self.tracks.len());
+        self.tracks.insert(index, Entry::new(track));
         self.normalize_selection();
     }
 
     pub fn pop_front(&mut self) -> Option<SearchResult> {
-        let track = self.tracks.pop_front();
+        let track = self.tracks.pop_front().map(|entry| entry.track);
         if track.is_some() && self.selected_index > 0 {
             self.selected_index = self.selected_index.saturating_sub(1);
         }
@@ -28,8 +124,59 @@ impl Queue {
         track
     }
 
+    /// Move past the playing front track: drop it, or with `RepeatMode::All`
+    /// send it to the tail. With `shuffle` a random upcoming track then takes
+    /// the front; a requeued track is never picked straight away. Replaying
+    /// a repeat-one track is up to the caller, so `One` moves on like `Off`.
+    pub fn advance(&mut self, repeat: RepeatMode, shuffle: bool) -> Option<SearchResult> {
+        let played = self.pop_front()?;
+        let requeued = repeat == RepeatMode::All;
+        if requeued {
+            self.tracks.push_back(Entry::new(played.clone()));
+        }
+        if self.played.len() == PLAYED_CAPACITY {
+            self.played.pop_front();
+        }
+        self.played.push_back(Played {
+            track: played.clone(),
+            requeued,
+        });
+        let candidates = self.tracks.len() - usize::from(requeued);
+        if shuffle && candidates > 1 {
+            let index = self.rng.below(candidates);
+            if let Some(entry) = self.tracks.remove(index) {
+                self.tracks.push_front(entry);
+            }
+        }
+        self.normalize_selection();
+        Some(played)
+    }
+
+    pub fn has_previous(&self) -> bool {
+        !self.played.is_empty()
+    }
+
+    /// Put the most recently played track back at the front, taking it off
+    /// the tail if repeat-all had requeued it there.
+    pub fn previous(&mut self) -> bool {
+        let Some(Played { track, requeued }) = self.played.pop_back() else {
+            return false;
+        };
+        if requeued
+            && let Some(index) = self
+                .tracks
+                .iter()
+                .rposition(|queued| queued.track.id == track.id)
+        {
+            self.tracks.remove(index);
+        }
+        self.tracks.push_front(Entry::new(track));
+        self.normalize_selection();
+        true
+    }
+
     pub fn remove(&mut self, index: usize) -> Option<SearchResult> {
-        let track = self.tracks.remove(index)?;
+        let track = self.tracks.remove(index)?.track;
         if index < self.selected_index {
             self.selected_index = self.selected_index.saturating_sub(1);
         }
@@ -51,24 +198,92 @@ impl Queue {
     }
 
     pub fn get(&self, index: usize) -> Option<&SearchResult> {
-        self.tracks.get(index)
+        self.tracks.get(index).map(|entry| &entry.track)
     }
 
     pub fn iter(&self) -> impl Iterator<Item = &SearchResult> {
-        self.tracks.iter()
+        self.tracks.iter().map(|entry| &entry.track)
     }
 
     pub fn move_to_front(&mut self, index: usize) {
         if index < self.tracks.len()
             && index > 0
-            && let Some(track) = self.tracks.remove(index)
+            && let Some(entry) = self.tracks.remove(index)
         {
-            self.tracks.push_front(track);
+            self.tracks.push_front(entry);
             self.selected_index = 0;
         }
         self.normalize_selection();
     }
 
+    /// Move the track at `from` to `to`, shifting the tracks in between.
+    pub fn move_track(&mut self, from: usize, to: usize) -> bool {
+        if from == to || to >= self.tracks.len() {
+            return false;
+        }
+        let Some(entry) = self.tracks.remove(from) else {
+            return false;
+        };
+        self.tracks.insert(to, entry);
+        true
+    }
+
+    pub fn is_marked(&self, index: usize) -> bool {
+        self.tracks.get(index).is_some_and(|entry| entry.marked)
+    }
+
+    pub fn marked_count(&self) -> usize {
+        self.tracks.iter().filter(|entry| entry.marked).count()
+    }
+
+    pub fn toggle_mark(&mut self, index: usize) {
+        if let Some(entry) = self.tracks.get_mut(index) {
+            entry.marked = !entry.marked;
+        }
+    }
+
+    pub fn clear_marks(&mut self) {
+        for entry in &mut self.tracks {
+            entry.marked = false;
+        }
+    }
+
+    /// Drop every marked track, returning how many went.
+    pub fn remove_marked(&mut self) -> usize {
+        let before = self.tracks.len();
+        self.tracks.retain(|entry| !entry.marked);
+        self.normalize_selection();
+        before - self.tracks.len()
+    }
+
+    /// Move every marked track one place up (or down), never above `first`.
+    /// Marked tracks that are already against the edge stay put and the rest
+    /// close up behind them. Returns where the track at `follow` ended up.
+    pub fn move_marked(&mut self, up: bool, first: usize, follow: usize) -> usize {
+        let len = self.tracks.len();
+        let mut follow = follow;
+        let mut step = |queue: &mut Self, index: usize, target: usize| {
+            if queue.tracks[index].marked && !queue.tracks[target].marked {
+                queue.tracks.swap(index, target);
+                if follow == index {
+                    follow = target;
+                } else if follow == target {
+                    follow = index;
+                }
+            }
+        };
+        if up {
+            for index in first.saturating_add(1)..len {
+                step(self, index, index - 1);
+            }
+        } else {
+            for index in (first..len.saturating_sub(1)).rev() {
+                step(self, index, index + 1);
+            }
+        }
+        follow
+    }
+
     fn normalize_selection(&mut self) {
         self.selected_index = self.selected_index.min(self.tracks.len().saturating_sub(1));
     }
@@ -316,6 +531,104 @@ mod tests {
         assert_eq!(ids, vec!["1", "2", "3"]);
     }
 
+    // --- advance ---
+
+    fn ids(queue: &Queue) -> Vec<&str> {
+        queue.iter().map(|t| t.id.as_str()).collect()
+    }
+
+    fn queue_of(ids: &[&str]) -> Queue {
+        seeded_queue_of(0, ids)
+    }
+
+    fn seeded_queue_of(seed: u64, ids: &[&str]) -> Queue {
+        let mut queue = Queue::with_seed(seed);
+        for id in ids {
+            queue.push_back(create_test_track(id, id));
+        }
+        queue
+    }
+
+    #[test]
+    fn advance_drops_or_requeues_the_played_track() {
+        let mut queue = queue_of(&["1", "2", "3"]);
+        assert_eq!(queue.advance(RepeatMode::Off, false).unwrap().id, "1");
+        assert_eq!(ids(&queue), ["2", "3"]);
+        queue.advance(RepeatMode::One, false);
+        assert_eq!(ids(&queue), ["3"]);
+
+        let mut queue = queue_of(&["1", "2", "3"]);
+        queue.advance(RepeatMode::All, false);
+        assert_eq!(ids(&queue), ["2", "3", "1"]);
+        queue.advance(RepeatMode::All, false);
+        queue.advance(RepeatMode::All, false);
+        assert_eq!(ids(&queue), ["1", "2", "3"]);
+
+        let mut single = queue_of(&["1"]);
+        single.advance(RepeatMode::All, true);
+        assert_eq!(ids(&single), ["1"]);
+        assert!(Queue::new().advance(RepeatMode::All, true).is_none());
+    }
+
+    #[test]
+    fn shuffle_is_reproducible_from_a_seed_and_avoids_the_requeued_track() {
+        let fronts = |seed: u64| {
+            let mut queue = seeded_queue_of(seed, &["1", "2", "3", "4", "5", "6"]);
+            (0..5)
+                .map(|_| {
+                    queue.advance(RepeatMode::Off, true);
+                    queue.get(0).unwrap().id.clone()
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(fronts(7), fronts(7));
+        assert_ne!(fronts(7), fronts(8));
+
+        for seed in 0..50 {
+            let mut queue = seeded_queue_of(seed, &["1", "2", "3"]);
+            queue.advance(RepeatMode::All, true);
+            assert_ne!(queue.get(0).unwrap().id, "1");
+            assert_eq!(queue.len(), 3);
+        }
+    }
+
+    #[test]
+    fn previous_brings_played_tracks_back_in_reverse_order() {
+        let mut queue = queue_of(&["1", "2", "3"]);
+        assert!(!queue.previous());
+        queue.advance(RepeatMode::Off, false);
+        queue.advance(RepeatMode::Off, false);
+        assert_eq!(ids(&queue), ["3"]);
+
+        assert!(queue.previous());
+        assert_eq!(ids(&queue), ["2", "3"]);
+        assert!(queue.previous());
+        assert_eq!(ids(&queue), ["1", "2", "3"]);
+        assert!(!queue.has_previous());
+
+        // Repeat-all's copy at the tail goes back to the front with it.
+        queue.advance(RepeatMode::All, false);
+        assert_eq!(ids(&queue), ["2", "3", "1"]);
+        queue.previous();
+        assert_eq!(ids(&queue), ["1", "2", "3"]);
+
+        for _ in 0..PLAYED_CAPACITY + 5 {
+            queue.advance(RepeatMode::All, false);
+        }
+        assert_eq!(queue.played.len(), PLAYED_CAPACITY);
+    }
+
+    #[test]
+    fn repeat_modes_cycle_and_parse_from_config() {
+        assert_eq!(RepeatMode::Off.cycle(), RepeatMode::All);
+        assert_eq!(RepeatMode::All.cycle(), RepeatMode::One);
+        assert_eq!(RepeatMode::One.cycle(), RepeatMode::Off);
+        assert_eq!(
+            serde_json::from_str::<RepeatMode>(r#""one""#).unwrap(),
+            RepeatMode::One
+        );
+    }
+
     #[test]
     fn test_clear_resets_selected_index() {
         let mut queue = Queue::new();
@@ -328,4 +641,57 @@ mod tests {
         assert_eq!(queue.selected_index, 0);
         assert!(queue.is_empty());
     }
+
+    // --- reordering and marks ---
+
+    #[test]
+    fn insert_places_tracks_at_the_index_or_the_tail() {
+        let mut queue = queue_of(&["1", "2"]);
+        queue.insert(1, create_test_track("next", "Next"));
+        queue.insert(10, create_test_track("last", "Last"));
+        assert_eq!(ids(&queue), ["1", "next", "2", "last"]);
+    }
+
+    #[test]
+    fn move_track_shifts_the_tracks_in_between() {
+        let mut queue = queue_of(&["1", "2", "3", "4"]);
+        assert!(queue.move_track(0, 2));
+        assert_eq!(ids(&queue), ["2", "3", "1", "4"]);
+        assert!(queue.move_track(3, 0));
+        assert_eq!(ids(&queue), ["4", "2", "3", "1"]);
+        assert!(!queue.move_track(1, 4));
+        assert!(!queue.move_track(2, 2));
+    }
+
+    #[test]
+    fn marked_tracks_move_together_and_stop_at_the_edges() {
+        let mut queue = queue_of(&["1", "2", "3", "4", "5"]);
+        queue.toggle_mark(2);
+        queue.toggle_mark(4);
+
+        assert_eq!(queue.move_marked(true, 1, 4), 3);
+        assert_eq!(ids(&queue), ["1", "3", "2", "5", "4"]);
+        assert_eq!(queue.move_marked(true, 1, 3), 2);
+        assert_eq!(ids(&queue), ["1", "3", "5", "2", "4"]);
+        // "3" is against `first`, so "5" closes up behind it.
+        assert_eq!(queue.move_marked(true, 1, 2), 2);
+        assert_eq!(ids(&queue), ["1", "3", "5", "2", "4"]);
+
+        assert_eq!(queue.move_marked(false, 1, 1), 2);
+        assert_eq!(ids(&queue), ["1", "2", "3", "5", "4"]);
+    }
+
+    #[test]
+    fn remove_marked_keeps_unmarked_tracks_in_order() {
+        let mut queue = queue_of(&["1", "2", "3", "4"]);
+        queue.toggle_mark(0);
+        queue.toggle_mark(2);
+        queue.toggle_mark(2);
+        queue.toggle_mark(3);
+        assert!(queue.is_marked(3));
+
+        assert_eq!(queue.remove_marked(), 2);
+        assert_eq!(ids(&queue), ["2", "3"]);
+        assert_eq!(queue.marked_count(), 0);
+    }
 }
@@ -0,0 +1,172 @@
+//! Radio mode: keeps a few tracks queued behind the playing one by reading
+//! the YouTube mix for the last queued track, or the last played one once
+//! the queue runs out. Mixes are read on a worker
+//! thread so yt-dlp never holds up the UI loop.
+
+use std::collections::{HashSet, VecDeque};
+use std::sync::atomic::AtomicBool;
+
+use anyhow::Result;
+
+use crate::search::{self, SearchResult};
+use crate::worker::Job;
+
+/// How many tracks radio keeps queued behind the playing one.
+pub const UPCOMING: usize = 3;
+
+/// Mix entries read per fetch. Mixes run to about fifty.
+const MIX_LENGTH: usize = 25;
+
+pub(crate) type FetchMix = fn(&str, usize, &AtomicBool) -> Result<Vec<SearchResult>>;
+
+pub struct Radio {
+    /// Videos played this session; radio never offers them again.
+    played: HashSet<String>,
+    /// The video played most recently, which seeds radio once the queue
+    /// runs out.
+    last_played: Option<String>,
+    /// Mix entries fetched but not queued yet.
+    candidates: VecDeque<SearchResult>,
+    /// The last video a mix was fetched for. Each seed is fetched once, so a
+    /// mix with nothing new left ends the radio instead of refetching it.
+    seed: Option<String>,
+    fetch: Option<Job<Vec<SearchResult>>>,
+    fetch_mix: FetchMix,
+}
+
+impl Default for Radio {
+    fn default() -> Self {
+        Self::with_fetcher(search::fetch_mix)
+    }
+}
+
+impl Radio {
+    pub(crate) fn with_fetcher(fetch_mix: FetchMix) -> Self {
+        Self {
+            played: HashSet::new(),
+            last_played: None,
+            candidates: VecDeque::new(),
+            seed: None,
+            fetch: None,
+            fetch_mix,
+        }
+    }
+
+    pub fn record_played(&mut self, video_id: &str) {
+        self.played.insert(video_id.to_string());
+        self.last_played = Some(video_id.to_string());
+    }
+
+    pub fn last_played(&self) -> Option<&str> {
+        self.last_played.as_deref()
+    }
+
+    /// Up to `wanted` tracks to queue, skipping anything played this session
+    /// or already `queued`. When the fetched tracks run out this starts
+    /// fetching the mix for `seed`, whose tracks a later call returns. A
+    /// failed fetch is reported once.
+    pub fn next_tracks(
+        &mut self,
+        seed: &str,
+        wanted: usize,
+        queued: &HashSet<&str>,
+    ) -> Result<Vec<SearchResult>> {
+        if let Some(result) = self.fetch.as_ref().and_then(Job::poll) {
+            self.fetch = None;
+            self.candidates.extend(result?);
+        }
+
+        let mut tracks = Vec::new();
+        while tracks.len() < wanted
+            && let Some(track) = self.candidates.pop_front()
+        {
+            let fresh = !self.played.contains(&track.id)
+                && !queued.contains(track.id.as_str())
+                && !tracks
+                    .iter()
+                    .any(|taken: &SearchResult| taken.id == track.id);
+            if fresh {
+                tracks.push(track);
+            }
+        }
+
+        if tracks.len() < wanted && self.fetch.is_none() && self.seed.as_deref() != Some(seed) {
+            self.seed = Some(seed.to_string());
+            self.fetch = Some(self.spawn_fetch(seed)?);
+        }
+        Ok(tracks)
+    }
+
+    fn spawn_fetch(&self, seed: &str) -> Result<Job<Vec<SearchResult>>> {
+        let fetch_mix = self.fetch_mix;
+        let seed = seed.to_string();
+        Job::spawn("radio", move |cancelled| {
+            fetch_mix(&seed, MIX_LENGTH, cancelled)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::bail;
+
+    use super::*;
+    use crate::worker::wait_until;
+
+    fn mix_of_seed(seed: &str, _: usize, _: &AtomicBool) -> Result<Vec<SearchResult>> {
+        Ok([seed, "a", "b", "c", "d"]
+            .map(SearchResult::test_track)
+            .to_vec())
+    }
+
+    /// Call `next_tracks` until the fetch it starts has been delivered.
+    fn next_after_fetch(
+        radio: &mut Radio,
+        seed: &str,
+        wanted: usize,
+        queued: &HashSet<&str>,
+    ) -> Result<Vec<SearchResult>> {
+        let mut outcome = Ok(Vec::new());
+        wait_until("the mix never arrived", || {
+            outcome = radio.next_tracks(seed, wanted, queued);
+            !matches!(&outcome, Ok(tracks) if tracks.is_empty()) || radio.fetch.is_none()
+        });
+        outcome
+    }
+
+    fn ids(tracks: &[SearchResult]) -> Vec<&str> {
+        tracks.iter().map(|track| track.id.as_str()).collect()
+    }
+
+    #[test]
+    fn mix_tracks_skip_played_and_queued_videos() {
+        let mut radio = Radio::with_fetcher(mix_of_seed);
+        radio.record_played("seed");
+        radio.record_played("b");
+        let queued = HashSet::from(["seed", "a"]);
+
+        let tracks = next_after_fetch(&mut radio, "seed", 2, &queued).unwrap();
+        assert_eq!(ids(&tracks), ["c", "d"]);
+
+        // The mix for this seed is used up and is not fetched again.
+        assert!(radio.next_tracks("seed", 1, &queued).unwrap().is_empty());
+        assert!(radio.fetch.is_none());
+
+        let tracks = next_after_fetch(&mut radio, "d", 1, &queued).unwrap();
+        assert_eq!(ids(&tracks), ["d"]);
+    }
+
+    #[test]
+    fn fetch_failures_are_reported_once() {
+        fn failing(_: &str, _: usize, _: &AtomicBool) -> Result<Vec<SearchResult>> {
+            bail!("yt-dlp is offline")
+        }
+        let mut radio = Radio::with_fetcher(failing);
+        let queued = HashSet::new();
+
+        let error = next_after_fetch(&mut radio, "seed", 3, &queued).unwrap_err();
+        assert_eq!(error.to_string(), "yt-dlp is offline");
+        assert!(radio.next_tracks("seed", 3, &queued).unwrap().is_empty());
+        assert!(radio.fetch.is_none());
+    }
+}
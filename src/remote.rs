@@ -0,0 +1,444 @@
+//! Remote control of a running youtui over a Unix socket, for window-manager
+//! keybinds and scripts. The protocol mirrors mpv's JSON IPC: each line is a
+//! request such as `{"command": ["seek", 30], "request_id": 1}` and is
+//! answered with `{"request_id": 1, "error": "success", "data": ...}`.
+
+use std::fs::{self, DirBuilder};
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde_json::{Value, json};
+
+use crate::search::{self, SearchResult};
+
+/// How long a client connection waits for the UI loop to carry out a command.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(2);
+/// How long `youtui remote` waits for an answer; enqueueing looks every video
+/// up with yt-dlp first.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Resolves a video ID to its metadata for `enqueue`.
+pub type Lookup = fn(&str) -> Result<SearchResult>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RemoteCommand {
+    TogglePause,
+    Pause,
+    /// Unpause, or start the queue when nothing is loaded.
+    Play,
+    Next,
+    /// Restart the track, or go back to the previous one near its start.
+    Previous,
+    Seek {
+        seconds: f64,
+        absolute: bool,
+    },
+    Volume {
+        level: i32,
+        relative: bool,
+    },
+    /// Tracks already looked up, in the order given.
+    Enqueue(Vec<SearchResult>),
+    GetStatus,
+    GetQueue,
+}
+
+/// A command waiting for the UI loop, which owns the app state.
+pub struct RemoteRequest {
+    command: RemoteCommand,
+    reply: Sender<Result<Value, String>>,
+}
+
+impl RemoteRequest {
+    /// Carry out the command and send the outcome back to the client.
+    pub fn handle(self, run: impl FnOnce(RemoteCommand) -> Result<Value>) {
+        let outcome = run(self.command).map_err(|error| format!("{error:#}"));
+        // The client may have given up waiting.
+        let _ = self.reply.send(outcome);
+    }
+}
+
+pub struct RemoteServer {
+    path: PathBuf,
+    requests: Receiver<RemoteRequest>,
+}
+
+impl RemoteServer {
+    /// Listen on the default socket path.
+    pub fn start() -> Result<Self> {
+        Self::bind(&socket_path(), search::fetch_video)
+    }
+
+    pub(crate) fn bind(path: &Path, lookup: Lookup) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            ensure_private_dir(parent)?;
+        }
+        if fs::symlink_metadata(path).is_ok() {
+            if UnixStream::connect(path).is_ok() {
+                bail!("another youtui is already listening on {}", path.display());
+            }
+            // Left behind by an instance that did not exit cleanly.
+            fs::remove_file(path)
+                .with_context(|| format!("Failed to remove stale socket {}", path.display()))?;
+        }
+        let listener = UnixListener::bind(path)
+            .with_context(|| format!("Failed to listen on {}", path.display()))?;
+
+        let (tx, rx) = mpsc::channel();
+        thread::Builder::new()
+            .name("youtui-remote".to_string())
+            .spawn(move || accept_clients(listener, tx, lookup))
+            .context("Failed to start the remote-control listener")?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            requests: rx,
+        })
+    }
+
+    /// The next command received since the last call, if any.
+    pub fn try_recv(&self) -> Option<RemoteRequest> {
+        self.requests.try_recv().ok()
+    }
+}
+
+impl Drop for RemoteServer {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// `$XDG_RUNTIME_DIR/youtui.sock`, or a per-user directory in the temporary
+/// directory where there is no runtime directory (macOS).
+pub fn socket_path() -> PathBuf {
+    match dirs::runtime_dir() {
+        Some(dir) => dir.join("youtui.sock"),
+        None => std::env::temp_dir()
+            .join(format!("youtui-{}", current_uid()))
+            .join("youtui.sock"),
+    }
+}
+
+/// Create `dir` owner-only if it is missing, and refuse it unless only the
+/// current user can reach it. The socket inherits the process umask, so the
+/// directory is what keeps other users out, and in a shared temporary
+/// directory someone else could have created it first.
+fn ensure_private_dir(dir: &Path) -> Result<()> {
+    DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)
+        .with_context(|| format!("Failed to create directory {}", dir.display()))?;
+    let metadata = fs::symlink_metadata(dir)
+        .with_context(|| format!("Failed to read directory {}", dir.display()))?;
+    if !metadata.is_dir() || metadata.uid() != current_uid() || metadata.mode() & 0o077 != 0 {
+        bail!(
+            "{} must be a directory that only you can access",
+            dir.display()
+        );
+    }
+    Ok(())
+}
+
+fn current_uid() -> u32 {
+    // SAFETY: getuid takes no arguments, cannot fail and touches no memory.
+    unsafe { libc::getuid() }
+}
+
+fn accept_clients(listener: UnixListener, requests: Sender<RemoteRequest>, lookup: Lookup) {
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let requests = requests.clone();
+        // A client that never sends a newline must not hold up the others.
+        let _ = thread::Builder::new()
+            .name("youtui-remote-client".to_string())
+            .spawn(move || serve_client(stream, &requests, lookup));
+    }
+}
+
+fn serve_client(
+    stream: UnixStream,
+    requests: &Sender<RemoteRequest>,
+    lookup: Lookup,
+) -> io::Result<()> {
+    let reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (request_id, outcome) = match serde_json::from_str::<Value>(&line) {
+            Ok(message) => (
+                message.get("request_id").cloned(),
+                run_request(&message, requests, lookup),
+            ),
+            Err(error) => (None, Err(format!("invalid JSON: {error}"))),
+        };
+        let mut reply = match outcome {
+            Ok(data) => json!({ "error": "success", "data": data }),
+            Err(error) => json!({ "error": error }),
+        };
+        if let Some(request_id) = request_id {
+            reply["request_id"] = request_id;
+        }
+        writeln!(writer, "{reply}")?;
+    }
+    Ok(())
+}
+
+fn run_request(
+    message: &Value,
+    requests: &Sender<RemoteRequest>,
+    lookup: Lookup,
+) -> Result<Value, String> {
+    let command = parse_command(message, lookup).map_err(|error| format!("{error:#}"))?;
+    let (reply_tx, reply_rx) = mpsc::channel();
+    requests
+        .send(RemoteRequest {
+            command,
+            reply: reply_tx,
+        })
+        .map_err(|_| "youtui is shutting down".to_string())?;
+    reply_rx
+        .recv_timeout(REPLY_TIMEOUT)
+        .map_err(|_| "youtui did not answer in time".to_string())?
+}
+
+fn parse_command(message: &Value, lookup: Lookup) -> Result<RemoteCommand> {
+    let args = message
+        .get("command")
+        .and_then(Value::as_array)
+        .context("the request needs a \"command\" array")?;
+    let (name, args) = args.split_first().context("the command is empty")?;
+    let name = name.as_str().context("the command name must be a string")?;
+
+    let command = match name {
+        "toggle-pause" => RemoteCommand::TogglePause,
+        "pause" => RemoteCommand::Pause,
+        "play" => RemoteCommand::Play,
+        "next" => RemoteCommand::Next,
+        "previous" => RemoteCommand::Previous,
+        "seek" => {
+            let seconds = number_arg(args, "seek")?;
+            RemoteCommand::Seek {
+                seconds,
+                absolute: absolute_mode(args, false)?,
+            }
+        }
+        "volume" => {
+            let level = number_arg(args, "volume")?;
+            RemoteCommand::Volume {
+                level: level.round().clamp(-100.0, 100.0) as i32,
+                relative: !absolute_mode(args, true)?,
+            }
+        }
+        "enqueue" => {
+            if args.is_empty() {
+                bail!("`enqueue` needs at least one video ID or URL");
+            }
+            let tracks = args
+                .iter()
+                .map(|arg| {
+                    let target = arg.as_str().context("video IDs and URLs must be strings")?;
+                    let video_id = search::parse_video_id(target)
+                        .with_context(|| format!("`{target}` is not a YouTube video ID or URL"))?;
+                    lookup(&video_id)
+                })
+                .collect::<Result<Vec<_>>>()?;
+            RemoteCommand::Enqueue(tracks)
+        }
+        "get-status" => RemoteCommand::GetStatus,
+        "get-queue" => RemoteCommand::GetQueue,
+        other => bail!("unknown command `{other}`"),
+    };
+    Ok(command)
+}
+
+/// The first argument as a finite number, given either as a JSON number or
+/// as a string (which is how `youtui remote` passes it).
+fn number_arg(args: &[Value], command: &str) -> Result<f64> {
+    let value = match args.first() {
+        Some(Value::Number(number)) => number.as_f64(),
+        Some(Value::String(text)) => text.trim().parse::<f64>().ok(),
+        _ => None,
+    };
+    value
+        .filter(|value| value.is_finite())
+        .with_context(|| format!("`{command}` needs a number"))
+}
+
+/// Whether the optional second argument, `"absolute"` or `"relative"`,
+/// selects the absolute form.
+fn absolute_mode(args: &[Value], default: bool) -> Result<bool> {
+    match args.get(1).map(Value::as_str) {
+        None => Ok(default),
+        Some(Some("absolute")) => Ok(true),
+        Some(Some("relative")) => Ok(false),
+        Some(_) => bail!("the mode must be \"absolute\" or \"relative\""),
+    }
+}
+
+/// Send one command to the instance listening on `path` and return its data.
+pub fn send(path: &Path, command: &[String]) -> Result<Value> {
+    let stream = UnixStream::connect(path)
+        .with_context(|| format!("youtui is not running (no socket at {})", path.display()))?;
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+    let mut writer = stream.try_clone()?;
+    writeln!(writer, "{}", json!({ "command": command, "request_id": 1 }))?;
+
+    let mut line = String::new();
+    BufReader::new(stream)
+        .read_line(&mut line)
+        .context("Failed to read the reply")?;
+    let reply: Value = serde_json::from_str(&line).context("youtui sent an invalid reply")?;
+    match reply.get("error").and_then(Value::as_str) {
+        Some("success") => Ok(reply.get("data").cloned().unwrap_or(Value::Null)),
+        Some(error) => Err(anyhow!("{error}")),
+        None => bail!("youtui sent an invalid reply"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::fs::PermissionsExt;
+
+    use super::*;
+
+    fn fake_lookup(video_id: &str) -> Result<SearchResult> {
+        if video_id == "missingVid1" {
+            bail!("Video unavailable");
+        }
+        SearchResult::from_line_parts("Fake", "1:00", "Channel", "0 views", "", video_id)
+            .context("invalid fixture")
+    }
+
+    fn parse(command: Value) -> Result<RemoteCommand> {
+        parse_command(&json!({ "command": command }), fake_lookup)
+    }
+
+    #[test]
+    fn commands_parse_with_numbers_given_as_json_or_strings() {
+        assert_eq!(
+            parse(json!(["seek", -10])).unwrap(),
+            RemoteCommand::Seek {
+                seconds: -10.0,
+                absolute: false
+            }
+        );
+        assert_eq!(
+            parse(json!(["seek", "90", "absolute"])).unwrap(),
+            RemoteCommand::Seek {
+                seconds: 90.0,
+                absolute: true
+            }
+        );
+        assert_eq!(
+            parse(json!(["volume", 40])).unwrap(),
+            RemoteCommand::Volume {
+                level: 40,
+                relative: false
+            }
+        );
+        assert_eq!(
+            parse(json!(["volume", "-5", "relative"])).unwrap(),
+            RemoteCommand::Volume {
+                level: -5,
+                relative: true
+            }
+        );
+        assert_eq!(parse(json!(["next"])).unwrap(), RemoteCommand::Next);
+        assert_eq!(parse(json!(["previous"])).unwrap(), RemoteCommand::Previous);
+
+        assert!(parse(json!(["seek"])).is_err());
+        assert!(parse(json!(["seek", "soon"])).is_err());
+        assert!(parse(json!(["seek", 5, "sideways"])).is_err());
+        assert!(parse(json!(["rewind"])).is_err());
+        assert!(parse(json!([])).is_err());
+        assert!(parse_command(&json!({ "cmd": ["next"] }), fake_lookup).is_err());
+    }
+
+    #[test]
+    fn enqueue_looks_up_ids_and_urls() {
+        let RemoteCommand::Enqueue(tracks) = parse(json!([
+            "enqueue",
+            "dQw4w9WgXcQ",
+            "https://youtu.be/jNQXAC9IVRw"
+        ]))
+        .unwrap() else {
+            panic!("not an enqueue command");
+        };
+        let ids: Vec<&str> = tracks.iter().map(|track| track.id.as_str()).collect();
+        assert_eq!(ids, ["dQw4w9WgXcQ", "jNQXAC9IVRw"]);
+
+        assert!(parse(json!(["enqueue"])).is_err());
+        assert!(parse(json!(["enqueue", "not a video"])).is_err());
+        let error = parse(json!(["enqueue", "missingVid1"])).unwrap_err();
+        assert!(format!("{error:#}").contains("Video unavailable"));
+    }
+
+    #[test]
+    fn a_local_client_drives_the_server_and_sees_errors() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("youtui").join("youtui.sock");
+        let server = RemoteServer::bind(&path, fake_lookup).unwrap();
+        assert!(RemoteServer::bind(&path, fake_lookup).is_err());
+        let mode = fs::metadata(path.parent().unwrap())
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o700, "socket directory mode {mode:o}");
+        // A directory others can open is refused rather than used.
+        let shared = temp_dir.path().join("shared");
+        fs::create_dir(&shared).unwrap();
+        fs::set_permissions(&shared, fs::Permissions::from_mode(0o755)).unwrap();
+        assert!(RemoteServer::bind(&shared.join("youtui.sock"), fake_lookup).is_err());
+
+        let ui = thread::spawn(move || {
+            let mut handled = 0;
+            while handled < 2 {
+                match server.try_recv() {
+                    Some(request) => {
+                        request.handle(|command| match command {
+                            RemoteCommand::GetStatus => Ok(json!({ "paused": true })),
+                            _ => bail!("Nothing is playing"),
+                        });
+                        handled += 1;
+                    }
+                    None => thread::sleep(Duration::from_millis(5)),
+                }
+            }
+            server
+        });
+
+        assert_eq!(
+            send(&path, &["get-status".to_string()]).unwrap(),
+            json!({ "paused": true })
+        );
+        let error = send(&path, &["next".to_string()]).unwrap_err();
+        assert_eq!(error.to_string(), "Nothing is playing");
+
+        // Malformed requests are answered without reaching the UI loop.
+        let mut stream = UnixStream::connect(&path).unwrap();
+        writeln!(stream, "{{\"command\": [\"jump\"], \"request_id\": 7}}").unwrap();
+        let mut reply = String::new();
+        BufReader::new(&stream).read_line(&mut reply).unwrap();
+        let reply: Value = serde_json::from_str(&reply).unwrap();
+        assert_eq!(reply["request_id"], 7);
+        assert_eq!(reply["error"], "unknown command `jump`");
+
+        drop(ui.join().unwrap());
+        assert!(!path.exists());
+        // A socket left behind by a crashed instance is replaced.
+        drop(UnixListener::bind(&path).unwrap());
+        assert!(RemoteServer::bind(&path, fake_lookup).is_ok());
+    }
+}
@@ -0,0 +1,221 @@
+//! Where each partly played video was left, so playing it again can pick up
+//! from there. Persisted beside `config.toml`.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{config_dir, write_atomically};
+
+/// Positions of the least recently played videos are dropped beyond this many.
+const CAPACITY: usize = 500;
+/// Positions this close to the start are not worth offering.
+const MIN_POSITION: f64 = 15.0;
+/// A playing track's position is written at most this often.
+const SAVE_INTERVAL: Duration = Duration::from_secs(15);
+/// The resume offer stays up until playback gets this far on its own.
+pub const OFFER_WINDOW: f64 = 15.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct ResumePoint {
+    video_id: String,
+    /// Seconds into the video.
+    position: f64,
+}
+
+#[derive(Debug, Default)]
+pub struct ResumePositions {
+    /// Most recently updated first.
+    points: Vec<ResumePoint>,
+    dirty: bool,
+    saved_at: Option<Instant>,
+}
+
+impl ResumePositions {
+    /// Load the saved positions; a missing file means none.
+    pub fn load() -> Result<Self> {
+        Self::load_from_path(&Self::path()?)
+    }
+
+    fn load_from_path(path: &Path) -> Result<Self> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(error) => return Err(error).context("Failed to read resume positions"),
+        };
+        let mut points: Vec<ResumePoint> =
+            serde_json::from_str(&contents).context("Failed to parse resume positions")?;
+        points.truncate(CAPACITY);
+        Ok(Self {
+            points,
+            ..Self::default()
+        })
+    }
+
+    #[cfg(not(test))]
+    fn save(&self) -> Result<()> {
+        self.save_to_path(&Self::path()?)
+    }
+
+    /// Like `Config::save`, unit tests persist through `save_to_path` only.
+    #[cfg(test)]
+    fn save(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn save_to_path(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(&self.points)?;
+        write_atomically(path, json.as_bytes()).context("Failed to save resume positions")
+    }
+
+    fn path() -> Result<PathBuf> {
+        Ok(config_dir()?.join("resume_positions.json"))
+    }
+
+    pub fn get(&self, video_id: &str) -> Option<f64> {
+        self.points
+            .iter()
+            .find(|point| point.video_id == video_id)
+            .map(|point| point.position)
+    }
+
+    /// The position to offer when `video_id` loads at `time_pos`, unless
+    /// playback already starts near (or past) it.
+    pub fn offer(&self, video_id: &str, time_pos: f64) -> Option<f64> {
+        self.get(video_id)
+            .filter(|&position| time_pos < OFFER_WINDOW && position > time_pos + OFFER_WINDOW)
+    }
+
+    /// Remember `time_pos` for `video_id`, or forget the video once playback
+    /// is within `margin` seconds of the end (or back near the start).
+    pub fn update(&mut self, video_id: &str, time_pos: f64, duration: f64, margin: f64) {
+        let existing = self
+            .points
+            .iter()
+            .position(|point| point.video_id == video_id);
+        if !time_pos.is_finite() || time_pos < MIN_POSITION || time_pos >= duration - margin {
+            if existing.is_some() {
+                self.forget(video_id);
+            }
+            return;
+        }
+        match existing {
+            Some(0) if self.points[0].position == time_pos => return,
+            Some(0) => self.points[0].position = time_pos,
+            Some(index) => {
+                self.points.remove(index);
+                self.insert(video_id, time_pos);
+            }
+            None => self.insert(video_id, time_pos),
+        }
+        self.dirty = true;
+    }
+
+    fn insert(&mut self, video_id: &str, position: f64) {
+        self.points.insert(
+            0,
+            ResumePoint {
+                video_id: video_id.to_string(),
+                position,
+            },
+        );
+        self.points.truncate(CAPACITY);
+    }
+
+    /// Drop the position of a video that played to the end.
+    pub fn forget(&mut self, video_id: &str) {
+        let before = self.points.len();
+        self.points.retain(|point| point.video_id != video_id);
+        if self.points.len() != before {
+            // Forgetting is saved on the next tick so a quit right after the
+            // end of a track does not offer to resume it.
+            self.dirty = true;
+            self.saved_at = None;
+        }
+    }
+
+    /// Save changed positions, at most every few seconds.
+    pub fn save_if_due(&mut self) -> Result<()> {
+        let due = self.dirty
+            && self
+                .saved_at
+                .is_none_or(|saved_at| saved_at.elapsed() >= SAVE_INTERVAL);
+        if due { self.flush() } else { Ok(()) }
+    }
+
+    /// Save changed positions now.
+    pub fn flush(&mut self) -> Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+        self.dirty = false;
+        self.saved_at = Some(Instant::now());
+        self.save()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions_are_kept_until_the_end_margin_or_a_restart() {
+        let mut positions = ResumePositions::default();
+        positions.update("a", 5.0, 600.0, 30.0);
+        assert_eq!(positions.get("a"), None);
+
+        positions.update("a", 120.0, 600.0, 30.0);
+        positions.update("b", 60.0, 600.0, 30.0);
+        positions.update("a", 240.0, 600.0, 30.0);
+        assert_eq!(positions.get("a"), Some(240.0));
+        assert_eq!(positions.points[0].video_id, "a");
+
+        positions.update("a", 575.0, 600.0, 30.0);
+        assert_eq!(positions.get("a"), None);
+
+        positions.update("b", 2.0, 600.0, 30.0);
+        assert_eq!(positions.get("b"), None);
+    }
+
+    #[test]
+    fn offers_only_when_playback_starts_well_before_the_saved_position() {
+        let mut positions = ResumePositions::default();
+        positions.update("a", 300.0, 600.0, 30.0);
+
+        assert_eq!(positions.offer("a", 0.0), Some(300.0));
+        // Started from a timestamp or a restored session instead.
+        assert_eq!(positions.offer("a", 290.0), None);
+        assert_eq!(positions.offer("b", 0.0), None);
+    }
+
+    #[test]
+    fn positions_round_trip_and_are_bounded() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("youtui/resume_positions.json");
+        assert!(
+            ResumePositions::load_from_path(&path)
+                .unwrap()
+                .points
+                .is_empty()
+        );
+
+        let mut positions = ResumePositions::default();
+        for index in 0..=CAPACITY {
+            positions.update(&index.to_string(), 100.0, 600.0, 30.0);
+        }
+        assert_eq!(positions.points.len(), CAPACITY);
+        assert_eq!(positions.get("0"), None);
+        positions.save_to_path(&path).unwrap();
+        assert_eq!(
+            ResumePositions::load_from_path(&path).unwrap().points,
+            positions.points
+        );
+
+        fs::write(&path, "[").unwrap();
+        assert!(ResumePositions::load_from_path(&path).is_err());
+    }
+}
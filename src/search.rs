@@ -7,22 +7,34 @@ use std::sync::mpsc::{self, RecvTimeoutError};
 use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::config::clamp_results_per_page;
 
 const MIN_DURATION: u32 = 180;
+/// YouTube's own bucket edges for its "Under 4 minutes" and "Over 20
+/// minutes" duration filters.
+const SHORT_VIDEO_LIMIT: f64 = 4.0 * 60.0;
+const LONG_VIDEO_LIMIT: f64 = 20.0 * 60.0;
 const SEARCH_CEILING: usize = 500;
+/// Playlists and channel tabs are read further than searches so that
+/// "enqueue all" covers long albums and back catalogues.
+const COLLECTION_CEILING: usize = 5000;
 const SEARCH_TIMEOUT: Duration = Duration::from_secs(45);
+const COLLECTION_TIMEOUT: Duration = Duration::from_secs(180);
+const METADATA_TIMEOUT: Duration = Duration::from_secs(45);
 const PROCESS_POLL_INTERVAL: Duration = Duration::from_millis(25);
+const VIDEO_ID_LEN: usize = 11;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SearchResult {
     pub title: String,
     pub duration: String,
     pub channel: String,
     pub views: String,
     /// Relative upload age ("3 days ago"), empty when unknown.
+    #[serde(default)]
     pub published: String,
     pub id: String,
 }
@@ -50,9 +62,14 @@ impl SearchResult {
         })
     }
 
-    #[allow(dead_code)]
+    /// A placeholder result for unit tests, titled with its ID.
+    #[cfg(test)]
+    pub(crate) fn test_track(id: &str) -> Self {
+        Self::from_line_parts(id, "3:00", "Channel", "0 views", "", id).unwrap()
+    }
+
     pub fn url(&self) -> String {
-        format!("https://www.youtube.com/watch?v={}", self.id)
+        video_url(&self.id)
     }
 
     pub fn safe_title(&self) -> String {
@@ -70,13 +87,488 @@ pub fn check_ytdlp() -> Result<()> {
     Ok(())
 }
 
+pub fn video_url(video_id: &str) -> String {
+    format!("https://www.youtube.com/watch?v={video_id}")
+}
+
+/// Extract a video ID from a bare ID or a watch, youtu.be, shorts, embed or
+/// live URL. Returns `None` for anything that does not name a single video.
+pub fn parse_video_id(input: &str) -> Option<String> {
+    let input = input.trim();
+    if is_video_id(input) {
+        return Some(input.to_string());
+    }
+
+    let (host, path, query) = url_parts(input);
+    let candidate = match host.as_str() {
+        "youtu.be" => path.split('/').next(),
+        "youtube.com" | "youtube-nocookie.com" => match path.trim_end_matches('/') {
+            "watch" => query_param(query, "v"),
+            path => path
+                .split_once('/')
+                .filter(|(kind, _)| matches!(*kind, "shorts" | "embed" | "live" | "v"))
+                .and_then(|(_, id)| id.split('/').next()),
+        },
+        _ => None,
+    }?;
+
+    is_video_id(candidate).then(|| candidate.to_string())
+}
+
+/// The start offset in a video URL's `t=` or `start=` parameter (also read
+/// after `#`), in seconds. Accepts `90`, `90s` and `1h2m3s` forms.
+pub fn parse_start_time(input: &str) -> Option<f64> {
+    let (_, params) = input.trim().split_once(['?', '#'])?;
+    let value = params
+        .split(['&', '#', '?'])
+        .filter_map(|pair| pair.split_once('='))
+        .find_map(|(name, value)| matches!(name, "t" | "start").then_some(value))?;
+
+    let seconds = if value.bytes().all(|byte| byte.is_ascii_digit()) {
+        value.parse::<u64>().ok()?
+    } else {
+        let mut total = 0_u64;
+        let mut digits = String::new();
+        for character in value.chars() {
+            if character.is_ascii_digit() {
+                digits.push(character);
+                continue;
+            }
+            let unit = match character {
+                'h' => 3600,
+                'm' => 60,
+                's' => 1,
+                _ => return None,
+            };
+            let amount: u64 = digits.parse().ok()?;
+            total = total.checked_add(amount.checked_mul(unit)?)?;
+            digits.clear();
+        }
+        // A trailing number without a unit counts as seconds (`1m30`).
+        if !digits.is_empty() {
+            total = total.checked_add(digits.parse().ok()?)?;
+        }
+        total
+    };
+    (seconds > 0).then_some(seconds as f64)
+}
+
+/// The URL to stream for a playlist URL, a channel URL or a bare `@handle`,
+/// or `None` for anything else (including single videos). Channels resolve
+/// to their Videos tab unless the Shorts or Live tab is named explicitly.
+pub fn collection_url(input: &str) -> Option<String> {
+    let input = input.trim();
+    if let Some(handle) = input.strip_prefix('@') {
+        return is_channel_name(handle)
+            .then(|| format!("https://www.youtube.com/@{handle}/videos"));
+    }
+
+    let (host, path, query) = url_parts(input);
+    if host != "youtube.com" {
+        return None;
+    }
+    let path = path.trim_end_matches('/');
+    if path == "playlist" {
+        let list = query_param(query, "list").filter(|list| is_channel_name(list))?;
+        return Some(format!("https://www.youtube.com/playlist?list={list}"));
+    }
+
+    let mut segments = path.split('/');
+    let channel = match segments.next()? {
+        handle if handle.strip_prefix('@').is_some_and(is_channel_name) => handle.to_string(),
+        kind @ ("channel" | "c" | "user") => {
+            format!(
+                "{kind}/{}",
+                segments.next().filter(|name| is_channel_name(name))?
+            )
+        }
+        _ => return None,
+    };
+    let tab = segments
+        .next()
+        .filter(|tab| matches!(*tab, "videos" | "shorts" | "streams"))
+        .unwrap_or("videos");
+    Some(format!("https://www.youtube.com/{channel}/{tab}"))
+}
+
+/// Split a URL, with or without its scheme, into the lowercased host (minus
+/// any `www.`, `m.` or `music.` prefix), the path and the query string.
+fn url_parts(input: &str) -> (String, &str, &str) {
+    let rest = input
+        .strip_prefix("https://")
+        .or_else(|| input.strip_prefix("http://"))
+        .unwrap_or(input);
+    let rest = rest.split('#').next().unwrap_or_default();
+    let (host, path_and_query) = rest.split_once('/').unwrap_or((rest, ""));
+    let (path, query) = path_and_query
+        .split_once('?')
+        .unwrap_or((path_and_query, ""));
+    let host = host.to_ascii_lowercase();
+    let host = ["www.", "m.", "music."]
+        .iter()
+        .find_map(|prefix| host.strip_prefix(prefix))
+        .unwrap_or(&host)
+        .to_string();
+    (host, path, query)
+}
+
+/// Handles, channel IDs, legacy custom names and playlist IDs.
+fn is_channel_name(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= 100
+        && value
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | '%'))
+}
+
+pub(crate) fn is_video_id(value: &str) -> bool {
+    value.len() == VIDEO_ID_LEN
+        && value
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_'))
+}
+
+fn query_param<'a>(query: &'a str, key: &str) -> Option<&'a str> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find_map(|(name, value)| (name == key).then_some(value))
+}
+
+/// Resolve a single video's metadata with a one-shot `yt-dlp --dump-json`.
+pub fn fetch_video(video_id: &str) -> Result<SearchResult> {
+    let metadata = fetch_video_metadata(video_id, &[], &AtomicBool::new(false))?;
+    parse_search_value(&metadata)
+        .map(|parsed| parsed.result)
+        .context("yt-dlp video metadata has no ID")
+}
+
+/// Read a single video's full `yt-dlp --dump-json` metadata, stopping yt-dlp
+/// as soon as `cancelled` is set. `extra_args` go to yt-dlp before the URL.
+pub fn fetch_video_metadata(
+    video_id: &str,
+    extra_args: &[String],
+    cancelled: &AtomicBool,
+) -> Result<Value> {
+    read_video_metadata(video_id, extra_args, cancelled, false)
+}
+
+/// Like `fetch_video_metadata`, but let yt-dlp exit on its own instead of
+/// stopping it once the metadata is printed. yt-dlp prints it before writing
+/// files such as `--write-subs` output, so those need the whole run.
+pub fn fetch_video_metadata_and_wait(
+    video_id: &str,
+    extra_args: &[String],
+    cancelled: &AtomicBool,
+) -> Result<Value> {
+    read_video_metadata(video_id, extra_args, cancelled, true)
+}
+
+fn read_video_metadata(
+    video_id: &str,
+    extra_args: &[String],
+    cancelled: &AtomicBool,
+    wait_for_exit: bool,
+) -> Result<Value> {
+    let mut cmd = Command::new("yt-dlp");
+    cmd.arg("--dump-json")
+        .arg("--no-playlist")
+        .arg("--skip-download")
+        .arg("--no-warnings")
+        .args(extra_args)
+        .arg(video_url(video_id))
+        .stdin(Stdio::null());
+
+    let mut metadata = None;
+    let completion =
+        run_streaming_search_command(cmd, cancelled, Instant::now(), METADATA_TIMEOUT, |line| {
+            if line.trim().is_empty() || metadata.is_some() {
+                return false;
+            }
+            metadata = Some(serde_json::from_str::<Value>(line));
+            !wait_for_exit
+        })?;
+
+    match completion.end {
+        CommandEnd::Stopped => {}
+        CommandEnd::Completed(status) if status.success() => {}
+        CommandEnd::Completed(_) => {
+            let detail = completion.stderr.trim();
+            bail!(
+                "yt-dlp could not read {video_id}{}{}",
+                if detail.is_empty() { "" } else { ": " },
+                detail
+            );
+        }
+        CommandEnd::Cancelled => bail!("Reading {video_id} was cancelled"),
+        CommandEnd::TimedOut => bail!(
+            "yt-dlp timed out after {} seconds reading {video_id}",
+            METADATA_TIMEOUT.as_secs()
+        ),
+        CommandEnd::PollFailed(error) => {
+            return Err(error).context("Failed to monitor yt-dlp");
+        }
+        CommandEnd::ReadFailed(error) => {
+            return Err(error).context("Failed to read yt-dlp output");
+        }
+        CommandEnd::OutputClosed => bail!("yt-dlp output closed unexpectedly"),
+    }
+
+    metadata
+        .context("yt-dlp returned no video metadata")?
+        .context("yt-dlp returned malformed video metadata")
+}
+
+/// The YouTube mix ("radio") playlist that starts from `video_id`.
+pub fn mix_url(video_id: &str) -> String {
+    format!("{}&list=RD{video_id}", video_url(video_id))
+}
+
+/// Read up to `limit` entries of the mix for `video_id`, streamed like a
+/// playlist. The mix usually opens with `video_id` itself.
+pub fn fetch_mix(
+    video_id: &str,
+    limit: usize,
+    cancelled: &AtomicBool,
+) -> Result<Vec<SearchResult>> {
+    let url = mix_url(video_id);
+    let mut mix = PaginatedSearch::new(&url, limit, false);
+    mix.collection_url = Some(url);
+    mix.ensure_results_with_cancel_and_progress(limit, cancelled, |_| {})?;
+    Ok(mix.results)
+}
+
+/// Optional narrowing of a text search. Playlists and channels are listed
+/// as they are.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct SearchFilters {
+    pub duration: DurationFilter,
+    pub uploaded: UploadFilter,
+    pub sort: SortOrder,
+    pub live: LiveFilter,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum DurationFilter {
+    #[default]
+    Any,
+    Short,
+    Medium,
+    Long,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum UploadFilter {
+    #[default]
+    Any,
+    Today,
+    Week,
+    Month,
+    Year,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SortOrder {
+    #[default]
+    Relevance,
+    Date,
+    Views,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum LiveFilter {
+    #[default]
+    Any,
+    LiveOnly,
+    ExcludeLive,
+}
+
+impl DurationFilter {
+    pub const ALL: [Self; 4] = [Self::Any, Self::Short, Self::Medium, Self::Long];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Any => "Any length",
+            Self::Short => "Under 4 minutes",
+            Self::Medium => "4-20 minutes",
+            Self::Long => "Over 20 minutes",
+        }
+    }
+
+    fn accepts(self, seconds: f64) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Short => seconds < SHORT_VIDEO_LIMIT,
+            Self::Medium => (SHORT_VIDEO_LIMIT..=LONG_VIDEO_LIMIT).contains(&seconds),
+            Self::Long => seconds > LONG_VIDEO_LIMIT,
+        }
+    }
+}
+
+impl UploadFilter {
+    pub const ALL: [Self; 5] = [Self::Any, Self::Today, Self::Week, Self::Month, Self::Year];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Any => "Any time",
+            Self::Today => "Today",
+            Self::Week => "This week",
+            Self::Month => "This month",
+            Self::Year => "This year",
+        }
+    }
+}
+
+impl SortOrder {
+    pub const ALL: [Self; 3] = [Self::Relevance, Self::Date, Self::Views];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Relevance => "Relevance",
+            Self::Date => "Newest first",
+            Self::Views => "Most viewed",
+        }
+    }
+}
+
+impl LiveFilter {
+    pub const ALL: [Self; 3] = [Self::Any, Self::LiveOnly, Self::ExcludeLive];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Any => "Live and uploaded",
+            Self::LiveOnly => "Live now only",
+            Self::ExcludeLive => "Exclude live",
+        }
+    }
+}
+
+impl SearchFilters {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The non-default choices, for showing beside the results.
+    pub fn summary(&self) -> Vec<&'static str> {
+        let mut parts = Vec::new();
+        if self.sort != SortOrder::Relevance {
+            parts.push(self.sort.label());
+        }
+        if self.duration != DurationFilter::Any {
+            parts.push(self.duration.label());
+        }
+        if self.uploaded != UploadFilter::Any {
+            parts.push(self.uploaded.label());
+        }
+        if self.live != LiveFilter::Any {
+            parts.push(self.live.label());
+        }
+        parts
+    }
+
+    /// What yt-dlp should list for `query`. Sorting by date alone maps onto
+    /// the `ytsearchdate` prefix; anything else becomes a results page URL
+    /// carrying YouTube's own filter parameter.
+    fn search_id(&self, query: &str) -> String {
+        if self.is_default() {
+            return format!("ytsearch{SEARCH_CEILING}:{query}");
+        }
+        if *self
+            == (Self {
+                sort: SortOrder::Date,
+                ..Self::default()
+            })
+        {
+            return format!("ytsearchdate{SEARCH_CEILING}:{query}");
+        }
+        format!(
+            "https://www.youtube.com/results?search_query={}&sp={}",
+            percent_encode(query),
+            percent_encode(&self.search_params())
+        )
+    }
+
+    /// The `sp` value of a YouTube results URL: a base64 protobuf message
+    /// holding the sort order (field 1) and a filter message (field 2) with
+    /// upload date (1), type (2), duration (3) and live (8). Results are
+    /// always restricted to videos, as `ytsearch` itself does.
+    fn search_params(&self) -> String {
+        let mut filter = vec![0x10, 0x01];
+        let uploaded = match self.uploaded {
+            UploadFilter::Any => None,
+            UploadFilter::Today => Some(2),
+            UploadFilter::Week => Some(3),
+            UploadFilter::Month => Some(4),
+            UploadFilter::Year => Some(5),
+        };
+        if let Some(value) = uploaded {
+            filter.splice(0..0, [0x08, value]);
+        }
+        let duration = match self.duration {
+            DurationFilter::Any => None,
+            DurationFilter::Short => Some(1),
+            DurationFilter::Long => Some(2),
+            DurationFilter::Medium => Some(3),
+        };
+        if let Some(value) = duration {
+            filter.extend([0x18, value]);
+        }
+        // YouTube has no "not live" filter; those are dropped while reading.
+        if self.live == LiveFilter::LiveOnly {
+            filter.extend([0x40, 0x01]);
+        }
+
+        let mut message = Vec::new();
+        let sort = match self.sort {
+            SortOrder::Relevance => None,
+            SortOrder::Date => Some(2),
+            SortOrder::Views => Some(3),
+        };
+        if let Some(value) = sort {
+            message.extend([0x08, value]);
+        }
+        message.extend([0x12, filter.len() as u8]);
+        message.extend(filter);
+        base64_simd::STANDARD.encode_to_string(message)
+    }
+
+    /// Re-check what the results page already filtered, since YouTube's
+    /// buckets are approximate and the "not live" choice has no parameter.
+    fn accepts(&self, entry: &ParsedSearchEntry) -> bool {
+        let live_ok = match self.live {
+            LiveFilter::Any => true,
+            LiveFilter::LiveOnly => entry.is_live,
+            LiveFilter::ExcludeLive => !entry.is_live,
+        };
+        live_ok
+            && entry
+                .duration_seconds
+                .is_none_or(|seconds| self.duration.accepts(seconds))
+    }
+}
+
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
 /// Lazy-paginated search: fetches one batch of raw yt-dlp results at a time
 /// and caches everything already fetched.
 #[derive(Clone)]
 pub struct PaginatedSearch {
     query: String,
+    /// Set when the query names a playlist or channel rather than a search.
+    collection_url: Option<String>,
     pub page_size: usize,
     pub filter_shorts: bool,
+    pub filters: SearchFilters,
     /// All results that have passed filtering so far.
     pub results: Vec<SearchResult>,
     /// How many raw yt-dlp playlist items we have consumed (1-indexed high-water mark).
@@ -89,18 +581,38 @@ impl PaginatedSearch {
     pub fn new(query: &str, page_size: usize, filter_shorts: bool) -> Self {
         PaginatedSearch {
             query: query.to_string(),
+            collection_url: collection_url(query),
             page_size: clamp_results_per_page(page_size),
             filter_shorts,
+            filters: SearchFilters::default(),
             results: Vec::new(),
             raw_cursor: 0,
             exhausted: false,
         }
     }
 
+    pub fn with_filters(mut self, filters: SearchFilters) -> Self {
+        self.filters = filters;
+        self
+    }
+
     pub(crate) fn query(&self) -> &str {
         &self.query
     }
 
+    /// Whether this streams a playlist or channel instead of a search.
+    pub fn is_collection(&self) -> bool {
+        self.collection_url.is_some()
+    }
+
+    fn ceiling(&self) -> usize {
+        if self.is_collection() {
+            COLLECTION_CEILING
+        } else {
+            SEARCH_CEILING
+        }
+    }
+
     /// Make sure we have enough filtered results to display `page` (0-indexed).
     /// Returns the number of displayable results we have.
     #[cfg(test)]
@@ -126,7 +638,7 @@ impl PaginatedSearch {
         &mut self,
         page: usize,
         cancelled: &AtomicBool,
-        mut on_progress: F,
+        on_progress: F,
     ) -> Result<usize>
     where
         F: FnMut(&PaginatedSearch),
@@ -134,7 +646,26 @@ impl PaginatedSearch {
         self.page_size = clamp_results_per_page(self.page_size);
         let page_size = self.page_size;
         let needed = page.saturating_add(1).saturating_mul(page_size);
-        let target = needed.saturating_add(page_size).min(SEARCH_CEILING);
+        let target = needed.saturating_add(page_size);
+        self.ensure_results_with_cancel_and_progress(target, cancelled, on_progress)
+    }
+
+    /// Fetch until at least `target` accepted entries are available, the
+    /// search is exhausted, or the extractor ceiling is reached. Playlists
+    /// and channels stream their flat entries through the same pipeline.
+    pub(crate) fn ensure_results_with_cancel_and_progress<F>(
+        &mut self,
+        target: usize,
+        cancelled: &AtomicBool,
+        mut on_progress: F,
+    ) -> Result<usize>
+    where
+        F: FnMut(&PaginatedSearch),
+    {
+        self.page_size = clamp_results_per_page(self.page_size);
+        let page_size = self.page_size;
+        let ceiling = self.ceiling();
+        let target = target.min(ceiling);
 
         if cancelled.load(Ordering::Relaxed) {
             bail!("Search cancelled");
@@ -144,13 +675,16 @@ impl PaginatedSearch {
         }
 
         let start = self.raw_cursor.saturating_add(1);
-        if start > SEARCH_CEILING {
+        if start > ceiling {
             self.exhausted = true;
             return Ok(self.results.len());
         }
 
-        let search_id = format!("ytsearch{}:{}", SEARCH_CEILING, self.query);
-        let range = format!("{start}:{SEARCH_CEILING}");
+        let (search_id, timeout) = match &self.collection_url {
+            Some(url) => (url.clone(), COLLECTION_TIMEOUT),
+            None => (self.filters.search_id(&self.query), SEARCH_TIMEOUT),
+        };
+        let range = format!("{start}:{ceiling}");
 
         let mut cmd = Command::new("yt-dlp");
         cmd.arg("--flat-playlist")
@@ -169,22 +703,21 @@ impl PaginatedSearch {
         let mut valid_lines = 0_usize;
         let mut malformed_lines = 0_usize;
 
-        let streamed =
-            run_streaming_search_command(cmd, cancelled, started, SEARCH_TIMEOUT, |line| {
-                match self.consume_search_line(line) {
-                    ConsumedLine::Accepted => {
-                        valid_lines += 1;
-                        unreported += 1;
-                        if unreported >= progress_batch {
-                            on_progress(self);
-                            unreported = 0;
-                        }
+        let streamed = run_streaming_search_command(cmd, cancelled, started, timeout, |line| {
+            match self.consume_search_line(line) {
+                ConsumedLine::Accepted => {
+                    valid_lines += 1;
+                    unreported += 1;
+                    if unreported >= progress_batch {
+                        on_progress(self);
+                        unreported = 0;
                     }
-                    ConsumedLine::Ignored => valid_lines += 1,
-                    ConsumedLine::Malformed => malformed_lines += 1,
                 }
-                self.results.len() >= target
-            });
+                ConsumedLine::Ignored => valid_lines += 1,
+                ConsumedLine::Malformed => malformed_lines += 1,
+            }
+            self.results.len() >= target
+        });
 
         if unreported > 0 {
             on_progress(self);
@@ -203,7 +736,7 @@ impl PaginatedSearch {
 
         match completion.end {
             CommandEnd::Stopped => {
-                self.exhausted = self.raw_cursor >= SEARCH_CEILING;
+                self.exhausted = self.raw_cursor >= ceiling;
             }
             CommandEnd::Completed(status) if status.success() => {
                 self.exhausted = true;
@@ -221,7 +754,7 @@ impl PaginatedSearch {
             CommandEnd::Cancelled => bail!("Search cancelled"),
             CommandEnd::TimedOut => bail!(
                 "yt-dlp search timed out after {} seconds",
-                SEARCH_TIMEOUT.as_secs()
+                timeout.as_secs()
             ),
             CommandEnd::PollFailed(error) => {
                 return Err(error).context("Failed to monitor yt-dlp search process");
@@ -241,7 +774,8 @@ impl PaginatedSearch {
             return ConsumedLine::Ignored;
         }
 
-        let fallback_cursor = self.raw_cursor.saturating_add(1).min(SEARCH_CEILING);
+        let ceiling = self.ceiling();
+        let fallback_cursor = self.raw_cursor.saturating_add(1).min(ceiling);
         let parsed = match parse_search_entry(line) {
             Ok(parsed) => parsed,
             Err(_) => {
@@ -257,14 +791,23 @@ impl PaginatedSearch {
             .playlist_index
             .unwrap_or(fallback_cursor)
             .max(self.raw_cursor)
-            .min(SEARCH_CEILING);
-
-        if self.filter_shorts
-            && parsed
-                .duration_seconds
-                .is_some_and(|seconds| seconds < MIN_DURATION as f64)
-        {
-            return ConsumedLine::Ignored;
+            .min(ceiling);
+
+        // Playlists keep short tracks such as album intros; only searches
+        // are filtered. Choosing a duration explicitly replaces the Shorts
+        // rule, so "Under 4 minutes" can still find them.
+        if !self.is_collection() {
+            let hides_shorts = self.filter_shorts && self.filters.duration == DurationFilter::Any;
+            if hides_shorts
+                && parsed
+                    .duration_seconds
+                    .is_some_and(|seconds| seconds < MIN_DURATION as f64)
+            {
+                return ConsumedLine::Ignored;
+            }
+            if !self.filters.accepts(&parsed) {
+                return ConsumedLine::Ignored;
+            }
         }
 
         if self
@@ -275,6 +818,16 @@ impl PaginatedSearch {
             return ConsumedLine::Ignored;
         }
 
+        // Playlists keep placeholders for videos that can no longer be played.
+        if parsed.duration_seconds.is_none()
+            && matches!(
+                parsed.result.title.as_str(),
+                "[Private video]" | "[Deleted video]"
+            )
+        {
+            return ConsumedLine::Ignored;
+        }
+
         self.results.push(parsed.result);
         ConsumedLine::Accepted
     }
@@ -441,13 +994,15 @@ struct ParsedSearchEntry {
     result: SearchResult,
     duration_seconds: Option<f64>,
     playlist_index: Option<usize>,
+    is_live: bool,
 }
 
 fn parse_search_entry(line: &str) -> serde_json::Result<Option<ParsedSearchEntry>> {
-    let entry: Value = serde_json::from_str(line)?;
-    let Some(id) = entry.get("id").and_then(Value::as_str) else {
-        return Ok(None);
-    };
+    serde_json::from_str(line).map(|entry| parse_search_value(&entry))
+}
+
+fn parse_search_value(entry: &Value) -> Option<ParsedSearchEntry> {
+    let id = entry.get("id").and_then(Value::as_str)?;
 
     let title = entry
         .get("title")
@@ -490,21 +1045,22 @@ fn parse_search_entry(line: &str) -> serde_json::Result<Option<ParsedSearchEntry
         .get("playlist_index")
         .and_then(Value::as_u64)
         .and_then(|index| usize::try_from(index).ok());
-
-    Ok(
-        SearchResult::from_line_parts(title, duration, channel, &views, &published, id).map(
-            |result| ParsedSearchEntry {
-                result,
-                duration_seconds,
-                playlist_index,
-            },
-        ),
-    )
+    let is_live = entry.get("live_status").and_then(Value::as_str) == Some("is_live")
+        || entry.get("is_live").and_then(Value::as_bool) == Some(true);
+
+    SearchResult::from_line_parts(title, duration, channel, &views, &published, id).map(|result| {
+        ParsedSearchEntry {
+            result,
+            duration_seconds,
+            playlist_index,
+            is_live,
+        }
+    })
 }
 
 /// Format seconds-since-upload as "x hours/days/weeks/months/years ago".
 /// yt-dlp's approximate_date is day-granular, so sub-day ages read "1 hour ago" at minimum.
-fn format_relative_age(age_seconds: i64) -> String {
+pub(crate) fn format_relative_age(age_seconds: i64) -> String {
     let hours = (age_seconds / 3600).max(1);
     let days = age_seconds / 86_400;
     let (count, unit) = if days >= 365 {
@@ -524,7 +1080,7 @@ fn format_relative_age(age_seconds: i64) -> String {
 
 /// "20260730" -> "2026-07-30". yt-dlp's approximate date has no real time of
 /// day (always midnight UTC), so only the date is shown.
-fn format_upload_date(raw: &str) -> Option<String> {
+pub(crate) fn format_upload_date(raw: &str) -> Option<String> {
     if raw.len() != 8 || !raw.bytes().all(|b| b.is_ascii_digit()) {
         return None;
     }
@@ -725,6 +1281,68 @@ mod tests {
         assert!(!search.exhausted);
     }
 
+    #[test]
+    fn filters_choose_the_search_id_and_youtube_filter_parameter() {
+        let query = "lo-fi & chill";
+        assert_eq!(
+            SearchFilters::default().search_id(query),
+            "ytsearch500:lo-fi & chill"
+        );
+        let newest = SearchFilters {
+            sort: SortOrder::Date,
+            ..SearchFilters::default()
+        };
+        assert_eq!(newest.search_id(query), "ytsearchdate500:lo-fi & chill");
+
+        let most_viewed = SearchFilters {
+            sort: SortOrder::Views,
+            ..SearchFilters::default()
+        };
+        assert_eq!(most_viewed.search_params(), "CAMSAhAB");
+        let this_week_long_live = SearchFilters {
+            duration: DurationFilter::Long,
+            uploaded: UploadFilter::Week,
+            live: LiveFilter::LiveOnly,
+            ..SearchFilters::default()
+        };
+        assert_eq!(this_week_long_live.search_params(), "EggIAxABGAJAAQ==");
+        assert_eq!(
+            this_week_long_live.search_id(query),
+            "https://www.youtube.com/results?search_query=lo-fi%20%26%20chill&sp=EggIAxABGAJAAQ%3D%3D"
+        );
+    }
+
+    #[test]
+    fn filters_recheck_duration_and_live_status_but_not_for_playlists() {
+        let filters = SearchFilters {
+            duration: DurationFilter::Short,
+            live: LiveFilter::ExcludeLive,
+            ..SearchFilters::default()
+        };
+        let mut search = PaginatedSearch::new("test", 10, true).with_filters(filters);
+        for line in [
+            r#"{"id":"short","duration":30}"#,
+            r#"{"id":"long","duration":600}"#,
+            r#"{"id":"live","duration":null,"live_status":"is_live"}"#,
+        ] {
+            search.consume_search_line(line);
+        }
+        assert_eq!(
+            search
+                .results
+                .iter()
+                .map(|item| item.id.as_str())
+                .collect::<Vec<_>>(),
+            ["short"]
+        );
+
+        let mut playlist =
+            PaginatedSearch::new("https://www.youtube.com/playlist?list=PLx", 10, true)
+                .with_filters(filters);
+        playlist.consume_search_line(r#"{"id":"long","duration":600}"#);
+        assert_eq!(playlist.results.len(), 1);
+    }
+
     #[test]
     fn shorts_filter_keeps_unknown_durations_and_skips_known_shorts() {
         let mut search = PaginatedSearch::new("test", 10, true);
@@ -799,6 +1417,167 @@ mod tests {
         assert_eq!(search.raw_cursor, 2);
     }
 
+    #[test]
+    fn start_times_are_read_from_t_and_start_parameters() {
+        for (input, expected) in [
+            ("https://youtu.be/dQw4w9WgXcQ?t=90", Some(90.0)),
+            (
+                "https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=1m30s",
+                Some(90.0),
+            ),
+            (
+                "https://www.youtube.com/watch?v=dQw4w9WgXcQ#t=1h2m3s",
+                Some(3723.0),
+            ),
+            (
+                "https://www.youtube.com/embed/dQw4w9WgXcQ?start=42",
+                Some(42.0),
+            ),
+            ("https://youtu.be/dQw4w9WgXcQ?si=abc&t=15s", Some(15.0)),
+            ("https://youtu.be/dQw4w9WgXcQ?t=1m30", Some(90.0)),
+            ("https://youtu.be/dQw4w9WgXcQ?t=0", None),
+            ("https://youtu.be/dQw4w9WgXcQ?t=soon", None),
+            ("https://youtu.be/dQw4w9WgXcQ?t=5x", None),
+            ("https://www.youtube.com/watch?v=dQw4w9WgXcQ", None),
+            ("dQw4w9WgXcQ", None),
+        ] {
+            assert_eq!(parse_start_time(input), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn collections_keep_short_entries_but_drop_unavailable_placeholders() {
+        let mut search = PaginatedSearch::new("https://youtube.com/playlist?list=PLabc", 10, true);
+        assert!(search.is_collection());
+
+        assert_eq!(
+            search.consume_search_line(r#"{"id":"intro","duration":42,"playlist_index":1}"#),
+            ConsumedLine::Accepted
+        );
+        assert_eq!(
+            search.consume_search_line(
+                r#"{"id":"gone","title":"[Private video]","duration":null,"playlist_index":2}"#
+            ),
+            ConsumedLine::Ignored
+        );
+
+        assert_eq!(search.results.len(), 1);
+        assert_eq!(search.raw_cursor, 2);
+        assert!(!PaginatedSearch::new("lofi", 10, true).is_collection());
+    }
+
+    #[test]
+    fn mix_urls_name_the_radio_playlist_for_a_video() {
+        assert_eq!(
+            mix_url("dQw4w9WgXcQ"),
+            "https://www.youtube.com/watch?v=dQw4w9WgXcQ&list=RDdQw4w9WgXcQ"
+        );
+    }
+
+    #[test]
+    fn playlist_channel_and_handle_inputs_resolve_to_collection_urls() {
+        for (input, expected) in [
+            (
+                "https://www.youtube.com/playlist?list=PLx0sYbCqOb8TBPRdmBHs5Iftvv9TPboYG",
+                "https://www.youtube.com/playlist?list=PLx0sYbCqOb8TBPRdmBHs5Iftvv9TPboYG",
+            ),
+            (
+                "music.youtube.com/playlist?list=OLAK5uy_abc&si=xyz",
+                "https://www.youtube.com/playlist?list=OLAK5uy_abc",
+            ),
+            ("@LofiGirl", "https://www.youtube.com/@LofiGirl/videos"),
+            (
+                "https://www.youtube.com/@LofiGirl/streams",
+                "https://www.youtube.com/@LofiGirl/streams",
+            ),
+            (
+                "https://youtube.com/@LofiGirl/featured",
+                "https://www.youtube.com/@LofiGirl/videos",
+            ),
+            (
+                "https://www.youtube.com/channel/UCSJ4gkVC6NrvII8umztf0Ow",
+                "https://www.youtube.com/channel/UCSJ4gkVC6NrvII8umztf0Ow/videos",
+            ),
+            (
+                "youtube.com/c/SomeName/shorts/",
+                "https://www.youtube.com/c/SomeName/shorts",
+            ),
+        ] {
+            assert_eq!(collection_url(input).as_deref(), Some(expected), "{input}");
+        }
+
+        for input in [
+            "lofi hip hop",
+            "@ two words",
+            "@",
+            "https://www.youtube.com/watch?v=dQw4w9WgXcQ&list=PLabc",
+            "https://www.youtube.com/playlist",
+            "https://example.com/@someone",
+            "https://www.youtube.com/results?search_query=lofi",
+        ] {
+            assert_eq!(collection_url(input), None, "{input}");
+        }
+    }
+
+    #[test]
+    fn video_ids_are_extracted_from_common_url_shapes() {
+        let expected = Some("dQw4w9WgXcQ".to_string());
+        for input in [
+            "dQw4w9WgXcQ",
+            " https://www.youtube.com/watch?v=dQw4w9WgXcQ ",
+            "https://youtube.com/watch?feature=share&v=dQw4w9WgXcQ&t=42",
+            "http://m.youtube.com/watch?v=dQw4w9WgXcQ#comments",
+            "https://music.youtube.com/watch?v=dQw4w9WgXcQ&list=RDAMVM",
+            "youtu.be/dQw4w9WgXcQ?si=abc",
+            "https://www.youtube.com/shorts/dQw4w9WgXcQ",
+            "https://www.youtube.com/embed/dQw4w9WgXcQ",
+            "https://www.youtube.com/live/dQw4w9WgXcQ?feature=share",
+        ] {
+            assert_eq!(parse_video_id(input), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn video_id_parser_rejects_queries_and_non_video_urls() {
+        for input in [
+            "",
+            "lofi hip hop",
+            "dQw4w9WgXc",
+            "dQw4w9WgXcQQ",
+            "dQw4w9WgX!Q",
+            "https://www.youtube.com/playlist?list=PL1234567890",
+            "https://www.youtube.com/@channel",
+            "https://example.com/watch?v=dQw4w9WgXcQ",
+            "https://www.youtube.com/watch?v=short",
+        ] {
+            assert_eq!(parse_video_id(input), None, "{input}");
+        }
+    }
+
+    #[test]
+    fn search_results_round_trip_through_json() {
+        let result = SearchResult::from_line_parts(
+            "Title",
+            "3:20",
+            "Channel",
+            "1K views",
+            "",
+            "dQw4w9WgXcQ",
+        )
+        .unwrap();
+        let json = serde_json::to_string(&result).unwrap();
+
+        assert_eq!(serde_json::from_str::<SearchResult>(&json).unwrap(), result);
+        assert_eq!(
+            serde_json::from_str::<SearchResult>(
+                r#"{"title":"T","duration":"N/A","channel":"C","views":"0 views","id":"x"}"#
+            )
+            .unwrap()
+            .published,
+            ""
+        );
+    }
+
     #[test]
     fn view_counts_are_compact_and_readable() {
         assert_eq!(
@@ -0,0 +1,234 @@
+//! Queries submitted from the search bar, newest first, persisted beside
+//! `config.toml` for recall and completion.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use crate::config::{config_dir, write_atomically};
+
+/// Older queries are dropped beyond this many.
+const CAPACITY: usize = 200;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchHistory {
+    /// Newest first, without duplicates.
+    entries: Vec<String>,
+}
+
+impl SearchHistory {
+    /// Load the saved history; a missing file is an empty history.
+    pub fn load() -> Result<Self> {
+        Self::load_from_path(&Self::path()?)
+    }
+
+    fn load_from_path(path: &Path) -> Result<Self> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(error) => return Err(error).context("Failed to read search history"),
+        };
+        let saved: Vec<String> =
+            serde_json::from_str(&contents).context("Failed to parse search history")?;
+        let mut history = Self::default();
+        // Re-recording oldest first applies the same trimming and
+        // de-duplication to a hand-edited file.
+        for query in saved.iter().rev() {
+            history.push(query);
+        }
+        Ok(history)
+    }
+
+    #[cfg(not(test))]
+    pub fn save(&self) -> Result<()> {
+        self.save_to_path(&Self::path()?)
+    }
+
+    /// Like `Config::save`, unit tests persist through `save_to_path` only.
+    #[cfg(test)]
+    pub fn save(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn save_to_path(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(&self.entries)?;
+        write_atomically(path, json.as_bytes()).context("Failed to save search history")
+    }
+
+    fn path() -> Result<PathBuf> {
+        Ok(config_dir()?.join("search_history.json"))
+    }
+
+    /// Move `query` to the front and save the history if that changed it.
+    pub fn record(&mut self, query: &str) -> Result<()> {
+        if self.push(query) {
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    fn push(&mut self, query: &str) -> bool {
+        let query = query.trim();
+        if query.is_empty() || self.entries.first().is_some_and(|newest| newest == query) {
+            return false;
+        }
+        self.entries.retain(|entry| entry != query);
+        self.entries.insert(0, query.to_string());
+        self.entries.truncate(CAPACITY);
+        true
+    }
+
+    /// The `index`-th most recent query.
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.entries.get(index).map(String::as_str)
+    }
+
+    /// The most recent query that extends `prefix`, compared character by
+    /// character without regard to case, for showing as an inline
+    /// suggestion. The query has more characters than `prefix`, so the
+    /// suggestion is what follows its first `prefix.chars().count()`.
+    pub fn completion(&self, prefix: &str) -> Option<&str> {
+        if prefix.trim().is_empty() {
+            return None;
+        }
+        let typed = prefix.chars().count();
+        self.entries
+            .iter()
+            .find(|entry| {
+                entry.chars().count() > typed
+                    && entry
+                        .chars()
+                        .zip(prefix.chars())
+                        .all(|(stored, typed)| stored.to_lowercase().eq(typed.to_lowercase()))
+            })
+            .map(String::as_str)
+    }
+
+    /// Queries containing the characters of `pattern` in order (ignoring
+    /// case), tightest and then most recent matches first.
+    pub fn fuzzy_matches(&self, pattern: &str) -> Vec<&str> {
+        let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+        let mut scored: Vec<(usize, usize, &str)> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(age, entry)| {
+                fuzzy_span(&entry.to_lowercase(), &pattern).map(|span| (span, age, entry.as_str()))
+            })
+            .collect();
+        scored.sort_by_key(|&(span, age, _)| (span, age));
+        scored.into_iter().map(|(_, _, entry)| entry).collect()
+    }
+}
+
+/// The length of the shortest stretch of `text` holding `pattern` as a
+/// subsequence, or `None` when it does not.
+fn fuzzy_span(text: &str, pattern: &[char]) -> Option<usize> {
+    let Some(&first) = pattern.first() else {
+        return Some(0);
+    };
+    let text: Vec<char> = text.chars().collect();
+    text.iter()
+        .enumerate()
+        .filter(|&(_, &character)| character == first)
+        .filter_map(|(start, _)| {
+            let mut wanted = pattern.iter().skip(1).peekable();
+            for (offset, character) in text[start + 1..].iter().enumerate() {
+                if wanted.peek().is_none() {
+                    return Some(offset + 1);
+                }
+                if wanted.peek() == Some(&character) {
+                    wanted.next();
+                }
+            }
+            wanted.peek().is_none().then_some(text.len() - start)
+        })
+        .min()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(queries: &[&str]) -> SearchHistory {
+        let mut history = SearchHistory::default();
+        for query in queries {
+            history.push(query);
+        }
+        history
+    }
+
+    #[test]
+    fn recording_moves_repeats_to_the_front_and_stays_bounded() {
+        let mut history = history(&["lofi", "jazz", " lofi "]);
+        assert_eq!(history.entries, ["lofi", "jazz"]);
+        assert!(!history.push("lofi"));
+        assert!(!history.push("   "));
+
+        for index in 0..CAPACITY {
+            history.push(&format!("query {index}"));
+        }
+        assert_eq!(history.entries.len(), CAPACITY);
+        assert_eq!(history.get(0), Some("query 199"));
+        assert!(!history.entries.contains(&"lofi".to_string()));
+    }
+
+    #[test]
+    fn history_round_trips_and_cleans_hand_edited_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("youtui/search_history.json");
+        assert_eq!(
+            SearchHistory::load_from_path(&path).unwrap(),
+            SearchHistory::default()
+        );
+
+        let saved = history(&["a", "b", "c"]);
+        saved.save_to_path(&path).unwrap();
+        assert_eq!(SearchHistory::load_from_path(&path).unwrap(), saved);
+
+        fs::write(&path, r#"["new", " old ", "new", ""]"#).unwrap();
+        assert_eq!(
+            SearchHistory::load_from_path(&path).unwrap().entries,
+            ["new", "old"]
+        );
+
+        fs::write(&path, "{").unwrap();
+        assert!(SearchHistory::load_from_path(&path).is_err());
+    }
+
+    #[test]
+    fn completion_prefers_the_newest_longer_prefix_match() {
+        let history = history(&["Daft Punk live", "daft punk", "dafne"]);
+
+        assert_eq!(history.completion("DAF"), Some("dafne"));
+        assert_eq!(history.completion("daft p"), Some("daft punk"));
+        assert_eq!(history.completion("daft punk"), Some("Daft Punk live"));
+        assert_eq!(history.completion("daft punk live"), None);
+        assert_eq!(history.completion(""), None);
+    }
+
+    #[test]
+    fn completion_matches_by_characters_when_case_folding_changes_length() {
+        // KELVIN SIGN is three bytes but lowercases to the one-byte `k`.
+        let history = history(&["\u{212A}", "\u{212A}ÄRZTE live"]);
+
+        assert_eq!(history.completion("kä"), Some("\u{212A}ÄRZTE live"));
+        assert_eq!(history.completion("käRZTE LIVE"), None);
+        assert_eq!(history.completion("x"), None);
+    }
+
+    #[test]
+    fn fuzzy_matches_rank_tight_matches_before_recent_ones() {
+        let history = history(&["lofi hip hop", "lo-fi beats", "classical guitar", "lofi"]);
+
+        assert_eq!(
+            history.fuzzy_matches("lofi"),
+            ["lofi", "lofi hip hop", "lo-fi beats"]
+        );
+        assert_eq!(history.fuzzy_matches("GTR"), ["classical guitar"]);
+        assert_eq!(history.fuzzy_matches("").len(), 4);
+        assert!(history.fuzzy_matches("zzz").is_empty());
+    }
+}
@@ -0,0 +1,247 @@
+//! The listening session (queue, selection and playback position) persisted
+//! beside `config.toml` so a quit or crash does not lose it.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{config_dir, write_atomically};
+use crate::search::SearchResult;
+
+/// Position-only changes are written at most this often; queue edits are
+/// written on the next tick.
+const POSITION_SAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QueueSnapshot {
+    pub tracks: Vec<SearchResult>,
+    pub selected_index: usize,
+    /// Playback position of the front track, in seconds.
+    pub time_pos: f64,
+}
+
+impl QueueSnapshot {
+    /// Load the saved session, if any. An empty saved queue is treated as none.
+    pub fn load() -> Result<Option<Self>> {
+        Self::load_from_path(&Self::path()?)
+    }
+
+    fn load_from_path(path: &Path) -> Result<Option<Self>> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(error) => return Err(error).context("Failed to read saved queue"),
+        };
+        let mut snapshot: Self =
+            serde_json::from_str(&contents).context("Failed to parse saved queue")?;
+        if snapshot.tracks.is_empty() {
+            return Ok(None);
+        }
+        snapshot.selected_index = snapshot
+            .selected_index
+            .min(snapshot.tracks.len().saturating_sub(1));
+        if !snapshot.time_pos.is_finite() || snapshot.time_pos < 0.0 {
+            snapshot.time_pos = 0.0;
+        }
+        Ok(Some(snapshot))
+    }
+
+    #[cfg(not(test))]
+    pub fn save(&self) -> Result<()> {
+        self.save_to_path(&Self::path()?)
+    }
+
+    /// Like `Config::save`, unit tests persist through `save_to_path` only.
+    #[cfg(test)]
+    pub fn save(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn save_to_path(&self, path: &Path) -> Result<()> {
+        if self.tracks.is_empty() {
+            // Nothing to offer on the next launch.
+            return match fs::remove_file(path) {
+                Err(error) if error.kind() != ErrorKind::NotFound => {
+                    Err(error).context("Failed to remove saved queue")
+                }
+                _ => Ok(()),
+            };
+        }
+        let json = serde_json::to_string_pretty(self)?;
+        write_atomically(path, json.as_bytes()).context("Failed to save queue")
+    }
+
+    fn path() -> Result<PathBuf> {
+        Ok(config_dir()?.join("queue.json"))
+    }
+
+    fn same_queue(&self, other: &Self) -> bool {
+        self.tracks == other.tracks && self.selected_index == other.selected_index
+    }
+}
+
+/// Decides when the running session needs to be written again.
+#[derive(Default)]
+pub struct SessionSaver {
+    saved: Option<QueueSnapshot>,
+    saved_at: Option<Instant>,
+}
+
+impl SessionSaver {
+    /// Save a changed queue immediately and a moved playback position at
+    /// most every few seconds.
+    pub fn save_if_changed(&mut self, snapshot: QueueSnapshot) -> Result<()> {
+        let due = match (&self.saved, self.saved_at) {
+            (Some(saved), Some(saved_at)) if saved.same_queue(&snapshot) => {
+                (saved.time_pos - snapshot.time_pos).abs() >= 1.0
+                    && saved_at.elapsed() >= POSITION_SAVE_INTERVAL
+            }
+            _ => true,
+        };
+        if due {
+            self.save(snapshot)?;
+        }
+        Ok(())
+    }
+
+    /// Save whatever differs from the last write, ignoring the throttle.
+    pub fn flush(&mut self, snapshot: QueueSnapshot) -> Result<()> {
+        if self.saved.as_ref() != Some(&snapshot) {
+            self.save(snapshot)?;
+        }
+        Ok(())
+    }
+
+    fn save(&mut self, snapshot: QueueSnapshot) -> Result<()> {
+        // An empty queue only clears a session this run saved, so declining
+        // the restore prompt leaves the previous session on disk until the
+        // new one has tracks to save over it.
+        let saved_tracks = self
+            .saved
+            .as_ref()
+            .is_some_and(|saved| !saved.tracks.is_empty());
+        if snapshot.tracks.is_empty() && !saved_tracks {
+            return Ok(());
+        }
+        self.saved_at = Some(Instant::now());
+        let result = snapshot.save();
+        self.saved = Some(snapshot);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_round_trips_and_replaces_the_previous_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("youtui/queue.json");
+        let first = QueueSnapshot {
+            tracks: vec![SearchResult::test_track("a"), SearchResult::test_track("b")],
+            selected_index: 1,
+            time_pos: 42.5,
+        };
+        first.save_to_path(&path).unwrap();
+        let second = QueueSnapshot {
+            tracks: vec![SearchResult::test_track("c")],
+            ..QueueSnapshot::default()
+        };
+        second.save_to_path(&path).unwrap();
+
+        assert_eq!(QueueSnapshot::load_from_path(&path).unwrap(), Some(second));
+        assert_eq!(fs::read_dir(path.parent().unwrap()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn empty_queue_removes_the_saved_session() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("queue.json");
+        QueueSnapshot {
+            tracks: vec![SearchResult::test_track("a")],
+            ..QueueSnapshot::default()
+        }
+        .save_to_path(&path)
+        .unwrap();
+
+        QueueSnapshot::default().save_to_path(&path).unwrap();
+        QueueSnapshot::default().save_to_path(&path).unwrap();
+
+        assert!(!path.exists());
+        assert_eq!(QueueSnapshot::load_from_path(&path).unwrap(), None);
+    }
+
+    #[test]
+    fn loaded_snapshots_are_sanitized() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("queue.json");
+        let saved = serde_json::json!({
+            "tracks": [SearchResult::test_track("a"), SearchResult::test_track("b")],
+            "selected_index": 9,
+            "time_pos": -3.0,
+        });
+        fs::write(&path, saved.to_string()).unwrap();
+
+        let snapshot = QueueSnapshot::load_from_path(&path).unwrap().unwrap();
+
+        assert_eq!(snapshot.selected_index, 1);
+        assert_eq!(snapshot.time_pos, 0.0);
+
+        fs::write(&path, "{not json").unwrap();
+        assert!(QueueSnapshot::load_from_path(&path).is_err());
+    }
+
+    #[test]
+    fn saver_throttles_position_updates_but_not_queue_edits() {
+        let mut saver = SessionSaver::default();
+        let snapshot = QueueSnapshot {
+            tracks: vec![SearchResult::test_track("a")],
+            ..QueueSnapshot::default()
+        };
+        saver.save_if_changed(snapshot.clone()).unwrap();
+
+        let moved = QueueSnapshot {
+            time_pos: 30.0,
+            ..snapshot.clone()
+        };
+        saver.save_if_changed(moved.clone()).unwrap();
+        assert_eq!(saver.saved.as_ref().unwrap().time_pos, 0.0);
+
+        let edited = QueueSnapshot {
+            tracks: vec![SearchResult::test_track("a"), SearchResult::test_track("b")],
+            ..moved.clone()
+        };
+        saver.save_if_changed(edited.clone()).unwrap();
+        assert_eq!(saver.saved.as_ref(), Some(&edited));
+
+        let final_position = QueueSnapshot {
+            time_pos: 31.0,
+            ..edited
+        };
+        saver.flush(final_position.clone()).unwrap();
+        assert_eq!(saver.saved, Some(final_position));
+    }
+
+    #[test]
+    fn an_empty_queue_only_clears_a_session_this_run_saved() {
+        let mut saver = SessionSaver::default();
+        saver.save_if_changed(QueueSnapshot::default()).unwrap();
+        saver.flush(QueueSnapshot::default()).unwrap();
+        assert_eq!(saver.saved, None);
+
+        saver
+            .save_if_changed(QueueSnapshot {
+                tracks: vec![SearchResult::test_track("a")],
+                ..QueueSnapshot::default()
+            })
+            .unwrap();
+        saver.save_if_changed(QueueSnapshot::default()).unwrap();
+        assert_eq!(saver.saved, Some(QueueSnapshot::default()));
+    }
+}
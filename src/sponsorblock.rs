@@ -0,0 +1,329 @@
+//! SponsorBlock: crowd-sourced timings of sponsor reads, intros and other
+//! segments that playback skips. Segments are read from the SponsorBlock API
+//! with curl, or through yt-dlp's `--sponsorblock-mark` when no API is
+//! configured, on a worker thread while the track starts.
+
+use std::process::Command;
+use std::sync::atomic::AtomicBool;
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde_json::Value;
+
+use crate::search;
+use crate::worker::{self, Job};
+
+/// The categories youtui can skip, as SponsorBlock names them.
+pub const CATEGORIES: [&str; 5] = ["sponsor", "selfpromo", "intro", "outro", "music_offtopic"];
+
+pub const DEFAULT_API: &str = "https://sponsor.ajay.app";
+
+pub(crate) type FetchSegments = fn(&str, &SegmentQuery, &AtomicBool) -> Result<Vec<Segment>>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    /// Seconds from the start of the video.
+    pub start: f64,
+    pub end: f64,
+    pub category: String,
+}
+
+impl Segment {
+    pub fn contains(&self, position: f64) -> bool {
+        self.start <= position && position < self.end
+    }
+}
+
+/// Where to read segments from and which ones to skip.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SegmentQuery {
+    /// API base URL; empty to let yt-dlp ask its default server.
+    pub api: String,
+    pub categories: Vec<String>,
+}
+
+/// How a category reads in the player bar: "Skipped self-promotion".
+pub fn category_label(category: &str) -> &str {
+    match category {
+        "selfpromo" => "self-promotion",
+        "music_offtopic" => "non-music section",
+        other => other,
+    }
+}
+
+pub fn fetch_segments(
+    video_id: &str,
+    query: &SegmentQuery,
+    cancelled: &AtomicBool,
+) -> Result<Vec<Segment>> {
+    if !search::is_video_id(video_id) {
+        bail!("\"{video_id}\" is not a video ID");
+    }
+    if query.api.is_empty() {
+        let args = [
+            "--sponsorblock-mark".to_string(),
+            query.categories.join(","),
+        ];
+        let metadata = search::fetch_video_metadata(video_id, &args, cancelled)?;
+        Ok(parse_chapter_segments(&metadata))
+    } else {
+        fetch_from_api(video_id, query, cancelled)
+    }
+}
+
+fn fetch_from_api(
+    video_id: &str,
+    query: &SegmentQuery,
+    cancelled: &AtomicBool,
+) -> Result<Vec<Segment>> {
+    // The categories go as a JSON array; every name is plain ASCII, so only
+    // the brackets, quotes and commas need escaping.
+    let categories = query
+        .categories
+        .iter()
+        .map(|category| format!("%22{category}%22"))
+        .collect::<Vec<_>>()
+        .join("%2C");
+    let url = format!(
+        "{}/api/skipSegments?videoID={video_id}&categories=%5B{categories}%5D",
+        query.api.trim_end_matches('/')
+    );
+    let mut curl = Command::new("curl");
+    curl.args(["--silent", "--show-error", "--location", "--max-time", "10"])
+        .args(["--write-out", "\n%{http_code}"])
+        .arg(&url);
+    let output = worker::run_curl(curl, cancelled)?;
+    if !output.status.success() {
+        bail!(
+            "curl failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (body, status) = stdout.rsplit_once('\n').unwrap_or(("", &stdout));
+    match status.trim() {
+        "200" => parse_api_segments(body),
+        // SponsorBlock answers 404 for a video nobody has submitted segments for.
+        "404" => Ok(Vec::new()),
+        status => bail!("SponsorBlock answered with HTTP {status}"),
+    }
+}
+
+/// Segments from a `/api/skipSegments` reply, in playback order. Segments
+/// meant to be muted or only marked are left out.
+fn parse_api_segments(body: &str) -> Result<Vec<Segment>> {
+    let reply: Value =
+        serde_json::from_str(body).context("SponsorBlock sent a reply that is not JSON")?;
+    let entries = reply
+        .as_array()
+        .ok_or_else(|| anyhow!("SponsorBlock sent a reply that is not a list"))?;
+    let segments = entries.iter().filter_map(|entry| {
+        let action = entry.get("actionType").and_then(Value::as_str);
+        if action.is_some_and(|action| action != "skip") {
+            return None;
+        }
+        let times = entry.get("segment")?.as_array()?;
+        Some(Segment {
+            start: times.first()?.as_f64()?,
+            end: times.get(1)?.as_f64()?,
+            category: entry.get("category")?.as_str()?.to_string(),
+        })
+    });
+    Ok(sorted(segments))
+}
+
+/// Segments yt-dlp's `--sponsorblock-mark` left in the metadata.
+fn parse_chapter_segments(metadata: &Value) -> Vec<Segment> {
+    let chapters = metadata
+        .get("sponsorblock_chapters")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten();
+    sorted(chapters.filter_map(|chapter| {
+        Some(Segment {
+            start: chapter.get("start_time")?.as_f64()?,
+            end: chapter.get("end_time")?.as_f64()?,
+            category: chapter.get("category")?.as_str()?.to_string(),
+        })
+    }))
+}
+
+fn sorted(segments: impl Iterator<Item = Segment>) -> Vec<Segment> {
+    let mut segments: Vec<Segment> = segments
+        .filter(|segment| segment.start.is_finite() && segment.end > segment.start)
+        .map(|segment| Segment {
+            start: segment.start.max(0.0),
+            ..segment
+        })
+        .collect();
+    segments.sort_by(|a, b| a.start.total_cmp(&b.start));
+    segments
+}
+
+/// Read the segments of the track that just started on a worker thread.
+/// Dropping the job stops the read.
+pub(crate) fn spawn_fetch(
+    video_id: &str,
+    query: SegmentQuery,
+    fetch_segments: FetchSegments,
+) -> Result<Job<Vec<Segment>>> {
+    let video_id = video_id.to_string();
+    Job::spawn("SponsorBlock", move |cancelled| {
+        fetch_segments(&video_id, &query, cancelled)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::atomic::Ordering;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use super::*;
+
+    fn segment(start: f64, end: f64, category: &str) -> Segment {
+        Segment {
+            start,
+            end,
+            category: category.to_string(),
+        }
+    }
+
+    #[test]
+    fn api_replies_keep_only_segments_to_skip_in_playback_order() {
+        let body = r#"[
+            {"category": "outro", "actionType": "skip", "segment": [580.5, 600.0], "UUID": "b"},
+            {"category": "sponsor", "actionType": "skip", "segment": [12.0, 45.25], "UUID": "a"},
+            {"category": "music_offtopic", "actionType": "mute", "segment": [100.0, 110.0]},
+            {"category": "sponsor", "segment": [200.0, 200.0]},
+            {"category": "intro", "segment": [-1.0, 8.0]}
+        ]"#;
+
+        assert_eq!(
+            parse_api_segments(body).unwrap(),
+            [
+                segment(0.0, 8.0, "intro"),
+                segment(12.0, 45.25, "sponsor"),
+                segment(580.5, 600.0, "outro"),
+            ]
+        );
+        assert!(parse_api_segments("Not Found").is_err());
+    }
+
+    #[test]
+    fn yt_dlp_segments_come_from_the_sponsorblock_chapters() {
+        let metadata = serde_json::json!({
+            "id": "abcdefghijk",
+            "chapters": [{"start_time": 0.0, "end_time": 30.0, "title": "Intro"}],
+            "sponsorblock_chapters": [
+                {"start_time": 61.0, "end_time": 90.0, "category": "selfpromo",
+                 "title": "Unpaid/Self Promotion", "type": "skip"},
+                {"start_time": 5.0, "end_time": 30.0, "category": "sponsor",
+                 "title": "Sponsor", "type": "skip"}
+            ]
+        });
+
+        assert_eq!(
+            parse_chapter_segments(&metadata),
+            [
+                segment(5.0, 30.0, "sponsor"),
+                segment(61.0, 90.0, "selfpromo"),
+            ]
+        );
+        assert!(parse_chapter_segments(&serde_json::json!({})).is_empty());
+    }
+
+    /// A stand-in API answering one request per reply. The server thread
+    /// returns the request lines it saw.
+    fn stand_in_api(
+        replies: Vec<(&'static str, &'static str)>,
+    ) -> (String, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}/", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let mut requests = Vec::new();
+            for (status, body) in replies {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut header = String::new();
+                while reader.read_line(&mut header).unwrap() > 2 {
+                    header.clear();
+                }
+                write!(
+                    stream,
+                    "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+                requests.push(request_line.trim().to_string());
+            }
+            requests
+        });
+        (base, server)
+    }
+
+    #[test]
+    fn segments_are_read_from_the_configured_api() {
+        let (api, server) = stand_in_api(vec![
+            (
+                "200 OK",
+                r#"[{"category": "sponsor", "actionType": "skip", "segment": [3.0, 9.5]}]"#,
+            ),
+            ("404 Not Found", "Not Found"),
+            ("500 Internal Server Error", "oops"),
+        ]);
+        let query = SegmentQuery {
+            api,
+            categories: vec!["sponsor".to_string(), "music_offtopic".to_string()],
+        };
+        let cancelled = AtomicBool::new(false);
+
+        let found = fetch_segments("abcdefghijk", &query, &cancelled).unwrap();
+        assert_eq!(found, [segment(3.0, 9.5, "sponsor")]);
+        // Nobody has submitted segments for this one.
+        let missing = fetch_segments("abcdefghijk", &query, &cancelled).unwrap();
+        assert!(missing.is_empty());
+        let error = fetch_segments("abcdefghijk", &query, &cancelled).unwrap_err();
+        assert_eq!(error.to_string(), "SponsorBlock answered with HTTP 500");
+
+        let expected = "GET /api/skipSegments?videoID=abcdefghijk\
+            &categories=%5B%22sponsor%22%2C%22music_offtopic%22%5D HTTP/1.1";
+        assert_eq!(server.join().unwrap(), [expected; 3]);
+    }
+
+    #[test]
+    fn only_video_ids_are_looked_up() {
+        let query = SegmentQuery {
+            api: "http://127.0.0.1:9".to_string(),
+            categories: vec!["sponsor".to_string()],
+        };
+        let error = fetch_segments("a&b=c", &query, &AtomicBool::new(false)).unwrap_err();
+        assert_eq!(error.to_string(), "\"a&b=c\" is not a video ID");
+    }
+
+    #[test]
+    fn cancelling_stops_a_hanging_api_request() {
+        // Connections are queued but never answered.
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let query = SegmentQuery {
+            api: format!("http://{}", listener.local_addr().unwrap()),
+            categories: vec!["sponsor".to_string()],
+        };
+        let cancelled = AtomicBool::new(false);
+        let started = Instant::now();
+
+        let error = thread::scope(|scope| {
+            scope.spawn(|| {
+                thread::sleep(Duration::from_millis(100));
+                cancelled.store(true, Ordering::Relaxed);
+            });
+            fetch_segments("abcdefghijk", &query, &cancelled).unwrap_err()
+        });
+        assert_eq!(error.to_string(), "curl was cancelled");
+        assert!(started.elapsed() < Duration::from_secs(5));
+        drop(listener);
+    }
+}
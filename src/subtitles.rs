@@ -0,0 +1,489 @@
+//! Subtitles for the playing track. yt-dlp writes the chosen language's
+//! captions as WebVTT into a temporary directory on a worker thread, and
+//! reports which other languages the video has. The cues are then matched
+//! against mpv's position for the subtitle line and the transcript view.
+
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+
+use anyhow::{Context, Result, bail};
+use serde_json::Value;
+
+use crate::search;
+use crate::worker::Job;
+
+pub(crate) type FetchSubtitles = fn(&str, &str, &AtomicBool) -> Result<Transcript>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cue {
+    /// Seconds from the start of the video.
+    pub start: f64,
+    pub end: f64,
+    /// The cue's lines, joined with spaces.
+    pub text: String,
+}
+
+/// A subtitle language the video offers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Language {
+    /// "en", "pt-BR".
+    pub code: String,
+    /// "English", as YouTube names it.
+    pub name: String,
+    /// Generated by YouTube's speech recognition or machine translation.
+    pub automatic: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Transcript {
+    pub cues: Vec<Cue>,
+    /// Uploaded subtitles first, then automatic captions, each by name.
+    pub languages: Vec<Language>,
+}
+
+impl Transcript {
+    /// The last cue to start by `position`, or the first one before any
+    /// has started.
+    pub fn line_at(&self, position: f64) -> usize {
+        self.cues
+            .partition_point(|cue| cue.start <= position)
+            .saturating_sub(1)
+    }
+
+    /// The cue showing at `position`, if any.
+    pub fn cue_at(&self, position: f64) -> Option<usize> {
+        let after = self.cues.partition_point(|cue| cue.start <= position);
+        after
+            .checked_sub(1)
+            .filter(|&index| position < self.cues[index].end)
+    }
+}
+
+pub fn fetch_subtitles(
+    video_id: &str,
+    language: &str,
+    cancelled: &AtomicBool,
+) -> Result<Transcript> {
+    // yt-dlp reads the language as a pattern; codes are only letters,
+    // digits and dashes.
+    if language.is_empty()
+        || !language
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        bail!("\"{language}\" is not a subtitle language");
+    }
+    let dir = tempfile::Builder::new()
+        .prefix("youtui-subtitles-")
+        .tempdir()
+        .context("Failed to create a directory for subtitles")?;
+    let args = [
+        "--no-simulate",
+        "--write-subs",
+        "--write-auto-subs",
+        "--sub-langs",
+        language,
+        "--sub-format",
+        "vtt",
+        "--output",
+    ]
+    .map(str::to_string)
+    .into_iter()
+    .chain([dir.path().join("%(id)s.%(ext)s").display().to_string()])
+    .collect::<Vec<_>>();
+    // The subtitle file is written after the metadata is printed.
+    let metadata = search::fetch_video_metadata_and_wait(video_id, &args, cancelled)?;
+    read_transcript(dir.path(), &metadata)
+}
+
+/// The cues of the `.vtt` file yt-dlp wrote into `dir`, if any, with the
+/// languages listed in `metadata`.
+fn read_transcript(dir: &Path, metadata: &Value) -> Result<Transcript> {
+    let vtt = fs::read_dir(dir)
+        .context("Failed to read the subtitle directory")?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .find(|path| path.extension().is_some_and(|ext| ext == "vtt"));
+    let cues = match vtt {
+        Some(path) => parse_vtt(&fs::read_to_string(&path).context("Failed to read subtitles")?),
+        None => Vec::new(),
+    };
+    Ok(Transcript {
+        cues,
+        languages: parse_languages(metadata),
+    })
+}
+
+/// The languages in yt-dlp's `subtitles` and `automatic_captions`. A
+/// language with uploaded subtitles is listed once, as uploaded, since
+/// yt-dlp prefers those.
+fn parse_languages(metadata: &Value) -> Vec<Language> {
+    let listed = |key: &str, automatic: bool| {
+        let mut languages: Vec<Language> = metadata
+            .get(key)
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+            .filter(|(code, _)| code.as_str() != "live_chat")
+            .map(|(code, formats)| Language {
+                code: code.clone(),
+                name: formats
+                    .get(0)
+                    .and_then(|format| format.get("name"))
+                    .and_then(Value::as_str)
+                    .unwrap_or(code)
+                    .to_string(),
+                automatic,
+            })
+            .collect();
+        languages.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.code.cmp(&b.code)));
+        languages
+    };
+    let mut languages = listed("subtitles", false);
+    let automatic: Vec<Language> = listed("automatic_captions", true)
+        .into_iter()
+        .filter(|language| {
+            !languages
+                .iter()
+                .any(|uploaded| uploaded.code == language.code)
+        })
+        .collect();
+    languages.extend(automatic);
+    languages
+}
+
+/// The cues of a WebVTT file, with styling tags removed. YouTube's automatic
+/// captions roll: each cue repeats the line before it, so a first line equal
+/// to the previous cue's last line is dropped.
+pub fn parse_vtt(vtt: &str) -> Vec<Cue> {
+    let mut cues = Vec::new();
+    let mut previous_line = String::new();
+    for block in vtt.replace("\r\n", "\n").split("\n\n") {
+        // The header, NOTE and STYLE blocks have no timing line. A cue may
+        // have an identifier above its timing.
+        let mut lines = block.lines().skip_while(|line| !line.contains("-->"));
+        let Some((start, end)) = lines.next().and_then(parse_timing) else {
+            continue;
+        };
+        let mut text: Vec<String> = lines
+            .map(|line| strip_tags(line).trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect();
+        let last = text.last().cloned();
+        if text.first() == Some(&previous_line) {
+            text.remove(0);
+        }
+        if let Some(last) = last {
+            previous_line = last;
+        }
+        if !text.is_empty() {
+            cues.push(Cue {
+                start,
+                end,
+                text: text.join(" "),
+            });
+        }
+    }
+    cues
+}
+
+/// "00:01:02.500 --> 00:01:04.000 align:start" -> (62.5, 64.0).
+fn parse_timing(line: &str) -> Option<(f64, f64)> {
+    let (start, rest) = line.split_once("-->")?;
+    let end = rest.split_whitespace().next()?;
+    let (start, end) = (parse_timestamp(start.trim())?, parse_timestamp(end)?);
+    (end > start).then_some((start, end))
+}
+
+/// "01:02:03.500" or "02:03.500" in seconds.
+fn parse_timestamp(timestamp: &str) -> Option<f64> {
+    let mut seconds = 0.0;
+    for part in timestamp.split(':') {
+        let value: f64 = part.parse().ok()?;
+        if !value.is_finite() || value < 0.0 {
+            return None;
+        }
+        seconds = seconds * 60.0 + value;
+    }
+    Some(seconds)
+}
+
+/// Remove `<c>`, `<i>` and inline `<00:00:01.000>` timing tags and decode
+/// the entities WebVTT allows.
+fn strip_tags(line: &str) -> String {
+    let mut text = String::with_capacity(line.len());
+    let mut in_tag = false;
+    for c in line.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SubtitleState {
+    Loading,
+    Loaded(Transcript),
+    Failed(String),
+}
+
+/// The subtitles of the playing track in the chosen language. They are
+/// only read while something shows them, and reading starts over when the
+/// track or the language changes.
+pub struct Subtitles {
+    /// The video and language `state` describes.
+    key: Option<(String, String)>,
+    state: SubtitleState,
+    fetch: Option<Job<Transcript>>,
+    fetch_subtitles: FetchSubtitles,
+}
+
+impl Default for Subtitles {
+    fn default() -> Self {
+        Self::with_fetcher(fetch_subtitles)
+    }
+}
+
+impl Subtitles {
+    pub(crate) fn with_fetcher(fetch_subtitles: FetchSubtitles) -> Self {
+        Self {
+            key: None,
+            state: SubtitleState::Loading,
+            fetch: None,
+            fetch_subtitles,
+        }
+    }
+
+    /// The subtitles for the track they were last updated for.
+    pub fn state(&self) -> Option<&SubtitleState> {
+        self.key.as_ref().map(|_| &self.state)
+    }
+
+    pub fn transcript(&self) -> Option<&Transcript> {
+        match self.state() {
+            Some(SubtitleState::Loaded(transcript)) => Some(transcript),
+            _ => None,
+        }
+    }
+
+    /// Follow the playing `video_id`, reading its subtitles in `language`
+    /// when they are `wanted` and not read yet, and pick up a finished read.
+    /// Returns whether the subtitles changed.
+    pub fn update(&mut self, video_id: Option<&str>, language: &str, wanted: bool) -> bool {
+        let current = self
+            .key
+            .as_ref()
+            .is_some_and(|(id, lang)| Some(id.as_str()) == video_id && lang == language);
+        if !current {
+            let had_subtitles = self.key.take().is_some();
+            self.fetch = None;
+            let Some(video_id) = video_id.filter(|_| wanted) else {
+                return had_subtitles;
+            };
+            self.key = Some((video_id.to_string(), language.to_string()));
+            self.state = match self.spawn_fetch(video_id, language) {
+                Ok(fetch) => {
+                    self.fetch = Some(fetch);
+                    SubtitleState::Loading
+                }
+                Err(error) => SubtitleState::Failed(error.to_string()),
+            };
+            return true;
+        }
+
+        let Some(result) = self.fetch.as_ref().and_then(Job::poll) else {
+            return false;
+        };
+        self.fetch = None;
+        self.state = match result {
+            Ok(transcript) => SubtitleState::Loaded(transcript),
+            Err(error) => SubtitleState::Failed(error.to_string()),
+        };
+        true
+    }
+
+    fn spawn_fetch(&self, video_id: &str, language: &str) -> Result<Job<Transcript>> {
+        let fetch_subtitles = self.fetch_subtitles;
+        let video_id = video_id.to_string();
+        let language = language.to_string();
+        Job::spawn("subtitle", move |cancelled| {
+            fetch_subtitles(&video_id, &language, cancelled)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::worker::wait_until;
+
+    fn cue(start: f64, end: f64, text: &str) -> Cue {
+        Cue {
+            start,
+            end,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn vtt_cues_are_parsed_without_tags_or_metadata_blocks() {
+        let vtt = "WEBVTT\r\nKind: captions\r\nLanguage: en\r\n\r\n\
+            NOTE written by hand\r\n\r\n\
+            STYLE\r\n::cue { color: yellow }\r\n\r\n\
+            intro\r\n00:00:01.000 --> 00:00:03.500 align:start position:0%\r\n\
+            <i>Hello</i> &amp; welcome\r\nto the show\r\n\r\n\
+            01:02.250 --> 01:04.000\r\nTom &lt;3 Jerry\r\n\r\n\
+            00:02:00.000 --> 00:01:00.000\r\nends before it starts\r\n";
+
+        assert_eq!(
+            parse_vtt(vtt),
+            [
+                cue(1.0, 3.5, "Hello & welcome to the show"),
+                cue(62.25, 64.0, "Tom <3 Jerry"),
+            ]
+        );
+    }
+
+    #[test]
+    fn rolling_automatic_captions_keep_each_line_once() {
+        let vtt = "WEBVTT\n\n\
+            00:00:00.000 --> 00:00:02.310 align:start position:0%\n \n\
+            so<00:00:00.480><c> today</c><00:00:00.960><c> we</c>\n\n\
+            00:00:02.310 --> 00:00:02.320 align:start position:0%\n\
+            so today we\n \n\n\
+            00:00:02.320 --> 00:00:04.000 align:start position:0%\n\
+            so today we\n\
+            are<00:00:02.800><c> cooking</c>\n";
+
+        assert_eq!(
+            parse_vtt(vtt),
+            [cue(0.0, 2.31, "so today we"), cue(2.32, 4.0, "are cooking"),]
+        );
+    }
+
+    #[test]
+    fn the_cue_at_a_position_leaves_gaps_empty() {
+        let transcript = Transcript {
+            cues: vec![cue(1.0, 3.0, "one"), cue(5.0, 8.0, "two")],
+            languages: Vec::new(),
+        };
+        assert_eq!(transcript.cue_at(0.5), None);
+        assert_eq!(transcript.cue_at(1.0), Some(0));
+        assert_eq!(transcript.cue_at(4.0), None);
+        assert_eq!(transcript.cue_at(7.9), Some(1));
+        assert_eq!(transcript.cue_at(8.0), None);
+        assert_eq!(transcript.line_at(0.5), 0);
+        assert_eq!(transcript.line_at(4.0), 0);
+        assert_eq!(transcript.line_at(9.0), 1);
+    }
+
+    #[test]
+    fn uploaded_languages_come_before_automatic_captions() {
+        let metadata = serde_json::json!({
+            "subtitles": {
+                "fr": [{"ext": "vtt", "name": "French"}],
+                "en": [{"ext": "vtt", "name": "English"}],
+                "live_chat": [{"ext": "json"}]
+            },
+            "automatic_captions": {
+                "en": [{"ext": "vtt", "name": "English"}],
+                "de": [{"ext": "vtt", "name": "German"}],
+                "en-orig": [{"ext": "vtt", "name": "English (Original)"}]
+            }
+        });
+        let language = |code: &str, name: &str, automatic| Language {
+            code: code.to_string(),
+            name: name.to_string(),
+            automatic,
+        };
+
+        assert_eq!(
+            parse_languages(&metadata),
+            [
+                language("en", "English", false),
+                language("fr", "French", false),
+                language("en-orig", "English (Original)", true),
+                language("de", "German", true),
+            ]
+        );
+    }
+
+    #[test]
+    fn transcripts_are_read_from_the_subtitle_file_yt_dlp_wrote() {
+        let dir = tempfile::tempdir().unwrap();
+        let metadata = serde_json::json!({
+            "subtitles": {"en": [{"ext": "vtt", "name": "English"}]}
+        });
+        assert!(
+            read_transcript(dir.path(), &metadata)
+                .unwrap()
+                .cues
+                .is_empty()
+        );
+
+        fs::write(dir.path().join("abcdefghijk.en.vtt.part"), "WEBVTT\n").unwrap();
+        fs::write(
+            dir.path().join("abcdefghijk.en.vtt"),
+            "WEBVTT\n\n00:00:01.000 --> 00:00:02.500\nHello there\n",
+        )
+        .unwrap();
+        let transcript = read_transcript(dir.path(), &metadata).unwrap();
+        assert_eq!(transcript.cues, [cue(1.0, 2.5, "Hello there")]);
+        assert_eq!(transcript.languages.len(), 1);
+        assert_eq!(transcript.languages[0].code, "en");
+    }
+
+    fn wait_for_subtitles(subtitles: &mut Subtitles, video_id: &str, language: &str) {
+        wait_until("subtitles never arrived", || {
+            subtitles.update(Some(video_id), language, true);
+            subtitles.state() != Some(&SubtitleState::Loading)
+        });
+    }
+
+    #[test]
+    fn subtitles_are_read_when_wanted_and_again_for_a_new_track_or_language() {
+        fn canned(video_id: &str, language: &str, _: &AtomicBool) -> Result<Transcript> {
+            if language == "xx" {
+                bail!("no {language} subtitles");
+            }
+            Ok(Transcript {
+                cues: vec![cue(0.0, 1.0, &format!("{video_id} in {language}"))],
+                languages: Vec::new(),
+            })
+        }
+        let mut subtitles = Subtitles::with_fetcher(canned);
+
+        assert!(!subtitles.update(Some("a"), "en", false));
+        assert_eq!(subtitles.state(), None);
+
+        assert!(subtitles.update(Some("a"), "en", true));
+        wait_for_subtitles(&mut subtitles, "a", "en");
+        assert_eq!(subtitles.transcript().unwrap().cues[0].text, "a in en");
+        // Loaded subtitles stay while nothing shows them.
+        assert!(!subtitles.update(Some("a"), "en", false));
+        assert!(subtitles.transcript().is_some());
+
+        subtitles.update(Some("a"), "de", true);
+        wait_for_subtitles(&mut subtitles, "a", "de");
+        assert_eq!(subtitles.transcript().unwrap().cues[0].text, "a in de");
+
+        subtitles.update(Some("a"), "xx", true);
+        wait_for_subtitles(&mut subtitles, "a", "xx");
+        assert_eq!(
+            subtitles.state(),
+            Some(&SubtitleState::Failed("no xx subtitles".to_string()))
+        );
+
+        assert!(subtitles.update(None, "xx", true));
+        assert_eq!(subtitles.state(), None);
+    }
+}
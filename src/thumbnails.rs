@@ -0,0 +1,398 @@
+//! Thumbnail previews for search results. Images are downloaded with curl and
+//! decoded on a worker thread, then kept in a size-capped disk cache, so
+//! moving through the results never waits on the network.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::AtomicBool;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result, bail};
+use image::DynamicImage;
+use image::imageops::FilterType;
+use ratatui::layout::Size;
+use ratatui_image::Resize;
+use ratatui_image::picker::Picker;
+use ratatui_image::protocol::Protocol;
+
+use crate::search;
+use crate::video::Frame;
+use crate::worker::{self, Job};
+
+/// The disk cache is trimmed back to this size, least recently used first.
+/// A 320x180 thumbnail is around 15 KB.
+const DISK_CACHE_BYTES: u64 = 32 * 1024 * 1024;
+
+/// Decoded thumbnails kept in memory for going back over recent results.
+const MEMORY_CACHE_ENTRIES: usize = 64;
+
+pub(crate) type FetchImage = fn(&str, &Path, &AtomicBool) -> Result<DynamicImage>;
+
+/// What the preview pane shows for a result.
+pub enum Preview<'a> {
+    Loading,
+    Unavailable,
+    Pixels(&'a Protocol),
+    Blocks(&'a Frame),
+}
+
+pub struct Thumbnails {
+    dir: PathBuf,
+    decoded: HashMap<String, DynamicImage>,
+    /// Keys of `decoded`, oldest first.
+    decoded_order: VecDeque<String>,
+    /// Videos whose thumbnail could not be fetched; not retried this session.
+    failed: HashSet<String>,
+    fetch: Option<Fetch>,
+    shown: Option<Shown>,
+    fetch_image: FetchImage,
+}
+
+struct Fetch {
+    video_id: String,
+    job: Job<DynamicImage>,
+}
+
+/// The thumbnail on screen, already scaled for the pane.
+struct Shown {
+    video_id: String,
+    size: Size,
+    pixels: bool,
+    image: ShownImage,
+}
+
+enum ShownImage {
+    Pixels(Protocol),
+    Blocks(Frame),
+}
+
+impl Default for Thumbnails {
+    fn default() -> Self {
+        let dir = dirs::cache_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("youtui")
+            .join("thumbnails");
+        Self::with_fetcher(dir, fetch_thumbnail)
+    }
+}
+
+impl Thumbnails {
+    pub(crate) fn with_fetcher(dir: PathBuf, fetch_image: FetchImage) -> Self {
+        Self {
+            dir,
+            decoded: HashMap::new(),
+            decoded_order: VecDeque::new(),
+            failed: HashSet::new(),
+            fetch: None,
+            shown: None,
+            fetch_image,
+        }
+    }
+
+    /// Get the thumbnail for `video_id` ready to draw in a pane of `size`
+    /// cells, with the graphics protocol when a picker is given and in
+    /// half-block cells otherwise. Only one fetch runs at a time, so a quick
+    /// scroll skips the thumbnails it passes over, and hiding the pane
+    /// cancels it. Returns whether the preview changed.
+    pub fn update(&mut self, video_id: Option<&str>, size: Size, picker: Option<&Picker>) -> bool {
+        let mut changed = self.receive();
+
+        let Some(video_id) = video_id.filter(|_| size.width > 0 && size.height > 0) else {
+            self.fetch = None;
+            return self.shown.take().is_some() || changed;
+        };
+        let pixels = picker.is_some();
+        let up_to_date = self.shown.as_ref().is_some_and(|shown| {
+            shown.video_id == video_id && shown.size == size && shown.pixels == pixels
+        });
+        if up_to_date {
+            return changed;
+        }
+
+        if let Some(image) = self.decoded.get(video_id) {
+            let image = match picker {
+                Some(picker) => picker
+                    .new_protocol(image.clone(), size, Resize::Fit(None))
+                    .ok()
+                    .map(ShownImage::Pixels),
+                None => Some(ShownImage::Blocks(half_blocks(image, size))),
+            };
+            self.shown = image.map(|image| Shown {
+                video_id: video_id.to_string(),
+                size,
+                pixels,
+                image,
+            });
+            return true;
+        }
+
+        if self.shown.take().is_some() {
+            changed = true;
+        }
+        if self.fetch.is_none() && !self.failed.contains(video_id) {
+            self.spawn_fetch(video_id);
+        }
+        changed
+    }
+
+    pub fn preview(&self, video_id: &str) -> Preview<'_> {
+        match &self.shown {
+            Some(shown) if shown.video_id == video_id => match &shown.image {
+                ShownImage::Pixels(protocol) => Preview::Pixels(protocol),
+                ShownImage::Blocks(frame) => Preview::Blocks(frame),
+            },
+            _ if self.failed.contains(video_id) => Preview::Unavailable,
+            _ => Preview::Loading,
+        }
+    }
+
+    /// Collect a finished fetch. Returns whether it failed, since a failure
+    /// changes what the pane shows straight away.
+    fn receive(&mut self) -> bool {
+        let Some(fetch) = &self.fetch else {
+            return false;
+        };
+        let Some(result) = fetch.job.poll() else {
+            return false;
+        };
+        let video_id = self.fetch.take().expect("fetch checked").video_id;
+        match result {
+            Ok(image) => {
+                if self.decoded_order.len() >= MEMORY_CACHE_ENTRIES
+                    && let Some(oldest) = self.decoded_order.pop_front()
+                {
+                    self.decoded.remove(&oldest);
+                }
+                self.decoded_order.push_back(video_id.clone());
+                self.decoded.insert(video_id, image);
+                false
+            }
+            Err(_) => {
+                self.failed.insert(video_id);
+                true
+            }
+        }
+    }
+
+    fn spawn_fetch(&mut self, video_id: &str) {
+        let fetch_image = self.fetch_image;
+        let dir = self.dir.clone();
+        let worker_video_id = video_id.to_string();
+        let job = Job::spawn("thumbnail", move |cancelled| {
+            fetch_image(&worker_video_id, &dir, cancelled)
+        });
+        match job {
+            Ok(job) => {
+                self.fetch = Some(Fetch {
+                    video_id: video_id.to_string(),
+                    job,
+                });
+            }
+            Err(_) => {
+                self.failed.insert(video_id.to_string());
+            }
+        }
+    }
+}
+
+/// The thumbnail scaled to fit `size` cells at two pixels per cell.
+fn half_blocks(image: &DynamicImage, size: Size) -> Frame {
+    let scaled = image
+        .resize(
+            u32::from(size.width),
+            u32::from(size.height) * 2,
+            FilterType::Triangle,
+        )
+        .to_rgb8();
+    Frame {
+        width: scaled.width() as u16,
+        height_px: scaled.height() as u16,
+        rgb: scaled.into_raw(),
+    }
+}
+
+/// Read a thumbnail from the cache in `dir`, downloading it first when it is
+/// not there.
+fn fetch_thumbnail(video_id: &str, dir: &Path, cancelled: &AtomicBool) -> Result<DynamicImage> {
+    if !search::is_video_id(video_id) {
+        bail!("\"{video_id}\" is not a video ID");
+    }
+    let path = dir.join(format!("{video_id}.jpg"));
+    if path.is_file() {
+        // The modification time doubles as the last use for pruning.
+        if let Ok(file) = File::options().append(true).open(&path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+    } else {
+        fs::create_dir_all(dir).context("Could not create the thumbnail cache")?;
+        download(video_id, &path, cancelled)?;
+        prune(dir, DISK_CACHE_BYTES)?;
+    }
+    let bytes = fs::read(&path)?;
+    image::load_from_memory(&bytes).context("Could not decode the thumbnail")
+}
+
+fn download(video_id: &str, path: &Path, cancelled: &AtomicBool) -> Result<()> {
+    let partial = path.with_extension("part");
+    let mut curl = Command::new("curl");
+    curl.args(["--fail", "--silent", "--show-error", "--location"])
+        .args(["--max-time", "15", "--output"])
+        .arg(&partial)
+        .arg(format!("https://i.ytimg.com/vi/{video_id}/mqdefault.jpg"));
+    let output = worker::run_curl(curl, cancelled);
+    if !output.as_ref().is_ok_and(|output| output.status.success()) {
+        let _ = fs::remove_file(&partial);
+    }
+    let output = output?;
+    if !output.status.success() {
+        bail!(
+            "curl failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    fs::rename(&partial, path).context("Could not save the thumbnail")
+}
+
+/// Delete the least recently used files in `dir` until it holds at most
+/// `limit` bytes.
+fn prune(dir: &Path, limit: u64) -> Result<()> {
+    let mut files: Vec<(SystemTime, u64, PathBuf)> = fs::read_dir(dir)?
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let metadata = entry.metadata().ok()?;
+            metadata.is_file().then(|| {
+                let used = metadata.modified().unwrap_or(UNIX_EPOCH);
+                (used, metadata.len(), entry.path())
+            })
+        })
+        .collect();
+    let mut total: u64 = files.iter().map(|(_, len, _)| len).sum();
+    files.sort();
+    for (_, len, path) in files {
+        if total <= limit {
+            break;
+        }
+        if fs::remove_file(&path).is_ok() {
+            total -= len;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+    use std::time::Duration;
+
+    use image::RgbImage;
+
+    use super::*;
+    use crate::worker::wait_until;
+
+    fn gray_image(video_id: &str, _: &Path, _: &AtomicBool) -> Result<DynamicImage> {
+        if video_id == "broken" {
+            bail!("no thumbnail");
+        }
+        Ok(DynamicImage::ImageRgb8(RgbImage::from_pixel(
+            32,
+            18,
+            image::Rgb([128, 128, 128]),
+        )))
+    }
+
+    /// Call `update` until the fetch it starts has been collected.
+    fn update_after_fetch(thumbnails: &mut Thumbnails, video_id: &str, size: Size) {
+        wait_until("the thumbnail never arrived", || {
+            thumbnails.update(Some(video_id), size, None);
+            thumbnails.fetch.is_none()
+        });
+    }
+
+    #[test]
+    fn thumbnails_load_in_the_background_and_fit_the_pane() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut thumbnails = Thumbnails::with_fetcher(dir.path().to_path_buf(), gray_image);
+        let size = Size::new(8, 4);
+
+        thumbnails.update(Some("abc"), size, None);
+        assert!(matches!(thumbnails.preview("abc"), Preview::Loading));
+
+        update_after_fetch(&mut thumbnails, "abc", size);
+        let Preview::Blocks(frame) = thumbnails.preview("abc") else {
+            panic!("thumbnail not ready");
+        };
+        // 16:9 into 8x8 pixels keeps the aspect ratio.
+        assert_eq!((frame.width, frame.height_px), (8, 5));
+        assert!(matches!(thumbnails.preview("other"), Preview::Loading));
+
+        update_after_fetch(&mut thumbnails, "broken", size);
+        assert!(matches!(thumbnails.preview("broken"), Preview::Unavailable));
+        // Going back needs no second fetch.
+        assert!(thumbnails.update(Some("abc"), size, None));
+        assert!(thumbnails.fetch.is_none());
+        assert!(matches!(thumbnails.preview("abc"), Preview::Blocks(_)));
+    }
+
+    #[test]
+    fn hiding_the_pane_cancels_the_fetch() {
+        static CANCELLED: AtomicBool = AtomicBool::new(false);
+        fn slow(_: &str, _: &Path, cancelled: &AtomicBool) -> Result<DynamicImage> {
+            wait_until("the fetch was not cancelled", || {
+                cancelled.load(Ordering::Relaxed)
+            });
+            CANCELLED.store(true, Ordering::Relaxed);
+            bail!("cancelled")
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut thumbnails = Thumbnails::with_fetcher(dir.path().to_path_buf(), slow);
+        thumbnails.update(Some("abc"), Size::new(8, 4), None);
+        assert!(thumbnails.fetch.is_some());
+
+        thumbnails.update(None, Size::new(8, 4), None);
+        assert!(thumbnails.fetch.is_none());
+        wait_until("the fetch was not cancelled", || {
+            CANCELLED.load(Ordering::Relaxed)
+        });
+    }
+
+    #[test]
+    fn pruning_drops_the_least_recently_used_files() {
+        let dir = tempfile::tempdir().unwrap();
+        for (name, age) in [("old.jpg", 30), ("mid.jpg", 20), ("new.jpg", 10)] {
+            let path = dir.path().join(name);
+            fs::write(&path, [0; 100]).unwrap();
+            File::options()
+                .append(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(SystemTime::now() - Duration::from_secs(age))
+                .unwrap();
+        }
+
+        prune(dir.path(), 250).unwrap();
+
+        assert!(!dir.path().join("old.jpg").exists());
+        assert!(dir.path().join("mid.jpg").exists());
+        assert!(dir.path().join("new.jpg").exists());
+    }
+
+    #[test]
+    fn cached_thumbnails_are_read_without_downloading() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut encoded = std::io::Cursor::new(Vec::new());
+        let cancelled = AtomicBool::new(false);
+        gray_image("abcdefghijk", dir.path(), &cancelled)
+            .unwrap()
+            .write_to(&mut encoded, image::ImageFormat::Png)
+            .unwrap();
+        fs::write(dir.path().join("abcdefghijk.jpg"), encoded.into_inner()).unwrap();
+
+        let image = fetch_thumbnail("abcdefghijk", dir.path(), &cancelled).unwrap();
+        assert_eq!((image.width(), image.height()), (32, 18));
+        assert!(fetch_thumbnail("../abcdefghi", dir.path(), &cancelled).is_err());
+        assert!(fetch_thumbnail("abc", dir.path(), &cancelled).is_err());
+    }
+}
@@ -1,7 +1,27 @@
+use std::cell::RefCell;
+use std::time::Instant;
+
+use ratatui::layout::{Margin, Position, Rect};
+
 use crate::config::Config;
+use crate::details::DetailsView;
+use crate::keymap::Keymap;
+use crate::player::supports_background_playback;
 use crate::player_manager::PlayerManager;
-use crate::queue::Queue;
-use crate::search::SearchResult;
+use crate::playlists::{PlaylistStore, format_seconds};
+use crate::queue::{Queue, RepeatMode};
+use crate::resume::{OFFER_WINDOW, ResumePositions};
+use crate::search::{SearchFilters, SearchResult, collection_url};
+use crate::search_history::SearchHistory;
+use crate::session::QueueSnapshot;
+use crate::subtitles::{Subtitles, Transcript};
+use crate::thumbnails::Thumbnails;
+use crate::ui::theme::Theme;
+use crate::watch_history::WatchHistory;
+
+/// Going back within this many seconds of a track's start plays the one
+/// before it instead of restarting.
+const RESTART_THRESHOLD: f64 = 3.0;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum InputMode {
@@ -14,6 +34,8 @@ pub enum FocusedPanel {
     SearchBar,
     Results,
     Queue,
+    Playlists,
+    History,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -22,24 +44,158 @@ pub enum SettingsField {
     ResultsPerPage,
     SeekStep,
     SeekStepLarge,
+    ResumeMargin,
     CustomFormat,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlaylistPromptKind {
+    /// Name for a new playlist made from the queue.
+    Create,
+    Rename,
+    /// Path of an M3U/M3U8 or JSON file to import.
+    Import,
+    /// Path to export the selected playlist to.
+    Export,
+    /// Yes/no confirmation; the input is unused.
+    Delete,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaylistPrompt {
+    pub kind: PlaylistPromptKind,
+    pub input: String,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum AppAction {
     None,
     Play(usize),
+    /// Play a result and seek to the given position once it has loaded.
+    PlayFrom(usize, f64),
+    /// Resolve a video URL or ID typed into the search bar.
+    OpenVideo {
+        video_id: String,
+        start: Option<f64>,
+        /// Text search to run instead if a bare ID turns out not to exist.
+        fallback_query: Option<String>,
+    },
     NewSearch(String),
     FetchNextPage(usize),
     PrefetchNextPage(usize),
+    /// Fetch the rest of the current playlist or channel and queue it all.
+    EnqueueAll,
     CancelSearch,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SearchPhase {
     Initial,
-    RequestedPage { target_page: usize },
-    Prefetch { target_page: usize },
+    RequestedPage {
+        target_page: usize,
+    },
+    Prefetch {
+        target_page: usize,
+    },
+    /// Reading a whole playlist or channel for "enqueue all".
+    FetchingAll,
+    /// Resolving a single video URL or ID.
+    LookingUpVideo,
+}
+
+/// The search filter modal: edits a copy that only replaces
+/// `App::search_filters` when applied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterEditor {
+    pub draft: SearchFilters,
+    /// Row: 0 sort, 1 duration, 2 upload date, 3 live.
+    pub selected: usize,
+}
+
+/// Ctrl+R in the search bar: a fuzzy search through past queries.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HistorySearch {
+    pub pattern: String,
+    /// Index into the current matches for `pattern`.
+    pub selected: usize,
+}
+
+/// The transcript view: every subtitle line of the playing track.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TranscriptView {
+    /// The selected cue; `None` follows the line being spoken until the
+    /// selection is moved.
+    pub selected: Option<usize>,
+    pub query: String,
+    /// Typing into `query` rather than moving through the lines.
+    pub searching: bool,
+}
+
+impl TranscriptView {
+    /// The selected line of `transcript`, or else the one being spoken at
+    /// `position`.
+    pub fn line(&self, transcript: &Transcript, position: f64) -> usize {
+        self.selected
+            .unwrap_or_else(|| transcript.line_at(position))
+            .min(transcript.cues.len().saturating_sub(1))
+    }
+}
+
+/// Queue grab mode: the marked tracks follow the cursor until dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueueGrab {
+    /// Nothing was marked, so the selected track was marked just to carry it
+    /// and is unmarked again on drop.
+    pub temporary_mark: bool,
+}
+
+/// A saved position for the track that just loaded, offered until the user
+/// takes it or playback moves on without it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResumeOffer {
+    pub video_id: String,
+    pub position: f64,
+}
+
+/// A list panel as the last frame drew it, so a click can be traced back to
+/// the item under it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ListArea {
+    pub panel: FocusedPanel,
+    /// The whole panel, border included.
+    pub area: Rect,
+    /// The first item shown.
+    pub offset: usize,
+    /// Lines per item.
+    pub item_height: u16,
+    /// Items in the list; zero while it only shows a placeholder.
+    pub len: usize,
+}
+
+impl ListArea {
+    /// The item drawn at `position`, if it is inside the border.
+    pub fn item_at(&self, position: Position) -> Option<usize> {
+        let rows = self.area.inner(Margin::new(1, 1));
+        if !rows.contains(position) {
+            return None;
+        }
+        let index = self.offset + usize::from((position.y - rows.y) / self.item_height.max(1));
+        (index < self.len).then_some(index)
+    }
+}
+
+/// The parts of the last frame that input acts on. Rendering only borrows
+/// the app, so `render_ui` records them through a `RefCell`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScreenAreas {
+    pub search_bar: Option<Rect>,
+    pub lists: Vec<ListArea>,
+    /// The cells the progress bar's thumb travels across.
+    pub progress_bar: Option<Rect>,
+    /// Where the selected result's thumbnail goes.
+    pub thumbnail: Option<Rect>,
+    /// How many lines the details view can scroll before its text runs out.
+    pub details_scroll_limit: u16,
 }
 
 pub struct App {
@@ -59,6 +215,7 @@ pub struct App {
     pub player_manager: Option<PlayerManager>,
     pub queue: Queue,
     pub queue_selected_index: usize,
+    pub queue_grab: Option<QueueGrab>,
     pub focused_panel: FocusedPanel,
     pub loading: bool,
     pub search_phase: Option<SearchPhase>,
@@ -68,8 +225,46 @@ pub struct App {
     pub settings_text_input: Option<String>,
     pub status_message: Option<String>,
     pub config: Config,
+    /// Built from `config.keys`, which was validated when the config loaded.
+    pub keymap: Keymap,
+    pub theme: Theme,
     pub video_view: bool,
     pub video: crate::video::VideoState,
+    pub thumbnails: Thumbnails,
+    /// Queue saved by the previous run, awaiting the user's restore decision.
+    pub restore_prompt: Option<QueueSnapshot>,
+    pub playlists: PlaylistStore,
+    pub playlist_selected_index: usize,
+    pub playlist_prompt: Option<PlaylistPrompt>,
+    /// Applied to every new text search until changed.
+    pub search_filters: SearchFilters,
+    pub filter_editor: Option<FilterEditor>,
+    pub details: Option<DetailsView>,
+    /// The chapter list popup, with the selected chapter.
+    pub chapter_picker: Option<usize>,
+    pub subtitles: Subtitles,
+    pub transcript: Option<TranscriptView>,
+    /// The subtitle language popup, with the selected language.
+    pub language_picker: Option<usize>,
+    pub search_history: SearchHistory,
+    /// Which past query Up/Down has put in the search bar, and the text that
+    /// was there before recalling began.
+    pub history_recall: Option<(usize, String)>,
+    pub history_search: Option<HistorySearch>,
+    pub watch_history: WatchHistory,
+    pub watch_history_selected_index: usize,
+    pub resume_positions: ResumePositions,
+    pub resume_offer: Option<ResumeOffer>,
+    /// The video whose position `resume_positions` is following.
+    resume_video_id: Option<String>,
+    /// Whether the terminal has focus, as far as it reports focus changes.
+    pub terminal_focused: bool,
+    pub screen_areas: RefCell<ScreenAreas>,
+    /// When and where the last left click landed, to spot double clicks.
+    pub last_click: Option<(Instant, FocusedPanel, usize)>,
+    /// A queue track that began playing since the runner last looked, for
+    /// desktop notifications.
+    pub(crate) started_track: Option<SearchResult>,
 }
 
 impl App {
@@ -97,6 +292,7 @@ impl App {
             player_manager: None,
             queue: Queue::new(),
             queue_selected_index: 0,
+            queue_grab: None,
             focused_panel: FocusedPanel::Results,
             loading: false,
             search_phase: None,
@@ -105,9 +301,35 @@ impl App {
             settings_editing: None,
             settings_text_input: None,
             status_message: None,
+            keymap: config.keymap().unwrap_or_default(),
+            theme: Theme::default(),
             config,
             video_view: false,
             video: crate::video::VideoState::new(),
+            thumbnails: Thumbnails::default(),
+            restore_prompt: None,
+            playlists: PlaylistStore::default(),
+            playlist_selected_index: 0,
+            playlist_prompt: None,
+            search_filters: SearchFilters::default(),
+            filter_editor: None,
+            details: None,
+            chapter_picker: None,
+            subtitles: Subtitles::default(),
+            transcript: None,
+            language_picker: None,
+            search_history: SearchHistory::default(),
+            history_recall: None,
+            history_search: None,
+            watch_history: WatchHistory::default(),
+            watch_history_selected_index: 0,
+            resume_positions: ResumePositions::default(),
+            resume_offer: None,
+            resume_video_id: None,
+            terminal_focused: true,
+            screen_areas: RefCell::default(),
+            last_click: None,
+            started_track: None,
         }
     }
 
@@ -150,83 +372,359 @@ impl App {
     }
 
     pub fn handle_next_video(&mut self, manual: bool) {
-        // Remove the currently playing track from front of queue
+        self.advance_queue(manual);
+
+        // Now play the new front of the queue (if any)
         if !self.queue.is_empty() {
-            self.queue.pop_front();
-            // Adjust selected index if needed
-            if self.queue_selected_index > 0 {
-                self.queue_selected_index -= 1;
+            // Manual 'n' press always auto-plays, automatic transitions respect setting
+            self.load_queue_front(manual || self.config.auto_play_queue);
+        } else {
+            // Do not retain an idle manager here: AppAction::Play treats the
+            // absence of a manager as the signal to start a fresh selection.
+            if let Some(mut player) = self.player_manager.take()
+                && let Err(error) = player.clear()
+            {
+                self.status_message = Some(format!("Playback stopped: {error}"));
             }
         }
+    }
 
-        // Now play the new front of the queue (if any)
-        if !self.queue.is_empty() {
-            if let Some(track) = self.queue.get(0) {
-                let url = format!("https://www.youtube.com/watch?v={}", track.id);
-                let title = track.title.clone();
-                let video_id = track.id.clone();
+    /// Restart the playing track once it is a few seconds in, otherwise go
+    /// back to the track played before it.
+    pub fn handle_previous_video(&mut self) {
+        let position = self
+            .player_manager
+            .as_ref()
+            .filter(|player| player.current_video_id.is_some())
+            .map(|player| player.status.time_pos);
+        if position.is_none_or(|position| position <= RESTART_THRESHOLD) && self.queue.previous() {
+            if self.queue.len() > 1 {
+                self.queue_selected_index += 1;
+            }
+            self.load_queue_front(true);
+        } else if position.is_some() {
+            let Some(player) = self.player_manager.as_mut() else {
+                return;
+            };
+            if let Err(error) = player.seek_absolute(0.0) {
+                self.player_manager = None;
+                self.status_message = Some(format!("Playback stopped: {error}"));
+            }
+        } else {
+            self.status_message = Some("No previous track".to_string());
+        }
+    }
+
+    /// Move the currently playing track off the front of the queue as the
+    /// repeat and shuffle modes say.
+    fn advance_queue(&mut self, manual: bool) {
+        // Repeat-one replays a track that ended; skipping still moves on.
+        if !manual && self.config.repeat == RepeatMode::One {
+            return;
+        }
+        if self
+            .queue
+            .advance(self.config.repeat, self.config.shuffle)
+            .is_some()
+            // Adjust selected index if needed
+            && self.queue_selected_index > 0
+        {
+            self.queue_selected_index -= 1;
+        }
+    }
+
+    /// Load the front of the queue into mpv, creating the player if needed.
+    /// With `autoplay` false the track is loaded paused.
+    pub(crate) fn load_queue_front(&mut self, autoplay: bool) {
+        let Some(track) = self.queue.get(0).cloned() else {
+            return;
+        };
+        let url = track.url();
+        let title = track.title.clone();
+        let video_id = track.id.clone();
+        let started = autoplay.then_some(track);
 
-                // Manual 'n' press always auto-plays, automatic transitions respect setting
-                let should_auto_play = manual || self.config.auto_play_queue;
+        if let Some(ref mut player) = self.player_manager {
+            let result = if autoplay {
+                player.play(&self.config, &url, &title, &video_id)
+            } else {
+                player.load_paused(&self.config, &url, &title, &video_id)
+            };
 
-                if let Some(ref mut player) = self.player_manager {
-                    let result = if should_auto_play {
-                        player.play(&self.config, &url, &title, &video_id)
+            if let Err(error) = result {
+                self.player_manager = None;
+                self.status_message = Some(format!("Playback stopped: {error}"));
+            } else {
+                self.status_message = None;
+                self.started_track = started;
+            }
+        } else {
+            // Create player manager if it doesn't exist
+            match PlayerManager::new(&self.config) {
+                Ok(mut pm) => {
+                    let result = if autoplay {
+                        pm.play(&self.config, &url, &title, &video_id)
                     } else {
-                        player.load_paused(&self.config, &url, &title, &video_id)
+                        pm.load_paused(&self.config, &url, &title, &video_id)
                     };
 
-                    if let Err(error) = result {
-                        self.player_manager = None;
-                        self.status_message = Some(format!("Playback stopped: {error}"));
-                    } else {
-                        self.status_message = None;
-                    }
-                } else {
-                    // Create player manager if it doesn't exist
-                    use crate::player_manager::PlayerManager;
-                    match PlayerManager::new(&self.config) {
-                        Ok(mut pm) => {
-                            let result = if should_auto_play {
-                                pm.play(&self.config, &url, &title, &video_id)
-                            } else {
-                                pm.load_paused(&self.config, &url, &title, &video_id)
-                            };
-
-                            match result {
-                                Ok(()) => {
-                                    self.player_manager = Some(pm);
-                                    self.status_message = None;
-                                }
-                                Err(error) => {
-                                    self.status_message =
-                                        Some(format!("Could not start playback: {error}"));
-                                }
-                            }
+                    match result {
+                        Ok(()) => {
+                            self.player_manager = Some(pm);
+                            self.status_message = None;
+                            self.started_track = started;
                         }
                         Err(error) => {
-                            self.status_message = Some(format!("Could not create player: {error}"));
+                            self.status_message =
+                                Some(format!("Could not start playback: {error}"));
                         }
                     }
                 }
+                Err(error) => {
+                    self.status_message = Some(format!("Could not create player: {error}"));
+                }
             }
-        } else {
-            // Do not retain an idle manager here: AppAction::Play treats the
-            // absence of a manager as the signal to start a fresh selection.
-            if let Some(mut player) = self.player_manager.take()
-                && let Err(error) = player.clear()
-            {
-                self.status_message = Some(format!("Playback stopped: {error}"));
+        }
+    }
+
+    /// The past query Ctrl+R currently points at.
+    pub fn history_search_match(&self) -> Option<&str> {
+        let search = self.history_search.as_ref()?;
+        let matches = self.search_history.fuzzy_matches(&search.pattern);
+        matches
+            .get(search.selected.min(matches.len().saturating_sub(1)))
+            .copied()
+    }
+
+    /// Keep the watch history in step with the player: open an entry when a
+    /// track starts playing, follow its position, and close it when the track
+    /// ends (`finished`) or is replaced.
+    pub(crate) fn record_watch_progress(&mut self, finished: bool) {
+        let Some(player) = self.player_manager.as_ref() else {
+            self.watch_history.stop(false);
+            return;
+        };
+        let Some(video_id) = player.current_video_id.as_deref() else {
+            self.watch_history.stop(false);
+            return;
+        };
+        let status = &player.status;
+
+        if !self.watch_history.is_recording(video_id) {
+            self.watch_history.stop(false);
+            // A track loaded paused (a restored session) counts once it plays.
+            if !finished && (status.paused || status.duration <= 0.0) {
+                return;
+            }
+            // Tracks played from outside the queue (the CLI, the remote) only
+            // have what mpv reports about them.
+            let track = self
+                .queue
+                .iter()
+                .find(|track| track.id == video_id)
+                .cloned()
+                .unwrap_or_else(|| SearchResult {
+                    title: if status.title.is_empty() {
+                        video_id.to_string()
+                    } else {
+                        status.title.clone()
+                    },
+                    duration: format_seconds(status.duration as u64),
+                    channel: String::new(),
+                    views: String::new(),
+                    published: String::new(),
+                    id: video_id.to_string(),
+                });
+            self.watch_history.start(track);
+        }
+        self.watch_history
+            .update_progress(status.time_pos, status.duration);
+        if finished {
+            self.watch_history.stop(true);
+        }
+    }
+
+    /// Remember how far the playing track got, offering a saved position when
+    /// a track loads from the start and forgetting it when the track ends.
+    pub(crate) fn record_resume_position(&mut self, finished: bool) {
+        let Some((video_id, status)) = self.player_manager.as_ref().and_then(|player| {
+            player
+                .current_video_id
+                .as_deref()
+                .map(|video_id| (video_id, &player.status))
+        }) else {
+            self.resume_video_id = None;
+            self.resume_offer = None;
+            return;
+        };
+        if finished {
+            self.resume_positions.forget(video_id);
+            self.resume_offer = None;
+            return;
+        }
+        // The position is meaningless until the track has loaded; by then a
+        // timestamp or restored-session seek has also been applied.
+        if status.duration <= 0.0 {
+            return;
+        }
+
+        if self.resume_video_id.as_deref() != Some(video_id) {
+            self.resume_video_id = Some(video_id.to_string());
+            self.resume_offer =
+                self.resume_positions
+                    .offer(video_id, status.time_pos)
+                    .map(|position| ResumeOffer {
+                        video_id: video_id.to_string(),
+                        position,
+                    });
+        }
+        // Keep the saved position while the offer is still open.
+        if self.resume_offer.is_some() {
+            if status.time_pos < OFFER_WINDOW {
+                return;
             }
+            self.resume_offer = None;
+        }
+        let margin = self.config.resume_margin as f64;
+        self.resume_positions
+            .update(video_id, status.time_pos, status.duration, margin);
+    }
+
+    /// The queue track the player is on, if it is playing one.
+    pub fn playing_track(&self) -> Option<&SearchResult> {
+        let video_id = self.player_manager.as_ref()?.current_video_id.as_deref()?;
+        self.queue.get(0).filter(|track| track.id == video_id)
+    }
+
+    /// Whether the results come from a playlist or channel URL rather than a
+    /// text search.
+    pub fn showing_collection(&self) -> bool {
+        collection_url(&self.query).is_some()
+    }
+
+    /// Append tracks to the queue, starting the front track if nothing is
+    /// playing. Returns how many tracks were added.
+    pub fn enqueue_tracks(&mut self, tracks: impl IntoIterator<Item = SearchResult>) -> usize {
+        let before = self.queue.len();
+        for track in tracks {
+            self.queue.push_back(track);
+        }
+        let added = self.queue.len() - before;
+
+        let background_playback =
+            supports_background_playback(self.config.player) && !self.config.download_mode;
+        let player_is_idle = self
+            .player_manager
+            .as_ref()
+            .is_none_or(|player| player.current_video_id.is_none());
+        if added > 0 && background_playback && player_is_idle {
+            self.load_queue_front(true);
+        }
+        added
+    }
+
+    /// Queue tracks right after the playing one, or at the front when
+    /// nothing plays, starting the first in that case. Returns how many
+    /// tracks were added.
+    pub fn play_next(&mut self, tracks: impl IntoIterator<Item = SearchResult>) -> usize {
+        let index = self.first_movable_queue_index();
+        let had_tracks = !self.queue.is_empty();
+        let mut added = 0;
+        for track in tracks {
+            self.queue.insert(index + added, track);
+            added += 1;
+        }
+        if had_tracks && self.queue_selected_index >= index {
+            self.queue_selected_index += added;
+        }
+
+        let background_playback =
+            supports_background_playback(self.config.player) && !self.config.download_mode;
+        if added > 0 && background_playback && index == 0 {
+            self.load_queue_front(true);
+        }
+        added
+    }
+
+    /// Append tracks to the queue without starting or interrupting playback.
+    pub fn add_to_queue(&mut self, tracks: impl IntoIterator<Item = SearchResult>) -> usize {
+        let before = self.queue.len();
+        for track in tracks {
+            self.queue.push_back(track);
+        }
+        self.queue.len() - before
+    }
+
+    /// The playing track holds the front of the queue; reordering starts
+    /// below it.
+    pub fn first_movable_queue_index(&self) -> usize {
+        let playing = self
+            .player_manager
+            .as_ref()
+            .is_some_and(|player| player.current_video_id.is_some());
+        usize::from(playing && !self.queue.is_empty())
+    }
+
+    /// The session as it should be persisted: the queue, its selection, and
+    /// the playback position when the player is on the front track.
+    pub fn queue_snapshot(&self) -> QueueSnapshot {
+        let front_id = self.queue.get(0).map(|track| track.id.as_str());
+        let time_pos = self
+            .player_manager
+            .as_ref()
+            .filter(|player| front_id.is_some() && player.current_video_id.as_deref() == front_id)
+            .map_or(0.0, |player| player.status.time_pos);
+
+        QueueSnapshot {
+            tracks: self.queue.iter().cloned().collect(),
+            selected_index: self.queue_selected_index,
+            time_pos,
+        }
+    }
+
+    /// Bring back a saved session and cue its front track, paused at the
+    /// saved position.
+    pub fn restore_queue(&mut self, snapshot: QueueSnapshot) {
+        let QueueSnapshot {
+            tracks,
+            selected_index,
+            time_pos,
+        } = snapshot;
+        let restored = tracks.len();
+        for track in tracks {
+            self.queue.push_back(track);
+        }
+        self.queue_selected_index = selected_index.min(self.queue.len().saturating_sub(1));
+
+        let background_playback =
+            supports_background_playback(self.config.player) && !self.config.download_mode;
+        if !background_playback || self.queue.is_empty() {
+            return;
+        }
+
+        self.load_queue_front(false);
+        if let Some(player) = self.player_manager.as_mut() {
+            player.seek_when_loaded(time_pos);
+            let plural = if restored == 1 { "" } else { "s" };
+            self.status_message = Some(format!("Restored {restored} queued track{plural}"));
         }
     }
 }
 
+pub(crate) fn tracks_label(count: usize) -> String {
+    if count == 1 {
+        "1 track".to_string()
+    } else {
+        format!("{count} tracks")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::config::Config;
     use crate::search::SearchResult;
+    use crate::watch_history::WatchMark;
 
     fn make_track(id: &str, title: &str) -> SearchResult {
         SearchResult {
@@ -364,6 +862,36 @@ mod tests {
         assert!(app.queue.is_empty());
     }
 
+    #[test]
+    fn repeat_one_replays_an_ended_track_but_skipping_moves_on() {
+        let mut app = App::new("test".to_string(), 10, Config::default());
+        app.config.repeat = RepeatMode::One;
+        app.queue.push_back(make_track("A", "Track A"));
+        app.queue.push_back(make_track("B", "Track B"));
+
+        app.advance_queue(false);
+        assert_eq!(app.queue.get(0).unwrap().id, "A");
+        assert_eq!(app.queue.len(), 2);
+
+        app.advance_queue(true);
+        assert_eq!(app.queue.get(0).unwrap().id, "B");
+        assert_eq!(app.queue.len(), 1);
+    }
+
+    #[test]
+    fn repeat_all_sends_the_played_track_to_the_tail() {
+        let mut app = App::new("test".to_string(), 10, Config::default());
+        app.config.repeat = RepeatMode::All;
+        app.queue.push_back(make_track("A", "Track A"));
+        app.queue.push_back(make_track("B", "Track B"));
+        app.queue_selected_index = 1;
+
+        app.advance_queue(false);
+        let ids: Vec<&str> = app.queue.iter().map(|track| track.id.as_str()).collect();
+        assert_eq!(ids, ["B", "A"]);
+        assert_eq!(app.queue_selected_index, 0);
+    }
+
     #[test]
     fn test_handle_next_video_on_empty_queue_is_safe() {
         let mut app = App::new("test".to_string(), 10, Config::default());
@@ -414,4 +942,108 @@ mod tests {
         assert_eq!(app.queue.get(0).unwrap().id, "B");
         assert_eq!(app.queue.get(1).unwrap().id, "C");
     }
+
+    #[test]
+    fn queue_snapshot_records_position_only_for_the_playing_front_track() {
+        let (client_stream, _server_stream) = std::os::unix::net::UnixStream::pair().unwrap();
+        let mut app = App::new("test".to_string(), 10, Config::default());
+        app.queue.push_back(make_track("video-id", "Front"));
+        app.queue.push_back(make_track("B", "Track B"));
+        app.queue_selected_index = 1;
+        let mut player = PlayerManager::from_test_stream(client_stream);
+        player.status.time_pos = 61.5;
+        app.player_manager = Some(player);
+
+        let snapshot = app.queue_snapshot();
+        assert_eq!(snapshot.tracks.len(), 2);
+        assert_eq!(snapshot.selected_index, 1);
+        assert_eq!(snapshot.time_pos, 61.5);
+
+        app.queue.pop_front();
+        assert_eq!(app.queue_snapshot().time_pos, 0.0);
+    }
+
+    #[test]
+    fn resume_positions_are_offered_held_and_forgotten_at_the_end() {
+        let (client_stream, _server_stream) = std::os::unix::net::UnixStream::pair().unwrap();
+        let mut app = App::new("test".to_string(), 10, Config::default());
+        app.resume_positions.update("video-id", 300.0, 600.0, 30.0);
+        let mut player = PlayerManager::from_test_stream(client_stream);
+        player.status.time_pos = 2.0;
+        app.player_manager = Some(player);
+
+        // Not offered until the track has loaded.
+        app.record_resume_position(false);
+        assert!(app.resume_offer.is_none());
+
+        app.player_manager.as_mut().unwrap().status.duration = 600.0;
+        app.record_resume_position(false);
+        assert_eq!(app.resume_offer.as_ref().unwrap().position, 300.0);
+        assert_eq!(app.resume_positions.get("video-id"), Some(300.0));
+
+        // Playing on past the offer starts over from there.
+        app.player_manager.as_mut().unwrap().status.time_pos = 20.0;
+        app.record_resume_position(false);
+        assert!(app.resume_offer.is_none());
+        assert_eq!(app.resume_positions.get("video-id"), Some(20.0));
+
+        app.player_manager.as_mut().unwrap().status.time_pos = 580.0;
+        app.record_resume_position(false);
+        assert_eq!(app.resume_positions.get("video-id"), None);
+
+        app.player_manager.as_mut().unwrap().status.time_pos = 200.0;
+        app.record_resume_position(false);
+        app.record_resume_position(true);
+        assert_eq!(app.resume_positions.get("video-id"), None);
+    }
+
+    #[test]
+    fn watch_history_records_the_front_track_once_it_plays() {
+        let (client_stream, _server_stream) = std::os::unix::net::UnixStream::pair().unwrap();
+        let mut app = App::new("test".to_string(), 10, Config::default());
+        app.queue.push_back(make_track("video-id", "Front"));
+        let mut player = PlayerManager::from_test_stream(client_stream);
+        player.status.paused = true;
+        player.status.duration = 200.0;
+        app.player_manager = Some(player);
+
+        // Loaded paused, as a restored session is: not a play yet.
+        app.record_watch_progress(false);
+        assert!(app.watch_history.is_empty());
+
+        let player = app.player_manager.as_mut().unwrap();
+        player.status.paused = false;
+        player.status.time_pos = 50.0;
+        app.record_watch_progress(false);
+        app.record_watch_progress(false);
+        assert_eq!(app.watch_history.len(), 1);
+        assert_eq!(app.watch_history.get(0).unwrap().time_pos, 50.0);
+        assert!(app.watch_history.is_recording("video-id"));
+
+        app.record_watch_progress(true);
+        assert!(app.watch_history.get(0).unwrap().finished);
+        assert_eq!(app.watch_history.mark("video-id"), Some(WatchMark::Watched));
+
+        app.player_manager = None;
+        app.record_watch_progress(false);
+        assert_eq!(app.watch_history.len(), 1);
+    }
+
+    #[test]
+    fn watch_history_records_tracks_played_outside_the_queue() {
+        let (client_stream, _server_stream) = std::os::unix::net::UnixStream::pair().unwrap();
+        let mut app = App::new("test".to_string(), 10, Config::default());
+        app.queue.push_back(make_track("queued", "Queued"));
+        let mut player = PlayerManager::from_test_stream(client_stream);
+        player.current_video_id = Some("remote-id".to_string());
+        player.status.title = "From the remote".to_string();
+        player.status.duration = 125.0;
+        app.player_manager = Some(player);
+
+        app.record_watch_progress(false);
+        let entry = app.watch_history.get(0).unwrap();
+        assert_eq!(entry.track.id, "remote-id");
+        assert_eq!(entry.track.title, "From the remote");
+        assert_eq!(entry.track.duration, "2:05");
+    }
 }
@@ -1,8 +1,26 @@
-use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
-
-use crate::config::{clamp_results_per_page, clamp_seek_step};
+use std::ops::Range;
+use std::time::{Duration, Instant};
+
+use crossterm::event::{
+    KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use ratatui::layout::{Position, Rect};
+
+use crate::config::{clamp_results_per_page, clamp_resume_margin, clamp_seek_step};
+use crate::details::DetailsView;
+use crate::keymap::{Action, Context, KeyChord};
+use crate::player::supports_background_playback;
 use crate::player_manager::PlayerManager;
-use crate::ui::app::{App, AppAction, FocusedPanel, InputMode, SearchPhase, SettingsField};
+use crate::playlists::expand_home;
+use crate::search::{
+    self, DurationFilter, LiveFilter, SearchFilters, SearchResult, SortOrder, UploadFilter,
+};
+use crate::subtitles::Cue;
+use crate::ui::app::{
+    App, AppAction, FilterEditor, FocusedPanel, HistorySearch, InputMode, ListArea, PlaylistPrompt,
+    PlaylistPromptKind, QueueGrab, SearchPhase, SettingsField, TranscriptView, tracks_label,
+};
+use crate::ui::theme::{self, Theme};
 
 pub fn handle_key_event(app: &mut App, key: KeyEvent) {
     // Some terminals report key releases in addition to presses. Handling both
@@ -29,8 +47,52 @@ pub fn handle_key_event(app: &mut App, key: KeyEvent) {
         return;
     }
 
+    if app.restore_prompt.is_some() {
+        handle_restore_prompt_keys(app, key);
+        return;
+    }
+
+    if app.playlist_prompt.is_some() {
+        handle_playlist_prompt_keys(app, key);
+        return;
+    }
+
+    if app.filter_editor.is_some() {
+        handle_filter_editor_keys(app, key);
+        return;
+    }
+
+    if app.details.is_some() {
+        handle_details_keys(app, key);
+        return;
+    }
+
+    if app.chapter_picker.is_some() {
+        handle_chapter_picker_keys(app, key);
+        return;
+    }
+
+    if app.language_picker.is_some() {
+        handle_language_picker_keys(app, key);
+        return;
+    }
+
+    if app.transcript.is_some() {
+        handle_transcript_keys(app, key);
+        return;
+    }
+
+    // Reverse history search also claims Tab and Esc.
+    if app.history_search.is_some() {
+        handle_history_search_keys(app, key);
+        return;
+    }
+
     // Global Tab key for focus cycling (works in any mode except Help).
-    if app.input_mode != InputMode::Help && app.timestamp_input.is_none() {
+    if app.input_mode != InputMode::Help
+        && app.timestamp_input.is_none()
+        && app.queue_grab.is_none()
+    {
         match key.code {
             KeyCode::BackTab => {
                 cycle_focus_backward(app);
@@ -54,17 +116,183 @@ pub fn handle_key_event(app: &mut App, key: KeyEvent) {
     }
 }
 
+/// Two clicks on the same item within this long make a double click.
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
+/// Clicks focus a panel and select the item under the pointer, a double click
+/// plays it, and the wheel scrolls the list under the pointer. Returns whether
+/// anything changed. The mouse is ignored while a modal or typed input has the
+/// keyboard, except that the wheel scrolls the details view.
+pub fn handle_mouse_event(app: &mut App, mouse: MouseEvent) -> bool {
+    if app.details.is_some() {
+        return match mouse.kind {
+            MouseEventKind::ScrollUp => scroll_details(app, -3),
+            MouseEventKind::ScrollDown => scroll_details(app, 3),
+            _ => false,
+        };
+    }
+    if app.settings_open
+        || app.restore_prompt.is_some()
+        || app.playlist_prompt.is_some()
+        || app.filter_editor.is_some()
+        || app.history_search.is_some()
+        || app.chapter_picker.is_some()
+        || app.language_picker.is_some()
+        || app.transcript.is_some()
+        || app.input_mode == InputMode::Help
+        || app.timestamp_input.is_some()
+        || app.queue_grab.is_some()
+    {
+        return false;
+    }
+
+    let position = Position::new(mouse.column, mouse.row);
+    let areas = app.screen_areas.borrow().clone();
+    let list = areas
+        .lists
+        .iter()
+        .find(|list| list.area.contains(position))
+        .copied();
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => {
+            app.status_message = None;
+            if let Some(bar) = areas.progress_bar
+                && bar.contains(position)
+            {
+                seek_to_click(app, bar, position.x);
+            } else if let Some(list) = list {
+                click_list(app, list, position);
+            } else if areas.search_bar.is_some_and(|area| area.contains(position)) {
+                app.focused_panel = FocusedPanel::SearchBar;
+            }
+            true
+        }
+        MouseEventKind::ScrollUp | MouseEventKind::ScrollDown => {
+            let Some(list) = list else {
+                return false;
+            };
+            app.focused_panel = list.panel;
+            scroll_list(app, list.panel, mouse.kind == MouseEventKind::ScrollDown);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// The thumb sits on the bar's first cell at the start and its last at the
+/// end, so a click maps back along the same scale.
+fn seek_to_click(app: &mut App, bar: Rect, column: u16) {
+    let Some(duration) = app
+        .player_manager
+        .as_ref()
+        .map(|player| player.status.duration)
+        .filter(|duration| *duration > 0.0)
+    else {
+        return;
+    };
+    let fraction =
+        f64::from(column.saturating_sub(bar.x)) / f64::from(bar.width.saturating_sub(1).max(1));
+    let seconds = fraction.min(1.0) * duration;
+    run_player_command(app, |player| player.seek_absolute(seconds));
+}
+
+fn click_list(app: &mut App, list: ListArea, position: Position) {
+    app.focused_panel = list.panel;
+    let Some(index) = list.item_at(position) else {
+        app.last_click = None;
+        return;
+    };
+    let now = Instant::now();
+    let double_click = app.last_click.is_some_and(|(at, panel, clicked)| {
+        panel == list.panel && clicked == index && now.duration_since(at) <= DOUBLE_CLICK
+    });
+    // A third click starts over rather than making a second double click.
+    app.last_click = (!double_click).then_some((now, list.panel, index));
+
+    match list.panel {
+        FocusedPanel::Results => {
+            app.number_input.clear();
+            app.selected_index = index;
+            if double_click {
+                run_results_action(app, Action::Play);
+            }
+        }
+        FocusedPanel::Queue => {
+            app.queue_selected_index = index;
+            if double_click {
+                run_queue_action(app, Action::Play);
+            }
+        }
+        FocusedPanel::Playlists => {
+            app.playlist_selected_index = index;
+            if double_click {
+                run_playlists_action(app, Action::QueueAll);
+            }
+        }
+        FocusedPanel::History => {
+            app.watch_history_selected_index = index;
+            if double_click {
+                run_watch_history_action(app, Action::Play);
+            }
+        }
+        FocusedPanel::SearchBar => {}
+    }
+}
+
+fn scroll_list(app: &mut App, panel: FocusedPanel, down: bool) {
+    let action = if down { Action::Down } else { Action::Up };
+    match panel {
+        FocusedPanel::Results => {
+            let page_len = app.current_page_results().len();
+            if page_len == 0 {
+                return;
+            }
+            // Scrolling past either end of the page turns it.
+            if down && app.selected_index + 1 >= page_len {
+                run_results_action(app, Action::NextPage);
+            } else if !down && app.selected_index == 0 && app.has_prev_page() {
+                run_results_action(app, Action::PreviousPage);
+                app.selected_index = app.current_page_results().len().saturating_sub(1);
+            } else {
+                run_results_action(app, action);
+            }
+        }
+        FocusedPanel::Queue => run_queue_action(app, action),
+        FocusedPanel::Playlists => run_playlists_action(app, action),
+        FocusedPanel::History => run_watch_history_action(app, action),
+        FocusedPanel::SearchBar => {}
+    }
+}
+
+fn handle_restore_prompt_keys(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Char('y' | 'Y') | KeyCode::Enter => {
+            if let Some(snapshot) = app.restore_prompt.take() {
+                app.restore_queue(snapshot);
+                app.focused_panel = FocusedPanel::Queue;
+            }
+        }
+        // Declining keeps the saved session until a new queue is saved.
+        KeyCode::Char('n' | 'N') | KeyCode::Esc => app.restore_prompt = None,
+        _ => {}
+    }
+}
+
 fn cycle_focus_forward(app: &mut App) {
     app.focused_panel = match app.focused_panel {
         FocusedPanel::SearchBar => FocusedPanel::Results,
         FocusedPanel::Results => FocusedPanel::Queue,
-        FocusedPanel::Queue => FocusedPanel::SearchBar,
+        FocusedPanel::Queue => FocusedPanel::Playlists,
+        FocusedPanel::Playlists => FocusedPanel::History,
+        FocusedPanel::History => FocusedPanel::SearchBar,
     };
 }
 
 fn cycle_focus_backward(app: &mut App) {
     app.focused_panel = match app.focused_panel {
-        FocusedPanel::SearchBar => FocusedPanel::Queue,
+        FocusedPanel::SearchBar => FocusedPanel::History,
+        FocusedPanel::History => FocusedPanel::Playlists,
+        FocusedPanel::Playlists => FocusedPanel::Queue,
         FocusedPanel::Queue => FocusedPanel::Results,
         FocusedPanel::Results => FocusedPanel::SearchBar,
     };
@@ -82,26 +310,27 @@ fn handle_browse_keys(app: &mut App, key: KeyEvent) {
         return;
     }
 
-    // Settings has one consistent global shortcut. Lowercase `s` and `/`
-    // remain dedicated to search focus.
-    match key.code {
-        KeyCode::Char('S') if app.focused_panel != FocusedPanel::SearchBar => {
-            app.settings_open = true;
-            return;
-        }
-        KeyCode::F(2) => {
-            app.settings_open = true;
-            return;
-        }
-        _ => {}
+    if app.queue_grab.is_some() {
+        handle_queue_grab_keys(app, key);
+        return;
+    }
+
+    // Settings stays reachable from the search bar through its non-text
+    // keys (F2 by default); `S` would just be typed there.
+    let global = app.keymap.action(Context::Global, &key);
+    if global == Some(Action::Settings)
+        && (app.focused_panel != FocusedPanel::SearchBar || !KeyChord::from_event(&key).is_text())
+    {
+        app.settings_open = true;
+        return;
     }
 
     // Global quit keys work from any panel
+    if global == Some(Action::Quit) && app.focused_panel != FocusedPanel::SearchBar {
+        app.should_quit = true;
+        return;
+    }
     match (key.code, key.modifiers) {
-        (KeyCode::Char('q' | 'Q'), _) if app.focused_panel != FocusedPanel::SearchBar => {
-            app.should_quit = true;
-            return;
-        }
         (KeyCode::Esc, _) => {
             // The video view always returns to search/queue on Esc,
             // regardless of what would otherwise happen (cancel, quit).
@@ -132,102 +361,347 @@ fn handle_browse_keys(app: &mut App, key: KeyEvent) {
 
     // Global playback controls (work from any panel, don't conflict with panel keys)
     if app.focused_panel != FocusedPanel::SearchBar {
-        match key.code {
-            KeyCode::Char('v') => {
-                toggle_video_view(app);
-                return;
-            }
-            KeyCode::Char('n') if app.video_view => {
-                app.handle_next_video(true);
-                return;
-            }
-            KeyCode::Char('h' | '?') if app.video_view => {
-                app.input_mode = InputMode::Help;
-                return;
-            }
-            KeyCode::Char(' ') => {
-                if run_player_command(app, |player| player.toggle_pause()) {
-                    return;
-                }
-            }
-            KeyCode::Char('<') => {
-                if run_player_command(app, |player| player.seek(-10.0)) {
-                    return;
-                }
-            }
-            KeyCode::Char('>') => {
-                if run_player_command(app, |player| player.seek(10.0)) {
-                    return;
-                }
-            }
-            KeyCode::Left if key.modifiers.contains(KeyModifiers::SHIFT) => {
-                let seconds = -(app.config.seek_step_large as f64);
-                if run_player_command(app, |player| player.seek(seconds)) {
-                    return;
+        if app.video_view
+            && let Some(action) = app.keymap.action(Context::Video, &key)
+        {
+            match action {
+                Action::NextTrack => app.handle_next_video(true),
+                Action::PreviousTrack => app.handle_previous_video(),
+                Action::Details => {
+                    let track = app.playing_track().cloned();
+                    open_details(app, track);
                 }
+                Action::Help => app.input_mode = InputMode::Help,
+                _ => {}
             }
-            KeyCode::Right if key.modifiers.contains(KeyModifiers::SHIFT) => {
-                let seconds = app.config.seek_step_large as f64;
-                if run_player_command(app, |player| player.seek(seconds)) {
-                    return;
-                }
+            return;
+        }
+        // While a result range is being typed, `-` is its separator.
+        let typing_range = app.focused_panel == FocusedPanel::Results
+            && !app.number_input.is_empty()
+            && key.code == KeyCode::Char('-');
+        if let Some(action) = global
+            && !typing_range
+            && run_global_action(app, action)
+        {
+            return;
+        }
+    }
+
+    // Panel-specific keys (navigation, search editing, digit quick-picks) do
+    // not make sense over the video widget; the playback controls above
+    // still work, everything else is simply ignored while it is active.
+    if !app.video_view {
+        match app.focused_panel {
+            FocusedPanel::SearchBar => handle_search_bar_keys(app, key),
+            FocusedPanel::Results => handle_results_keys(app, key),
+            FocusedPanel::Queue => handle_queue_keys(app, key),
+            FocusedPanel::Playlists => handle_playlists_keys(app, key),
+            FocusedPanel::History => handle_watch_history_keys(app, key),
+        }
+    }
+}
+
+/// Run a global binding. Playback bindings only take the key while a player
+/// exists, so without one it falls through to the focused panel.
+fn run_global_action(app: &mut App, action: Action) -> bool {
+    match action {
+        Action::ToggleVideo => toggle_video_view(app),
+        Action::Shuffle => {
+            let result = app.config.toggle_shuffle();
+            record_settings_save_result(app, result);
+        }
+        Action::Repeat => {
+            let result = app.config.cycle_repeat();
+            record_settings_save_result(app, result);
+        }
+        Action::Pause => return run_player_command(app, |player| player.toggle_pause()),
+        Action::Rewind => return run_player_command(app, |player| player.seek(-10.0)),
+        Action::FastForward => return run_player_command(app, |player| player.seek(10.0)),
+        Action::SeekBack
+        | Action::SeekForward
+        | Action::SeekBackLarge
+        | Action::SeekForwardLarge => {
+            let step = match action {
+                Action::SeekBack | Action::SeekForward => app.config.seek_step,
+                _ => app.config.seek_step_large,
+            } as f64;
+            let seconds = match action {
+                Action::SeekBack | Action::SeekBackLarge => -step,
+                _ => step,
+            };
+            return run_player_command(app, |player| player.seek(seconds));
+        }
+        Action::Resume if app.resume_offer.is_some() => resume_playback(app),
+        Action::JumpToTime
+            if app
+                .player_manager
+                .as_ref()
+                .is_some_and(|player| player.current_video_id.is_some()) =>
+        {
+            app.timestamp_input = Some(String::new());
+        }
+        Action::VolumeUp => {
+            return run_player_command(app, |player| {
+                player.set_volume((player.status.volume + 5).min(100))
+            });
+        }
+        Action::VolumeDown => {
+            return run_player_command(app, |player| {
+                player.set_volume((player.status.volume - 5).max(0))
+            });
+        }
+        Action::Mute => {
+            return run_player_command(app, |player| {
+                let new_volume = if player.status.volume > 0 { 0 } else { 100 };
+                player.set_volume(new_volume)
+            });
+        }
+        Action::NextChapter | Action::PreviousChapter => {
+            return step_chapter(app, action == Action::NextChapter);
+        }
+        Action::Chapters => return open_chapter_picker(app),
+        Action::Subtitles => {
+            let result = app.config.toggle_subtitles();
+            record_settings_save_result(app, result);
+        }
+        Action::SubtitleLanguage => return open_language_picker(app),
+        Action::Transcript if loaded_player(app).is_some() => {
+            app.transcript = Some(TranscriptView::default());
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// The player, while it has a track loaded.
+fn loaded_player(app: &mut App) -> Option<&mut PlayerManager> {
+    app.player_manager
+        .as_mut()
+        .filter(|player| player.current_video_id.is_some())
+}
+
+fn step_chapter(app: &mut App, forward: bool) -> bool {
+    let Some(player) = loaded_player(app) else {
+        return false;
+    };
+    let message = match player.step_chapter(forward) {
+        Ok(true) => return true,
+        Ok(false) if player.status.chapters.is_empty() => "This video has no chapters",
+        Ok(false) if forward => "This is the last chapter",
+        Ok(false) => "No chapter to go back to",
+        Err(error) => {
+            app.player_manager = None;
+            app.status_message = Some(format!("Playback stopped: {error}"));
+            return true;
+        }
+    };
+    app.status_message = Some(message.to_string());
+    true
+}
+
+fn open_chapter_picker(app: &mut App) -> bool {
+    let Some(player) = loaded_player(app) else {
+        return false;
+    };
+    if player.status.chapters.is_empty() {
+        app.status_message = Some("This video has no chapters".to_string());
+    } else {
+        app.chapter_picker = Some(player.status.chapter.unwrap_or(0));
+    }
+    true
+}
+
+/// Esc, `q`, or the key of the action that opened a picker close it again.
+fn closes_picker(app: &App, key: &KeyEvent, opened_by: Action) -> bool {
+    matches!(key.code, KeyCode::Esc | KeyCode::Char('q'))
+        || app.keymap.action(Context::Global, key) == Some(opened_by)
+}
+
+fn handle_chapter_picker_keys(app: &mut App, key: KeyEvent) {
+    let Some(selected) = app.chapter_picker else {
+        return;
+    };
+    let count = app
+        .player_manager
+        .as_ref()
+        .map_or(0, |player| player.status.chapters.len());
+    let last = count.saturating_sub(1);
+    if closes_picker(app, &key, Action::Chapters) {
+        app.chapter_picker = None;
+        return;
+    }
+    match key.code {
+        KeyCode::Up | KeyCode::Char('k') => app.chapter_picker = Some(selected.saturating_sub(1)),
+        KeyCode::Down | KeyCode::Char('j') => app.chapter_picker = Some((selected + 1).min(last)),
+        KeyCode::Home | KeyCode::Char('g') => app.chapter_picker = Some(0),
+        KeyCode::End | KeyCode::Char('G') => app.chapter_picker = Some(last),
+        KeyCode::Enter => {
+            app.chapter_picker = None;
+            if selected < count {
+                run_player_command(app, |player| player.seek_chapter(selected));
             }
-            KeyCode::Left => {
-                let seconds = -(app.config.seek_step as f64);
-                if run_player_command(app, |player| player.seek(seconds)) {
-                    return;
-                }
+        }
+        _ => {}
+    }
+}
+
+fn open_language_picker(app: &mut App) -> bool {
+    if loaded_player(app).is_none() {
+        return false;
+    }
+    let selected = app.subtitles.transcript().and_then(|transcript| {
+        transcript
+            .languages
+            .iter()
+            .position(|language| language.code == app.config.subtitle_language)
+    });
+    app.language_picker = Some(selected.unwrap_or(0));
+    true
+}
+
+fn handle_language_picker_keys(app: &mut App, key: KeyEvent) {
+    let Some(selected) = app.language_picker else {
+        return;
+    };
+    let languages = app
+        .subtitles
+        .transcript()
+        .map_or(&[][..], |transcript| transcript.languages.as_slice());
+    let last = languages.len().saturating_sub(1);
+    if closes_picker(app, &key, Action::SubtitleLanguage) {
+        app.language_picker = None;
+        return;
+    }
+    match key.code {
+        KeyCode::Up | KeyCode::Char('k') => app.language_picker = Some(selected.saturating_sub(1)),
+        KeyCode::Down | KeyCode::Char('j') => app.language_picker = Some((selected + 1).min(last)),
+        KeyCode::Home | KeyCode::Char('g') => app.language_picker = Some(0),
+        KeyCode::End | KeyCode::Char('G') => app.language_picker = Some(last),
+        KeyCode::Enter => {
+            if let Some(language) = languages.get(selected) {
+                let code = language.code.clone();
+                let result = app.config.set_subtitle_language(&code);
+                record_settings_save_result(app, result);
             }
-            KeyCode::Right => {
-                let seconds = app.config.seek_step as f64;
-                if run_player_command(app, |player| player.seek(seconds)) {
-                    return;
-                }
+            app.language_picker = None;
+        }
+        _ => {}
+    }
+}
+
+/// The transcript line keys move from, once there are lines.
+fn transcript_selection(app: &App) -> Option<usize> {
+    let transcript = app.subtitles.transcript()?;
+    let view = app.transcript.as_ref()?;
+    let position = app
+        .player_manager
+        .as_ref()
+        .map_or(0.0, |player| player.status.time_pos);
+    (!transcript.cues.is_empty()).then(|| view.line(transcript, position))
+}
+
+/// The first line containing `query` at or after `start`, going forward or
+/// back and wrapping around. Matching ignores case.
+fn find_transcript_line(cues: &[Cue], query: &str, start: usize, forward: bool) -> Option<usize> {
+    let query = query.to_lowercase();
+    let len = cues.len();
+    if query.is_empty() || len == 0 {
+        return None;
+    }
+    (0..len)
+        .map(|step| {
+            if forward {
+                (start + step) % len
+            } else {
+                (start % len + len - step) % len
             }
-            KeyCode::Char('t')
-                if app
-                    .player_manager
-                    .as_ref()
-                    .is_some_and(|player| player.current_video_id.is_some()) =>
-            {
-                app.timestamp_input = Some(String::new());
-                return;
+        })
+        .find(|&index| cues[index].text.to_lowercase().contains(&query))
+}
+
+fn handle_transcript_keys(app: &mut App, key: KeyEvent) {
+    let selected = transcript_selection(app);
+    let closes = closes_picker(app, &key, Action::Transcript);
+    let cues = app
+        .subtitles
+        .transcript()
+        .map_or(&[][..], |transcript| transcript.cues.as_slice());
+    let Some(view) = app.transcript.as_mut() else {
+        return;
+    };
+
+    if view.searching {
+        match key.code {
+            KeyCode::Esc => {
+                view.searching = false;
+                view.query.clear();
             }
-            KeyCode::Char('=') | KeyCode::Char('+') => {
-                if run_player_command(app, |player| {
-                    player.set_volume((player.status.volume + 5).min(100))
-                }) {
-                    return;
+            KeyCode::Enter => {
+                view.searching = false;
+                let found = find_transcript_line(cues, &view.query, selected.unwrap_or(0), true);
+                match found {
+                    Some(index) => view.selected = Some(index),
+                    None if !view.query.is_empty() => {
+                        app.status_message = Some(format!("No line contains \"{}\"", view.query));
+                    }
+                    None => {}
                 }
             }
-            KeyCode::Char('-') => {
-                if run_player_command(app, |player| {
-                    player.set_volume((player.status.volume - 5).max(0))
-                }) {
-                    return;
-                }
+            KeyCode::Backspace => {
+                view.query.pop();
             }
-            KeyCode::Char('m')
-                if run_player_command(app, |player| {
-                    let new_volume = if player.status.volume > 0 { 0 } else { 100 };
-                    player.set_volume(new_volume)
-                }) =>
-            {
-                return;
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                view.query.push(c);
             }
             _ => {}
         }
+        return;
     }
 
-    // Panel-specific keys (navigation, search editing, digit quick-picks) do
-    // not make sense over the video widget; the playback controls above
-    // still work, everything else is simply ignored while it is active.
-    if !app.video_view {
-        match app.focused_panel {
-            FocusedPanel::SearchBar => handle_search_bar_keys(app, key),
-            FocusedPanel::Results => handle_results_keys(app, key),
-            FocusedPanel::Queue => handle_queue_keys(app, key),
+    if closes {
+        app.transcript = None;
+        return;
+    }
+    let last = cues.len().saturating_sub(1);
+    let Some(selected) = selected else {
+        return;
+    };
+    let moved = |offset: isize| Some(selected.saturating_add_signed(offset).min(last));
+    match key.code {
+        KeyCode::Up | KeyCode::Char('k') => view.selected = moved(-1),
+        KeyCode::Down | KeyCode::Char('j') => view.selected = moved(1),
+        KeyCode::PageUp => view.selected = moved(-10),
+        KeyCode::PageDown => view.selected = moved(10),
+        KeyCode::Home | KeyCode::Char('g') => view.selected = Some(0),
+        KeyCode::End | KeyCode::Char('G') => view.selected = Some(last),
+        KeyCode::Char('/') => {
+            view.searching = true;
+            view.query.clear();
+        }
+        KeyCode::Char(c @ ('n' | 'N')) => {
+            let forward = c == 'n';
+            let start = if forward {
+                selected + 1
+            } else {
+                selected + cues.len() - 1
+            };
+            match find_transcript_line(cues, &view.query, start, forward) {
+                Some(index) => view.selected = Some(index),
+                None if view.query.is_empty() => {
+                    app.status_message = Some("Press / to search the transcript".to_string());
+                }
+                None => {
+                    app.status_message = Some(format!("No line contains \"{}\"", view.query));
+                }
+            }
+        }
+        KeyCode::Enter => {
+            let start = cues[selected].start;
+            app.transcript = None;
+            run_player_command(app, |player| player.seek_absolute(start));
         }
+        _ => {}
     }
 }
 
@@ -340,25 +814,49 @@ fn run_player_command(
 }
 
 fn handle_results_keys(app: &mut App, key: KeyEvent) {
-    match (key.code, key.modifiers) {
-        (KeyCode::Up | KeyCode::Char('k'), _) => {
-            if app.selected_index > 0 {
-                app.selected_index -= 1;
+    // Typing a result number or range is not rebindable.
+    match key.code {
+        KeyCode::Char(c) if c.is_ascii_digit() => {
+            if app.number_input.len() < 13 {
+                app.number_input.push(c);
             }
+            return;
+        }
+        // "3-7" picks a range of results.
+        KeyCode::Char('-') if !app.number_input.is_empty() && !app.number_input.contains('-') => {
+            app.number_input.push('-');
+            return;
+        }
+        KeyCode::Backspace if !app.number_input.is_empty() => {
+            app.number_input.pop();
+            return;
+        }
+        _ => {}
+    }
+
+    if let Some(action) = app.keymap.action(Context::Results, &key) {
+        run_results_action(app, action);
+    }
+}
+
+fn run_results_action(app: &mut App, action: Action) {
+    match action {
+        Action::Up if app.selected_index > 0 => {
+            app.selected_index -= 1;
         }
-        (KeyCode::Down | KeyCode::Char('j'), _) => {
+        Action::Down => {
             let page_results = app.current_page_results();
             if app.selected_index < page_results.len().saturating_sub(1) {
                 app.selected_index += 1;
             }
         }
-        (KeyCode::Home | KeyCode::Char('g'), _) => {
+        Action::First => {
             app.selected_index = 0;
         }
-        (KeyCode::End | KeyCode::Char('G'), _) => {
+        Action::Last => {
             app.selected_index = app.current_page_results().len().saturating_sub(1);
         }
-        (KeyCode::Char('n') | KeyCode::PageDown, _) if app.has_next_page() => {
+        Action::NextPage if app.has_next_page() => {
             let target_page = app.page.saturating_add(1);
             let target_start = target_page.saturating_mul(app.page_size.max(1));
             if target_start < app.results.len() {
@@ -380,7 +878,7 @@ fn handle_results_keys(app: &mut App, key: KeyEvent) {
                 app.search_phase = Some(SearchPhase::RequestedPage { target_page });
             }
         }
-        (KeyCode::Char('p') | KeyCode::PageUp, _) if app.has_prev_page() => {
+        Action::PreviousPage if app.has_prev_page() => {
             app.page -= 1;
             app.selected_index = 0;
             if let Some(SearchPhase::RequestedPage { target_page }) = app.search_phase {
@@ -390,143 +888,402 @@ fn handle_results_keys(app: &mut App, key: KeyEvent) {
             }
             app.schedule_page_prefetch();
         }
-        (KeyCode::Char('h' | '?'), _) => {
+        Action::Help => {
             app.input_mode = InputMode::Help;
         }
-        (KeyCode::Char('s' | '/'), _) => {
+        Action::Search => {
             app.focused_panel = FocusedPanel::SearchBar;
         }
-        (KeyCode::Char(c), _) if c.is_ascii_digit() => {
-            if app.number_input.len() < 6 {
-                app.number_input.push(c);
-            }
+        Action::QueueAll => request_enqueue_all(app),
+        Action::Filters => {
+            app.filter_editor = Some(FilterEditor {
+                draft: app.search_filters,
+                selected: 0,
+            });
         }
-        (KeyCode::Enter, _) => {
-            let idx = if !app.number_input.is_empty() {
-                let page_len = app.current_page_results().len();
-                let page_start = app.page.saturating_mul(app.page_size.max(1));
-                let result = app.number_input.parse::<usize>().ok().and_then(|num| {
-                    let global_start = page_start.saturating_add(1);
-                    let global_end = page_start.saturating_add(page_len);
-                    if (global_start..=global_end).contains(&num) {
-                        // Match the number displayed beside the result.
-                        Some(num - 1)
-                    } else if num > 0 && num <= page_len {
-                        // Keep page-local quick picks for muscle memory.
-                        Some(page_start.saturating_add(num - 1))
-                    } else {
-                        None
-                    }
-                });
-                app.number_input.clear();
-                result
-            } else {
-                Some(
-                    app.page
-                        .saturating_mul(app.page_size.max(1))
-                        .saturating_add(app.selected_index),
-                )
+        Action::Details => {
+            let track = app.current_page_results().get(app.selected_index).cloned();
+            open_details(app, track);
+        }
+        Action::Play => {
+            let Some(range) = chosen_results(app) else {
+                return;
             };
-
-            if let Some(idx) = idx
-                && idx < app.results.len()
+            if range.len() == 1 {
+                app.pending_action = AppAction::Play(range.start);
+            } else if let Some(tracks) = queueable_results(app, range) {
+                let added = app.enqueue_tracks(tracks);
+                if app.status_message.is_none() {
+                    app.status_message = Some(format!("Queued {}", tracks_label(added)));
+                }
+            }
+        }
+        Action::Add => {
+            if let Some(range) = chosen_results(app)
+                && let Some(tracks) = queueable_results(app, range)
             {
-                app.pending_action = AppAction::Play(idx);
+                let message = queued_message("Queued", &tracks);
+                app.add_to_queue(tracks);
+                app.status_message = Some(message);
             }
         }
-        (KeyCode::Backspace, _) => {
-            app.number_input.pop();
+        Action::AddPage => {
+            app.number_input.clear();
+            let page_start = app.page.saturating_mul(app.page_size.max(1));
+            let range = page_start..page_start + app.current_page_results().len();
+            if !range.is_empty()
+                && let Some(tracks) = queueable_results(app, range)
+            {
+                let added = app.add_to_queue(tracks);
+                app.status_message = Some(format!("Queued {} from this page", tracks_label(added)));
+            }
+        }
+        Action::PlayNext => {
+            if let Some(range) = chosen_results(app)
+                && let Some(tracks) = queueable_results(app, range)
+            {
+                let message = queued_message("Playing next:", &tracks);
+                app.play_next(tracks);
+                if app.status_message.is_none() {
+                    app.status_message = Some(message);
+                }
+            }
         }
         _ => {}
     }
 }
 
-fn handle_search_bar_keys(app: &mut App, key: KeyEvent) {
-    match (key.code, key.modifiers) {
-        (KeyCode::Char(c), modifiers)
-            if !modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
-        {
-            if app.search_input.len() < 4096 {
-                app.search_input.push(c);
+/// The results Enter, `a` and `N` act on: the number or "first-last" range
+/// typed so far, else the selection. Numbers match those shown beside the
+/// results on this page, or count from the top of the page. Typed input is
+/// consumed either way.
+fn chosen_results(app: &mut App) -> Option<Range<usize>> {
+    let page_start = app.page.saturating_mul(app.page_size.max(1));
+    let range = if app.number_input.is_empty() {
+        let idx = page_start.saturating_add(app.selected_index);
+        Some(idx..idx.saturating_add(1))
+    } else {
+        let input = std::mem::take(&mut app.number_input);
+        let page_len = app.current_page_results().len();
+        let pick = |number: &str| {
+            let num = number.parse::<usize>().ok()?;
+            let global_start = page_start.saturating_add(1);
+            let global_end = page_start.saturating_add(page_len);
+            if (global_start..=global_end).contains(&num) {
+                // Match the number displayed beside the result.
+                Some(num - 1)
+            } else if num > 0 && num <= page_len {
+                // Keep page-local quick picks for muscle memory.
+                Some(page_start.saturating_add(num - 1))
+            } else {
+                None
+            }
+        };
+        match input.split_once('-') {
+            Some((first, last)) => {
+                let (first, last) = (pick(first)?, pick(last)?);
+                Some(first.min(last)..first.max(last) + 1)
             }
+            None => pick(&input).map(|idx| idx..idx + 1),
         }
-        (KeyCode::Backspace, _) => {
-            app.search_input.pop();
+    };
+    range.filter(|range| range.end <= app.results.len())
+}
+
+/// The results in `range`, when the queue can play them.
+fn queueable_results(app: &mut App, range: Range<usize>) -> Option<Vec<SearchResult>> {
+    if !supports_background_playback(app.config.player) || app.config.download_mode {
+        app.status_message = Some("Queueing needs mpv with download mode off".to_string());
+        return None;
+    }
+    Some(app.results[range].to_vec())
+}
+
+/// "Queued \"Title\"" for one track, "Queued 4 tracks" for several.
+fn queued_message(verb: &str, tracks: &[SearchResult]) -> String {
+    match tracks {
+        [track] => format!("{verb} \"{}\"", track.title),
+        _ => format!("{verb} {}", tracks_label(tracks.len())),
+    }
+}
+
+fn request_enqueue_all(app: &mut App) {
+    if !app.showing_collection() {
+        app.status_message =
+            Some("Queue all works on playlist, channel and @handle results".to_string());
+    } else if !supports_background_playback(app.config.player) || app.config.download_mode {
+        app.status_message = Some("Queueing needs mpv with download mode off".to_string());
+    } else if app.search_phase != Some(SearchPhase::FetchingAll) {
+        app.pending_action = AppAction::EnqueueAll;
+    }
+}
+
+const FILTER_ROWS: usize = 4;
+
+fn handle_filter_editor_keys(app: &mut App, key: KeyEvent) {
+    let Some(editor) = app.filter_editor.as_mut() else {
+        return;
+    };
+    match key.code {
+        KeyCode::Up | KeyCode::Char('k') | KeyCode::BackTab => {
+            editor.selected = editor.selected.saturating_sub(1);
         }
-        (KeyCode::Enter, _) => {
-            let query = app.search_input.trim().to_string();
-            if !query.is_empty() {
-                // Update query immediately so search bar shows new query
-                app.query.clone_from(&query);
+        KeyCode::Down | KeyCode::Char('j') | KeyCode::Tab => {
+            editor.selected = (editor.selected + 1).min(FILTER_ROWS - 1);
+        }
+        KeyCode::Left | KeyCode::Char('h') => cycle_filter(editor, false),
+        KeyCode::Right | KeyCode::Char('l') | KeyCode::Char(' ') => cycle_filter(editor, true),
+        KeyCode::Char('x') | KeyCode::Backspace => editor.draft = SearchFilters::default(),
+        KeyCode::Esc => app.filter_editor = None,
+        KeyCode::Enter => {
+            let filters = editor.draft;
+            app.filter_editor = None;
+            if filters == app.search_filters {
+                return;
+            }
+            app.search_filters = filters;
+            if app.showing_collection() {
+                app.status_message =
+                    Some("Filters apply to searches, not playlists or channels".to_string());
+            } else if !app.query.trim().is_empty() {
                 app.loading = true;
                 app.search_phase = Some(SearchPhase::Initial);
-                app.pending_action = AppAction::NewSearch(query);
-                app.search_input.clear();
-                app.number_input.clear();
-                app.focused_panel = FocusedPanel::Results;
+                app.pending_action = AppAction::NewSearch(app.query.clone());
             }
         }
-        (KeyCode::Esc, _) => {
+        _ => {}
+    }
+}
+
+fn cycle_filter(editor: &mut FilterEditor, forward: bool) {
+    fn step<T: Copy + PartialEq>(choices: &[T], current: T, forward: bool) -> T {
+        let index = choices
+            .iter()
+            .position(|&choice| choice == current)
+            .unwrap_or(0);
+        let next = if forward {
+            (index + 1) % choices.len()
+        } else {
+            (index + choices.len() - 1) % choices.len()
+        };
+        choices[next]
+    }
+
+    let draft = &mut editor.draft;
+    match editor.selected {
+        0 => draft.sort = step(&SortOrder::ALL, draft.sort, forward),
+        1 => draft.duration = step(&DurationFilter::ALL, draft.duration, forward),
+        2 => draft.uploaded = step(&UploadFilter::ALL, draft.uploaded, forward),
+        _ => draft.live = step(&LiveFilter::ALL, draft.live, forward),
+    }
+}
+
+fn handle_search_bar_keys(app: &mut App, key: KeyEvent) {
+    match app.keymap.action(Context::Search, &key) {
+        Some(Action::FindSearch) => {
+            app.history_recall = None;
+            app.history_search = Some(HistorySearch {
+                pattern: app.search_input.clone(),
+                selected: 0,
+            });
+        }
+        Some(Action::OlderSearch) => {
+            let next = app
+                .history_recall
+                .as_ref()
+                .map_or(0, |(index, _)| index + 1);
+            if let Some(query) = app.search_history.get(next) {
+                let draft = match app.history_recall.take() {
+                    Some((_, draft)) => draft,
+                    None => std::mem::take(&mut app.search_input),
+                };
+                app.search_input = query.to_string();
+                app.history_recall = Some((next, draft));
+            }
+        }
+        Some(Action::NewerSearch) => match app.history_recall.take() {
+            Some((0, draft)) => app.search_input = draft,
+            Some((index, draft)) => {
+                app.search_input = app.search_history.get(index - 1).unwrap_or("").to_string();
+                app.history_recall = Some((index - 1, draft));
+            }
+            None => {}
+        },
+        Some(Action::AcceptSuggestion) => {
+            if let Some(completion) = app.search_history.completion(&app.search_input) {
+                app.search_input = completion.to_string();
+                app.history_recall = None;
+            }
+        }
+        Some(Action::Submit) => submit_search(app),
+        Some(Action::Cancel) => {
             app.search_input.clear();
             app.focused_panel = FocusedPanel::Results;
         }
+        _ => match (key.code, key.modifiers) {
+            (KeyCode::Char(c), modifiers)
+                if !modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+                    && app.search_input.len() < 4096 =>
+            {
+                app.history_recall = None;
+                app.search_input.push(c);
+            }
+            (KeyCode::Backspace, _) => {
+                app.history_recall = None;
+                app.search_input.pop();
+            }
+            _ => {}
+        },
+    }
+}
+
+fn submit_search(app: &mut App) {
+    let query = app.search_input.trim().to_string();
+    app.history_recall = None;
+    if query.is_empty() {
+        return;
+    }
+    if let Err(error) = app.search_history.record(&query) {
+        app.status_message = Some(format!("Could not save search history: {error}"));
+    }
+    // Update query immediately so search bar shows new query
+    app.query.clone_from(&query);
+    app.loading = true;
+    if let Some(video_id) = direct_video_id(&query) {
+        app.search_phase = Some(SearchPhase::LookingUpVideo);
+        app.pending_action = AppAction::OpenVideo {
+            start: search::parse_start_time(&query),
+            fallback_query: (video_id == query).then(|| query.clone()),
+            video_id,
+        };
+    } else {
+        app.search_phase = Some(SearchPhase::Initial);
+        app.pending_action = AppAction::NewSearch(query);
+    }
+    app.search_input.clear();
+    app.number_input.clear();
+    app.focused_panel = FocusedPanel::Results;
+}
+
+/// Keys while Ctrl+R is searching the history: typing narrows the matches,
+/// Ctrl+R or Up steps to older ones, Enter searches the match again and
+/// Right/Tab puts it in the search bar for editing.
+fn handle_history_search_keys(app: &mut App, key: KeyEvent) {
+    let Some(search) = app.history_search.as_mut() else {
+        return;
+    };
+    match (key.code, key.modifiers) {
+        (KeyCode::Char('r'), KeyModifiers::CONTROL) | (KeyCode::Up, _) => {
+            let matches = app.search_history.fuzzy_matches(&search.pattern).len();
+            search.selected = (search.selected + 1).min(matches.saturating_sub(1));
+        }
+        (KeyCode::Down, _) => search.selected = search.selected.saturating_sub(1),
+        (KeyCode::Char(c), modifiers)
+            if !modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+                && search.pattern.len() < 4096 =>
+        {
+            search.pattern.push(c);
+            search.selected = 0;
+        }
+        (KeyCode::Backspace, _) => {
+            search.pattern.pop();
+            search.selected = 0;
+        }
+        (KeyCode::Enter | KeyCode::Right | KeyCode::Tab, _) => {
+            let Some(query) = app.history_search_match().map(str::to_string) else {
+                return;
+            };
+            app.history_search = None;
+            app.search_input = query;
+            if key.code == KeyCode::Enter {
+                submit_search(app);
+            }
+        }
+        (KeyCode::Esc, _) | (KeyCode::Char('g'), KeyModifiers::CONTROL) => {
+            app.history_search = None;
+        }
         _ => {}
     }
 }
 
+/// The video named by a URL or bare ID typed into the search bar. An
+/// all-lowercase 11-letter word ("programming") is much more likely to be a
+/// query than an ID, so it is searched for as text.
+fn direct_video_id(query: &str) -> Option<String> {
+    let video_id = search::parse_video_id(query)?;
+    let looks_like_word = video_id == query && query.bytes().all(|b| b.is_ascii_lowercase());
+    (!looks_like_word).then_some(video_id)
+}
+
 fn handle_queue_keys(app: &mut App, key: KeyEvent) {
-    match key.code {
-        KeyCode::Up | KeyCode::Char('k') => {
-            if app.queue_selected_index > 0 {
-                app.queue_selected_index -= 1;
-            }
+    if let Some(action) = app.keymap.action(Context::Queue, &key) {
+        run_queue_action(app, action);
+    }
+}
+
+fn run_queue_action(app: &mut App, action: Action) {
+    match action {
+        Action::Up if app.queue_selected_index > 0 => {
+            app.queue_selected_index -= 1;
         }
-        KeyCode::Down | KeyCode::Char('j') => {
-            if app.queue_selected_index < app.queue.len().saturating_sub(1) {
-                app.queue_selected_index += 1;
-            }
+        Action::Down if app.queue_selected_index < app.queue.len().saturating_sub(1) => {
+            app.queue_selected_index += 1;
         }
-        KeyCode::Home | KeyCode::Char('g') => {
+        Action::First => {
             app.queue_selected_index = 0;
         }
-        KeyCode::End | KeyCode::Char('G') => {
+        Action::Last => {
             app.queue_selected_index = app.queue.len().saturating_sub(1);
         }
-        KeyCode::Enter => {
-            if promote_selected_queue_item(app) {
-                play_queue_front(app);
-            }
+        Action::Play if promote_selected_queue_item(app) => {
+            play_queue_front(app);
         }
-        KeyCode::Delete | KeyCode::Backspace => {
-            if app.queue_selected_index < app.queue.len() {
-                let was_playing = removed_queue_item_was_playing(
-                    app.queue_selected_index,
+        Action::Remove if app.queue.marked_count() > 0 => {
+            let was_playing = app.queue.is_marked(0)
+                && removed_queue_item_was_playing(
+                    0,
                     app.player_manager
                         .as_ref()
                         .and_then(|pm| pm.current_video_id.as_deref()),
                 );
-                app.queue.remove(app.queue_selected_index);
+            let removed = app.queue.remove_marked();
+            app.queue_selected_index = app
+                .queue_selected_index
+                .min(app.queue.len().saturating_sub(1));
+            app.status_message = Some(format!("Removed {}", tracks_label(removed)));
+            if was_playing {
+                play_after_front_removed(app);
+            }
+        }
+        Action::Remove if app.queue_selected_index < app.queue.len() => {
+            let was_playing = removed_queue_item_was_playing(
+                app.queue_selected_index,
+                app.player_manager
+                    .as_ref()
+                    .and_then(|pm| pm.current_video_id.as_deref()),
+            );
+            app.queue.remove(app.queue_selected_index);
 
-                if app.queue_selected_index >= app.queue.len() && app.queue_selected_index > 0 {
-                    app.queue_selected_index -= 1;
-                }
+            if app.queue_selected_index >= app.queue.len() && app.queue_selected_index > 0 {
+                app.queue_selected_index -= 1;
+            }
 
-                if was_playing {
-                    // The currently-playing track was removed; play whatever is now at the
-                    // front WITHOUT popping again (calling handle_next_video would double-pop).
-                    if app.queue.is_empty() {
-                        if let Some(mut pm) = app.player_manager.take()
-                            && let Err(error) = pm.clear()
-                        {
-                            app.status_message = Some(format!("Could not stop playback: {error}"));
-                        }
-                    } else {
-                        play_queue_front(app);
-                    }
-                }
+            if was_playing {
+                play_after_front_removed(app);
+            }
+        }
+        Action::MoveUp => move_queue_selection(app, true),
+        Action::MoveDown => move_queue_selection(app, false),
+        Action::Grab => grab_queue_selection(app),
+        Action::Mark if app.queue_selected_index < app.queue.len() => {
+            app.queue.toggle_mark(app.queue_selected_index);
+            // Like marking in a file manager: step on to the next track.
+            if app.queue_selected_index + 1 < app.queue.len() {
+                app.queue_selected_index += 1;
             }
         }
-        KeyCode::Char('c') => {
+        Action::ClearMarks => app.queue.clear_marks(),
+        Action::Clear => {
             app.queue.clear();
             app.queue_selected_index = 0;
             // Clear player when queue is cleared
@@ -536,20 +1293,391 @@ fn handle_queue_keys(app: &mut App, key: KeyEvent) {
                 app.status_message = Some(format!("Could not stop playback: {error}"));
             }
         }
-        KeyCode::Char('n') => {
+        Action::NextTrack => {
             // Next track - manual action, always auto-plays
             app.handle_next_video(true);
         }
-        KeyCode::Char('s' | '/') => {
+        Action::PreviousTrack => app.handle_previous_video(),
+        Action::Details => {
+            let track = app.queue.get(app.queue_selected_index).cloned();
+            open_details(app, track);
+        }
+        Action::Search => {
             app.focused_panel = FocusedPanel::SearchBar;
         }
-        KeyCode::Char('h' | '?') => {
+        Action::Help => {
             app.input_mode = InputMode::Help;
         }
         _ => {}
     }
 }
 
+/// Open the details view for `track`; the runner starts reading its
+/// metadata on the next pass.
+fn open_details(app: &mut App, track: Option<SearchResult>) {
+    match track {
+        Some(track) => app.details = Some(DetailsView::new(track)),
+        None => app.status_message = Some("Nothing selected to show details for".to_string()),
+    }
+}
+
+fn handle_details_keys(app: &mut App, key: KeyEvent) {
+    match key.code {
+        // Closing drops the view, which stops a read still in progress.
+        KeyCode::Esc | KeyCode::Char('i' | 'q') => app.details = None,
+        KeyCode::Up | KeyCode::Char('k') => {
+            scroll_details(app, -1);
+        }
+        KeyCode::Down | KeyCode::Char('j') => {
+            scroll_details(app, 1);
+        }
+        KeyCode::PageUp => {
+            scroll_details(app, -10);
+        }
+        KeyCode::PageDown | KeyCode::Char(' ') => {
+            scroll_details(app, 10);
+        }
+        KeyCode::Home | KeyCode::Char('g') => {
+            scroll_details(app, i32::MIN);
+        }
+        KeyCode::End | KeyCode::Char('G') => {
+            scroll_details(app, i32::MAX);
+        }
+        _ => {}
+    }
+}
+
+/// Move the details view by `lines`, no further than the last frame showed
+/// there is text to scroll to. Returns whether it moved.
+fn scroll_details(app: &mut App, lines: i32) -> bool {
+    let limit = app.screen_areas.borrow().details_scroll_limit;
+    let Some(details) = app.details.as_mut() else {
+        return false;
+    };
+    let scroll = (i64::from(details.scroll) + i64::from(lines)).clamp(0, i64::from(limit)) as u16;
+    let moved = scroll != details.scroll;
+    details.scroll = scroll;
+    moved
+}
+
+fn handle_watch_history_keys(app: &mut App, key: KeyEvent) {
+    if let Some(action) = app.keymap.action(Context::History, &key) {
+        run_watch_history_action(app, action);
+    }
+}
+
+fn run_watch_history_action(app: &mut App, action: Action) {
+    let selected = app.watch_history_selected_index;
+    let selected_track = app
+        .watch_history
+        .get(selected)
+        .map(|entry| entry.track.clone());
+    match action {
+        Action::Up => {
+            app.watch_history_selected_index = selected.saturating_sub(1);
+        }
+        Action::Down if selected + 1 < app.watch_history.len() => {
+            app.watch_history_selected_index += 1;
+        }
+        Action::First => app.watch_history_selected_index = 0,
+        Action::Last => {
+            app.watch_history_selected_index = app.watch_history.len().saturating_sub(1);
+        }
+        Action::Play => {
+            let Some(track) = selected_track else {
+                return;
+            };
+            if !supports_background_playback(app.config.player) || app.config.download_mode {
+                app.status_message = Some("Replaying needs mpv with download mode off".to_string());
+                return;
+            }
+            // Like Enter in the queue: the track jumps in front of whatever is
+            // playing, which stays queued right behind it.
+            app.queue.push_back(track);
+            app.queue.move_to_front(app.queue.len() - 1);
+            app.queue_selected_index = 0;
+            play_queue_front(app);
+            app.watch_history_selected_index = 0;
+        }
+        Action::Add => {
+            if let Some(track) = selected_track {
+                let title = track.title.clone();
+                app.enqueue_tracks([track]);
+                if app.status_message.is_none() {
+                    app.status_message = Some(format!("Queued \"{title}\""));
+                }
+            }
+        }
+        Action::Remove if selected < app.watch_history.len() => {
+            app.watch_history.remove(selected);
+            app.watch_history_selected_index =
+                selected.min(app.watch_history.len().saturating_sub(1));
+        }
+        Action::Search => app.focused_panel = FocusedPanel::SearchBar,
+        Action::Help => app.input_mode = InputMode::Help,
+        _ => {}
+    }
+}
+
+fn handle_playlists_keys(app: &mut App, key: KeyEvent) {
+    if let Some(action) = app.keymap.action(Context::Playlists, &key) {
+        run_playlists_action(app, action);
+    }
+}
+
+fn run_playlists_action(app: &mut App, action: Action) {
+    let selected = app.playlist_selected_index;
+    let selected_name = app
+        .playlists
+        .get(selected)
+        .map(|playlist| playlist.name.clone());
+    match action {
+        Action::Up if selected > 0 => {
+            app.playlist_selected_index -= 1;
+        }
+        Action::Down if selected < app.playlists.len().saturating_sub(1) => {
+            app.playlist_selected_index += 1;
+        }
+        Action::First => {
+            app.playlist_selected_index = 0;
+        }
+        Action::Last => {
+            app.playlist_selected_index = app.playlists.len().saturating_sub(1);
+        }
+        Action::QueueAll => {
+            let Some(playlist) = app.playlists.get(selected) else {
+                return;
+            };
+            let (name, tracks) = (playlist.name.clone(), playlist.tracks.clone());
+            let added = app.enqueue_tracks(tracks);
+            // Starting playback reports its own failures.
+            if app.status_message.is_none() {
+                app.status_message =
+                    Some(format!("Queued {} from \"{name}\"", tracks_label(added)));
+            }
+        }
+        Action::NewPlaylist => {
+            if app.queue.is_empty() {
+                app.status_message = Some("Queue is empty — nothing to save".to_string());
+            } else {
+                open_playlist_prompt(app, PlaylistPromptKind::Create, String::new());
+            }
+        }
+        Action::AddQueue => {
+            let Some(name) = selected_name else {
+                return;
+            };
+            if app.queue.is_empty() {
+                app.status_message = Some("Queue is empty — nothing to add".to_string());
+                return;
+            }
+            let tracks: Vec<_> = app.queue.iter().cloned().collect();
+            app.status_message = Some(match app.playlists.append(selected, &tracks) {
+                Ok(()) => format!("Added {} to \"{name}\"", tracks_label(tracks.len())),
+                Err(error) => format!("Could not update playlist: {error:#}"),
+            });
+        }
+        Action::Rename => {
+            if let Some(name) = selected_name {
+                open_playlist_prompt(app, PlaylistPromptKind::Rename, name);
+            }
+        }
+        Action::Remove if selected_name.is_some() => {
+            open_playlist_prompt(app, PlaylistPromptKind::Delete, String::new());
+        }
+        Action::Import => open_playlist_prompt(app, PlaylistPromptKind::Import, String::new()),
+        Action::Export => {
+            if let Some(name) = selected_name {
+                let suggestion = format!("~/{}.m3u8", name.replace(['/', '\\'], "-"));
+                open_playlist_prompt(app, PlaylistPromptKind::Export, suggestion);
+            }
+        }
+        Action::Search => {
+            app.focused_panel = FocusedPanel::SearchBar;
+        }
+        Action::Help => {
+            app.input_mode = InputMode::Help;
+        }
+        _ => {}
+    }
+}
+
+fn open_playlist_prompt(app: &mut App, kind: PlaylistPromptKind, input: String) {
+    app.playlist_prompt = Some(PlaylistPrompt { kind, input });
+}
+
+fn handle_playlist_prompt_keys(app: &mut App, key: KeyEvent) {
+    let Some(prompt) = app.playlist_prompt.as_mut() else {
+        return;
+    };
+
+    if prompt.kind == PlaylistPromptKind::Delete {
+        match key.code {
+            KeyCode::Char('y' | 'Y') | KeyCode::Enter => {
+                app.playlist_prompt = None;
+                let index = app.playlist_selected_index;
+                app.status_message = Some(match app.playlists.delete(index) {
+                    Ok(playlist) => format!("Deleted \"{}\"", playlist.name),
+                    Err(error) => format!("Could not delete playlist: {error:#}"),
+                });
+                app.playlist_selected_index = index.min(app.playlists.len().saturating_sub(1));
+            }
+            KeyCode::Char('n' | 'N') | KeyCode::Esc => app.playlist_prompt = None,
+            _ => {}
+        }
+        return;
+    }
+
+    match (key.code, key.modifiers) {
+        (KeyCode::Char(c), modifiers)
+            if !modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+                && prompt.input.len() < 4096 =>
+        {
+            prompt.input.push(c);
+        }
+        (KeyCode::Backspace, _) => {
+            prompt.input.pop();
+        }
+        (KeyCode::Esc, _) => app.playlist_prompt = None,
+        (KeyCode::Enter, _) => submit_playlist_prompt(app),
+        _ => {}
+    }
+}
+
+/// Carry out a text prompt. On failure the prompt stays open with the error
+/// shown so the input can be corrected.
+fn submit_playlist_prompt(app: &mut App) {
+    let Some(prompt) = app.playlist_prompt.as_ref() else {
+        return;
+    };
+    let input = prompt.input.trim();
+    let selected = app.playlist_selected_index;
+
+    let result = match prompt.kind {
+        PlaylistPromptKind::Create => {
+            let tracks = app.queue.iter().cloned().collect::<Vec<_>>();
+            let count = tracks.len();
+            app.playlists.create(input, tracks).map(|index| {
+                (
+                    index,
+                    format!("Saved \"{}\" with {}", input, tracks_label(count)),
+                )
+            })
+        }
+        PlaylistPromptKind::Rename => app
+            .playlists
+            .rename(selected, input)
+            .map(|index| (index, format!("Renamed to \"{input}\""))),
+        PlaylistPromptKind::Import => app.playlists.import(&expand_home(input)).map(|imported| {
+            let playlist = app.playlists.get(imported.index);
+            let mut message = format!(
+                "Imported \"{}\" with {}",
+                playlist.map_or("", |playlist| playlist.name.as_str()),
+                tracks_label(playlist.map_or(0, |playlist| playlist.tracks.len()))
+            );
+            if imported.skipped > 0 {
+                message.push_str(&format!(
+                    " ({} non-YouTube entries skipped)",
+                    imported.skipped
+                ));
+            }
+            (imported.index, message)
+        }),
+        PlaylistPromptKind::Export => {
+            let path = expand_home(input);
+            app.playlists
+                .export(selected, &path)
+                .map(|()| (selected, format!("Exported to {}", path.display())))
+        }
+        PlaylistPromptKind::Delete => return,
+    };
+
+    match result {
+        Ok((index, message)) => {
+            app.playlist_prompt = None;
+            app.playlist_selected_index = index;
+            app.status_message = Some(message);
+        }
+        Err(error) => app.status_message = Some(format!("{error:#}")),
+    }
+}
+
+/// The playing front track was removed: play whatever is now at the front
+/// WITHOUT popping again (calling handle_next_video would double-pop).
+fn play_after_front_removed(app: &mut App) {
+    if app.queue.is_empty() {
+        if let Some(mut pm) = app.player_manager.take()
+            && let Err(error) = pm.clear()
+        {
+            app.status_message = Some(format!("Could not stop playback: {error}"));
+        }
+    } else {
+        play_queue_front(app);
+    }
+}
+
+/// Move the marked tracks, or the selected one when none are, one place up
+/// or down. The playing track keeps its place at the front.
+fn move_queue_selection(app: &mut App, up: bool) {
+    let first = app.first_movable_queue_index();
+    let selected = app.queue_selected_index;
+    if app.queue.marked_count() > 0 {
+        app.queue_selected_index = app.queue.move_marked(up, first, selected);
+        return;
+    }
+    let target = if up {
+        selected.checked_sub(1)
+    } else {
+        Some(selected + 1)
+    };
+    if let Some(target) = target
+        && selected >= first
+        && target >= first
+        && app.queue.move_track(selected, target)
+    {
+        app.queue_selected_index = target;
+    }
+}
+
+fn grab_queue_selection(app: &mut App) {
+    let selected = app.queue_selected_index;
+    if selected >= app.queue.len() {
+        return;
+    }
+    let temporary_mark = app.queue.marked_count() == 0;
+    if temporary_mark {
+        if selected < app.first_movable_queue_index() {
+            app.status_message = Some("The playing track stays at the front".to_string());
+            return;
+        }
+        app.queue.toggle_mark(selected);
+    }
+    app.queue_grab = Some(QueueGrab { temporary_mark });
+}
+
+/// Keys while carrying queue tracks: the queue's move and reorder keys drag
+/// them along, and its play and grab keys or Esc put them down.
+fn handle_queue_grab_keys(app: &mut App, key: KeyEvent) {
+    if key.code == KeyCode::Esc {
+        drop_queue_grab(app);
+        return;
+    }
+    match app.keymap.action(Context::Queue, &key) {
+        Some(Action::Up | Action::MoveUp) => move_queue_selection(app, true),
+        Some(Action::Down | Action::MoveDown) => move_queue_selection(app, false),
+        Some(Action::Play | Action::Grab) => drop_queue_grab(app),
+        _ => {}
+    }
+}
+
+fn drop_queue_grab(app: &mut App) {
+    if let Some(grab) = app.queue_grab.take()
+        && grab.temporary_mark
+    {
+        app.queue.clear_marks();
+    }
+}
+
 fn promote_selected_queue_item(app: &mut App) -> bool {
     if app.queue.is_empty() || app.queue_selected_index >= app.queue.len() {
         return false;
@@ -561,6 +1689,20 @@ fn promote_selected_queue_item(app: &mut App) -> bool {
     true
 }
 
+/// Take the resume offer for the playing track.
+fn resume_playback(app: &mut App) {
+    let Some(offer) = app.resume_offer.take() else {
+        return;
+    };
+    let still_playing = app
+        .player_manager
+        .as_ref()
+        .is_some_and(|player| player.current_video_id.as_deref() == Some(&offer.video_id));
+    if still_playing {
+        run_player_command(app, |player| player.seek_absolute(offer.position));
+    }
+}
+
 fn play_queue_front(app: &mut App) {
     let Some(track) = app.queue.get(0) else {
         return;
@@ -620,6 +1762,7 @@ fn handle_settings_keys(app: &mut App, key: KeyEvent) {
                     SettingsField::ResultsPerPage
                         | SettingsField::SeekStep
                         | SettingsField::SeekStepLarge
+                        | SettingsField::ResumeMargin
                 ) || c.is_ascii_digit();
                 if accepts_character
                     && let Some(input) = app.settings_text_input.as_mut()
@@ -642,7 +1785,9 @@ fn handle_settings_keys(app: &mut App, key: KeyEvent) {
     }
 
     // Define selectable indices (skip section headers)
-    const SELECTABLE_INDICES: &[usize] = &[2, 3, 4, 5, 6, 7, 8, 12, 13, 17, 18, 22];
+    const SELECTABLE_INDICES: &[usize] = &[
+        2, 3, 4, 5, 6, 7, 8, 9, 13, 14, 18, 19, 20, 21, 22, 23, 24, 28,
+    ];
 
     match key.code {
         KeyCode::Esc => {
@@ -708,27 +1853,58 @@ fn handle_settings_keys(app: &mut App, key: KeyEvent) {
                     app.settings_editing = Some(SettingsField::SeekStepLarge);
                     app.settings_text_input = Some(app.config.seek_step_large.to_string());
                 }
-                12 => {
+                9 => {
+                    app.settings_editing = Some(SettingsField::ResumeMargin);
+                    app.settings_text_input = Some(app.config.resume_margin.to_string());
+                }
+                13 => {
                     // Download Mode checkbox
                     let result = app.config.toggle_download_mode();
                     record_settings_save_result(app, result);
                 }
-                13 => {
+                14 => {
                     // Download Dir text field - enter edit mode
                     app.settings_editing = Some(SettingsField::DownloadDir);
                     app.settings_text_input = Some(app.config.download_dir.clone());
                 }
-                17 => {
+                18 => {
                     // Results Per Page text field - enter edit mode
                     app.settings_editing = Some(SettingsField::ResultsPerPage);
                     app.settings_text_input = Some(app.config.results_per_page.to_string());
                 }
-                18 => {
+                19 => {
                     // Video Renderer cycle: auto → pixels → blocks
                     let result = app.config.cycle_video_render();
                     record_settings_save_result(app, result);
                 }
+                20 => {
+                    // Notifications checkbox
+                    let result = app.config.toggle_notify();
+                    record_settings_save_result(app, result);
+                }
+                21 => {
+                    // Radio checkbox
+                    let result = app.config.toggle_radio();
+                    record_settings_save_result(app, result);
+                }
                 22 => {
+                    // Theme cycle: dark → light → high-contrast → no-color
+                    let next = theme::next_built_in(&app.config.theme);
+                    let result = app.config.set_theme(next);
+                    record_settings_save_result(app, result);
+                    app.theme = Theme::load(next).unwrap_or_default();
+                }
+                23 => {
+                    // Thumbnail previews checkbox
+                    let result = app.config.toggle_thumbnails();
+                    record_settings_save_result(app, result);
+                }
+                24 => {
+                    // SponsorBlock checkbox
+                    let result = app.config.toggle_sponsorblock();
+                    record_settings_save_result(app, result);
+                }
+                28 => {
                     // Custom Format text field - enter edit mode
                     app.settings_editing = Some(SettingsField::CustomFormat);
                     app.settings_text_input = Some(app.config.custom_format.clone());
@@ -771,6 +1947,10 @@ fn finish_settings_edit(app: &mut App, field: SettingsField) {
             let value = input.parse::<u64>().unwrap_or(app.config.seek_step_large);
             app.config.seek_step_large = clamp_seek_step(value);
         }
+        SettingsField::ResumeMargin => {
+            let value = input.parse::<u64>().unwrap_or(app.config.resume_margin);
+            app.config.resume_margin = clamp_resume_margin(value);
+        }
         SettingsField::CustomFormat => app.config.custom_format = input,
     }
 
@@ -784,8 +1964,15 @@ fn finish_settings_edit(app: &mut App, field: SettingsField) {
 mod tests {
     use super::*;
     use crate::config::Config;
+    use crate::details::Chapter;
     use crate::player_manager::PlayerManager;
+    use crate::queue::RepeatMode;
     use crate::search::SearchResult;
+    use crate::session::QueueSnapshot;
+    use crate::subtitles::{Language, Subtitles, Transcript};
+    use crate::ui::layout::render_ui;
+    use crate::worker::wait_until;
+    use ratatui::{Terminal, backend::TestBackend};
     use serde_json::{Value, json};
     use std::io::{BufRead, BufReader, Write};
     use std::os::unix::net::UnixStream;
@@ -870,60 +2057,550 @@ mod tests {
     }
 
     #[test]
-    fn timestamp_prompt_opens_only_for_an_active_player() {
-        let mut app = App::new("test".to_string(), 10, Config::default());
-        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('t')));
-        assert!(app.timestamp_input.is_none());
+    fn accepting_the_restore_prompt_cues_the_front_track_paused() {
+        let (mut app, server) = app_with_command_capture(Config::default(), 0.0);
+        app.restore_prompt = Some(QueueSnapshot {
+            tracks: create_test_results(3),
+            selected_index: 2,
+            time_pos: 30.0,
+        });
+
+        // Keys other than the answers are swallowed by the modal.
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('q')));
+        assert!(!app.should_quit);
+        assert!(app.restore_prompt.is_some());
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('y')));
+
+        assert!(app.restore_prompt.is_none());
+        assert_eq!(app.queue.len(), 3);
+        assert_eq!(app.queue_selected_index, 2);
+        assert_eq!(app.focused_panel, FocusedPanel::Queue);
+        assert_eq!(
+            server.join().unwrap(),
+            json!([
+                "loadfile",
+                "https://www.youtube.com/watch?v=id1",
+                "replace",
+                "-1",
+                "pause=yes"
+            ])
+        );
+        let player = app.player_manager.as_ref().unwrap();
+        assert_eq!(player.current_video_id.as_deref(), Some("id1"));
+        assert!(player.status.paused);
+    }
+
+    #[test]
+    fn declining_the_restore_prompt_starts_with_an_empty_queue() {
+        let mut app = App::new("test".to_string(), 10, Config::default());
+        app.restore_prompt = Some(QueueSnapshot {
+            tracks: create_test_results(2),
+            ..QueueSnapshot::default()
+        });
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Esc));
+
+        assert!(app.restore_prompt.is_none());
+        assert!(app.queue.is_empty());
+        assert!(app.player_manager.is_none());
+        assert!(!app.should_quit);
+    }
+
+    #[test]
+    fn queue_all_is_offered_only_for_playlist_and_channel_results() {
+        let mut app = App::new("lofi".to_string(), 10, Config::default());
+        app.focused_panel = FocusedPanel::Results;
+        app.results = create_test_results(3);
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('A')));
+        assert_eq!(app.pending_action, AppAction::None);
+        assert!(app.status_message.as_deref().unwrap().contains("playlist"));
+
+        app.query = "@SomeChannel".to_string();
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('A')));
+        assert_eq!(app.pending_action, AppAction::EnqueueAll);
+    }
+
+    #[test]
+    fn a_queues_results_without_starting_playback() {
+        let mut app = App::new("test".to_string(), 10, Config::default());
+        app.focused_panel = FocusedPanel::Results;
+        app.results = create_test_results(3);
+        app.selected_index = 1;
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('a')));
+
+        assert!(app.player_manager.is_none());
+        assert_eq!(app.pending_action, AppAction::None);
+        assert_eq!(app.queue.get(0).unwrap().id, "id2");
+        assert_eq!(app.status_message.as_deref(), Some("Queued \"Video 2\""));
+    }
+
+    #[test]
+    fn typed_ranges_and_whole_pages_go_to_the_queue() {
+        // The server end is gone: a stray volume command would stop playback.
+        let (client_stream, _) = UnixStream::pair().unwrap();
+        let mut app = App::new("test".to_string(), 10, Config::default());
+        app.player_manager = Some(PlayerManager::from_test_stream(client_stream));
+        app.focused_panel = FocusedPanel::Results;
+        app.results = create_test_results(15);
+        app.total_results = 15;
+
+        type_text(&mut app, "4-2a");
+        let ids: Vec<&str> = app.queue.iter().map(|track| track.id.as_str()).collect();
+        assert_eq!(ids, ["id2", "id3", "id4"]);
+        assert_eq!(app.status_message.as_deref(), Some("Queued 3 tracks"));
+        assert!(app.player_manager.is_some());
+        assert!(app.number_input.is_empty());
+
+        // Out-of-page numbers pick nothing.
+        type_text(&mut app, "9-40a");
+        assert_eq!(app.queue.len(), 3);
+
+        app.page = 1;
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('e')));
+        assert_eq!(app.queue.len(), 8);
+        assert_eq!(app.queue.get(3).unwrap().id, "id11");
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Queued 5 tracks from this page")
+        );
+
+        type_text(&mut app, "12-13");
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Enter));
+        assert_eq!(app.queue.len(), 10);
+        assert_eq!(app.pending_action, AppAction::None);
+    }
+
+    fn type_text(app: &mut App, text: &str) {
+        for c in text.chars() {
+            handle_key_event(app, KeyEvent::from(KeyCode::Char(c)));
+        }
+    }
+
+    #[test]
+    fn saving_the_queue_as_a_playlist_prompts_for_a_unique_name() {
+        let mut app = App::new("test".to_string(), 10, Config::default());
+        app.focused_panel = FocusedPanel::Playlists;
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('N')));
+        assert!(app.playlist_prompt.is_none());
+        assert!(app.status_message.as_deref().unwrap().contains("empty"));
+
+        for track in create_test_results(2) {
+            app.queue.push_back(track);
+        }
+        app.playlists.create("Zzz", create_test_results(1)).unwrap();
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('N')));
+        type_text(&mut app, "zzz");
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Enter));
+
+        // A clash keeps the prompt open with the error shown.
+        assert!(app.playlist_prompt.is_some());
+        assert!(
+            app.status_message
+                .as_deref()
+                .unwrap()
+                .contains("already exists")
+        );
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Tab));
+        assert_eq!(app.focused_panel, FocusedPanel::Playlists);
+        for _ in 0..3 {
+            handle_key_event(&mut app, KeyEvent::from(KeyCode::Backspace));
+        }
+        type_text(&mut app, "Mix");
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Enter));
+
+        assert!(app.playlist_prompt.is_none());
+        assert_eq!(app.playlist_selected_index, 0);
+        let saved = app.playlists.get(0).unwrap();
+        assert_eq!(saved.name, "Mix");
+        assert_eq!(saved.tracks.len(), 2);
+    }
+
+    #[test]
+    fn playlist_keys_append_rename_and_confirm_deletion() {
+        let mut app = App::new("test".to_string(), 10, Config::default());
+        app.focused_panel = FocusedPanel::Playlists;
+        app.playlists.create("Mix", create_test_results(1)).unwrap();
+        for track in create_test_results(3) {
+            app.queue.push_back(track);
+        }
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('a')));
+        assert_eq!(app.playlists.get(0).unwrap().tracks.len(), 4);
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('r')));
+        assert_eq!(app.playlist_prompt.as_ref().unwrap().input, "Mix");
+        type_text(&mut app, "tape");
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Enter));
+        assert_eq!(app.playlists.get(0).unwrap().name, "Mixtape");
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Delete));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('x')));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Esc));
+        assert_eq!(app.playlists.len(), 1);
+        assert!(!app.should_quit);
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('d')));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('y')));
+        assert!(app.playlists.is_empty());
+        assert_eq!(app.queue.len(), 3);
+    }
+
+    #[test]
+    fn enter_on_a_playlist_appends_it_behind_the_playing_track() {
+        let (client_stream, _server_stream) = UnixStream::pair().unwrap();
+        let mut app = App::new("test".to_string(), 10, Config::default());
+        app.player_manager = Some(PlayerManager::from_test_stream(client_stream));
+        app.queue.push_back(create_test_track("playing", "Playing"));
+        app.focused_panel = FocusedPanel::Playlists;
+        app.playlists.create("Mix", create_test_results(2)).unwrap();
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Enter));
+
+        let ids: Vec<&str> = app.queue.iter().map(|track| track.id.as_str()).collect();
+        assert_eq!(ids, ["playing", "id1", "id2"]);
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Queued 2 tracks from \"Mix\"")
+        );
+    }
+
+    #[test]
+    fn history_keys_replay_requeue_and_remove_entries() {
+        let (client_stream, _server_stream) = UnixStream::pair().unwrap();
+        let mut app = App::new("test".to_string(), 10, Config::default());
+        app.player_manager = Some(PlayerManager::from_test_stream(client_stream));
+        app.queue.push_back(create_test_track("playing", "Playing"));
+        for track in create_test_results(2) {
+            app.watch_history.start(track);
+        }
+        app.focused_panel = FocusedPanel::History;
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('a')));
+        let ids: Vec<&str> = app.queue.iter().map(|track| track.id.as_str()).collect();
+        assert_eq!(ids, ["playing", "id2"]);
+        assert_eq!(app.status_message.as_deref(), Some("Queued \"Video 2\""));
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('j')));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Enter));
+        let ids: Vec<&str> = app.queue.iter().map(|track| track.id.as_str()).collect();
+        assert_eq!(ids, ["id1", "playing", "id2"]);
+        assert_eq!(app.watch_history_selected_index, 0);
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('G')));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('d')));
+        assert_eq!(app.watch_history.len(), 1);
+        assert_eq!(app.watch_history.get(0).unwrap().track.id, "id2");
+        assert_eq!(app.watch_history_selected_index, 0);
+    }
+
+    #[test]
+    fn rebound_playlist_and_history_keys_replace_the_defaults() {
+        let mut app = App::new("test".to_string(), 10, Config::default());
+        app.config.keys =
+            toml::from_str("[playlists]\nrename = \"ctrl+r\"\n[history]\nremove = \"x\"\n")
+                .unwrap();
+        app.keymap = app.config.keymap().unwrap();
+        app.playlists.create("Mix", create_test_results(1)).unwrap();
+        app.focused_panel = FocusedPanel::Playlists;
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('r')));
+        assert!(app.playlist_prompt.is_none());
+        handle_key_event(
+            &mut app,
+            KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL),
+        );
+        assert_eq!(app.playlist_prompt.as_ref().unwrap().input, "Mix");
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Esc));
+
+        for track in create_test_results(2) {
+            app.watch_history.start(track);
+        }
+        app.focused_panel = FocusedPanel::History;
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('d')));
+        assert_eq!(app.watch_history.len(), 2);
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('x')));
+        assert_eq!(app.watch_history.len(), 1);
+    }
+
+    #[test]
+    fn timestamp_prompt_opens_only_for_an_active_player() {
+        let mut app = App::new("test".to_string(), 10, Config::default());
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('t')));
+        assert!(app.timestamp_input.is_none());
+
+        let (client_stream, _server_stream) = UnixStream::pair().unwrap();
+        let mut inactive_player = PlayerManager::from_test_stream(client_stream);
+        inactive_player.current_video_id = None;
+        app.player_manager = Some(inactive_player);
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('t')));
+        assert!(app.timestamp_input.is_none());
+
+        app.player_manager.as_mut().unwrap().current_video_id = Some("video-id".to_string());
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('t')));
+        assert_eq!(app.timestamp_input.as_deref(), Some(""));
+    }
+
+    #[test]
+    fn timestamp_prompt_handles_text_backspace_invalid_input_and_escape() {
+        let (client_stream, _server_stream) = UnixStream::pair().unwrap();
+        let mut app = App::new("test".to_string(), 10, Config::default());
+        app.player_manager = Some(PlayerManager::from_test_stream(client_stream));
+        app.timestamp_input = Some(String::new());
+
+        for c in "123456789".chars() {
+            handle_key_event(&mut app, KeyEvent::from(KeyCode::Char(c)));
+        }
+        assert_eq!(app.timestamp_input.as_deref(), Some("12345678"));
+        app.timestamp_input = Some(String::new());
+
+        for c in "12:3x".chars() {
+            handle_key_event(&mut app, KeyEvent::from(KeyCode::Char(c)));
+        }
+        assert_eq!(app.timestamp_input.as_deref(), Some("12:3"));
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Backspace));
+        assert_eq!(app.timestamp_input.as_deref(), Some("12:"));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Enter));
+        assert_eq!(app.status_message.as_deref(), Some("Invalid timestamp"));
+        assert_eq!(app.timestamp_input.as_deref(), Some("12:"));
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Esc));
+        assert!(app.timestamp_input.is_none());
+    }
+
+    #[test]
+    fn timestamp_enter_seeks_absolute_and_clamps_to_duration() {
+        let (mut app, server) = app_with_command_capture(Config::default(), 100.0);
+        app.timestamp_input = Some("2:30".to_string());
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Enter));
+
+        assert!(app.timestamp_input.is_none());
+        assert_eq!(server.join().unwrap(), json!(["seek", "100", "absolute"]));
+    }
+
+    fn with_chapters(app: &mut App, starts: &[f64], playing: Option<usize>) {
+        let status = &mut app.player_manager.as_mut().unwrap().status;
+        status.chapters = starts
+            .iter()
+            .map(|&start| Chapter {
+                start,
+                title: format!("At {start}"),
+            })
+            .collect();
+        status.chapter = playing;
+    }
+
+    #[test]
+    fn bracket_keys_jump_to_the_next_and_previous_chapter() {
+        let (mut app, server) = app_with_command_capture(Config::default(), 600.0);
+        with_chapters(&mut app, &[0.0, 120.0, 300.0], Some(0));
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char(']')));
+        assert_eq!(server.join().unwrap(), json!(["seek", "120", "absolute"]));
+        let status = &app.player_manager.as_ref().unwrap().status;
+        assert_eq!(status.chapter, Some(1));
+
+        let (mut app, _server) = app_with_command_capture(Config::default(), 600.0);
+        with_chapters(&mut app, &[], None);
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('[')));
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("This video has no chapters")
+        );
+    }
+
+    #[test]
+    fn the_chapter_picker_seeks_to_the_chosen_chapter() {
+        let (mut app, server) = app_with_command_capture(Config::default(), 600.0);
+        with_chapters(&mut app, &[0.0, 120.0, 300.0], Some(1));
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('C')));
+        assert_eq!(app.chapter_picker, Some(1));
+        for _ in 0..3 {
+            handle_key_event(&mut app, KeyEvent::from(KeyCode::Down));
+        }
+        assert_eq!(app.chapter_picker, Some(2));
+        // The picker has the keyboard, so `q` closes it instead of quitting.
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('q')));
+        assert!(app.chapter_picker.is_none() && !app.should_quit);
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('C')));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('k')));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Enter));
+        assert!(app.chapter_picker.is_none());
+        assert_eq!(server.join().unwrap(), json!(["seek", "0", "absolute"]));
+    }
+
+    fn canned_transcript(
+        _: &str,
+        language: &str,
+        _: &std::sync::atomic::AtomicBool,
+    ) -> anyhow::Result<Transcript> {
+        let cue = |start: f64, text: &str| Cue {
+            start,
+            end: start + 5.0,
+            text: format!("{text} ({language})"),
+        };
+        let language = |code: &str, name: &str, automatic| Language {
+            code: code.to_string(),
+            name: name.to_string(),
+            automatic,
+        };
+        Ok(Transcript {
+            cues: vec![
+                cue(0.0, "Hello there"),
+                cue(10.0, "General Kenobi"),
+                cue(20.0, "You are a bold one"),
+                cue(30.0, "Hello again"),
+            ],
+            languages: vec![
+                language("en", "English", false),
+                language("de", "German", true),
+            ],
+        })
+    }
+
+    /// Read the canned transcript for the playing track, as the runner would.
+    fn with_transcript(app: &mut App) {
+        app.subtitles = Subtitles::with_fetcher(canned_transcript);
+        wait_until("subtitles never arrived", || {
+            app.subtitles
+                .update(Some("abcdefghijk"), &app.config.subtitle_language, true);
+            app.subtitles.transcript().is_some()
+        });
+    }
+
+    #[test]
+    fn the_transcript_searches_lines_and_seeks_to_the_chosen_one() {
+        let (mut app, server) = app_with_command_capture(Config::default(), 600.0);
+        with_transcript(&mut app);
+        app.player_manager.as_mut().unwrap().status.time_pos = 12.0;
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('T')));
+        // The view opens on the line being spoken.
+        assert_eq!(transcript_selection(&app), Some(1));
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('/')));
+        for c in "HELLO".chars() {
+            handle_key_event(&mut app, KeyEvent::from(KeyCode::Char(c)));
+        }
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Enter));
+        assert_eq!(transcript_selection(&app), Some(3));
+        // `n` wraps around to the first match and `N` goes back.
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('n')));
+        assert_eq!(transcript_selection(&app), Some(0));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('N')));
+        assert_eq!(transcript_selection(&app), Some(3));
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('/')));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('x')));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Enter));
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("No line contains \"x\"")
+        );
+        assert_eq!(transcript_selection(&app), Some(3));
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Enter));
+        assert!(app.transcript.is_none());
+        assert_eq!(server.join().unwrap(), json!(["seek", "30", "absolute"]));
+    }
+
+    #[test]
+    fn subtitles_toggle_and_the_language_picker_saves_the_choice() {
+        let (mut app, _server) = app_with_command_capture(Config::default(), 600.0);
+        with_transcript(&mut app);
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('l')));
+        assert!(app.config.subtitles);
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('L')));
+        assert_eq!(app.language_picker, Some(0));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('j')));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Enter));
+        assert!(app.language_picker.is_none());
+        assert_eq!(app.config.subtitle_language, "de");
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('l')));
+        assert!(!app.config.subtitles);
+    }
+
+    #[test]
+    fn pickers_close_on_the_key_that_opened_them() {
+        let (mut app, _server) = app_with_command_capture(Config::default(), 600.0);
+        app.config.keys = toml::from_str(
+            "[global]\nchapters = \"f4\"\nsubtitle_language = \"f5\"\ntranscript = \"f6\"\n",
+        )
+        .unwrap();
+        app.keymap = app.config.keymap().unwrap();
+        with_chapters(&mut app, &[0.0, 120.0], Some(0));
+        with_transcript(&mut app);
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::F(4)));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('C')));
+        assert!(app.chapter_picker.is_some());
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::F(4)));
+        assert!(app.chapter_picker.is_none());
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::F(5)));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('L')));
+        assert!(app.language_picker.is_some());
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::F(5)));
+        assert!(app.language_picker.is_none());
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::F(6)));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('T')));
+        assert!(app.transcript.is_some());
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::F(6)));
+        assert!(app.transcript.is_none());
+    }
+
+    #[test]
+    fn capital_r_takes_the_resume_offer_for_the_playing_track() {
+        let (mut app, server) = app_with_command_capture(Config::default(), 600.0);
+        app.focused_panel = FocusedPanel::Queue;
+        app.resume_offer = Some(crate::ui::app::ResumeOffer {
+            video_id: "video-id".to_string(),
+            position: 300.0,
+        });
 
-        let (client_stream, _server_stream) = UnixStream::pair().unwrap();
-        let mut inactive_player = PlayerManager::from_test_stream(client_stream);
-        inactive_player.current_video_id = None;
-        app.player_manager = Some(inactive_player);
-        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('t')));
-        assert!(app.timestamp_input.is_none());
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('R')));
 
-        app.player_manager.as_mut().unwrap().current_video_id = Some("video-id".to_string());
-        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('t')));
-        assert_eq!(app.timestamp_input.as_deref(), Some(""));
+        assert!(app.resume_offer.is_none());
+        assert_eq!(server.join().unwrap(), json!(["seek", "300", "absolute"]));
     }
 
     #[test]
-    fn timestamp_prompt_handles_text_backspace_invalid_input_and_escape() {
-        let (client_stream, _server_stream) = UnixStream::pair().unwrap();
-        let mut app = App::new("test".to_string(), 10, Config::default());
-        app.player_manager = Some(PlayerManager::from_test_stream(client_stream));
-        app.timestamp_input = Some(String::new());
-
-        for c in "123456789".chars() {
-            handle_key_event(&mut app, KeyEvent::from(KeyCode::Char(c)));
-        }
-        assert_eq!(app.timestamp_input.as_deref(), Some("12345678"));
-        app.timestamp_input = Some(String::new());
-
-        for c in "12:3x".chars() {
-            handle_key_event(&mut app, KeyEvent::from(KeyCode::Char(c)));
+    fn p_restarts_a_track_that_has_played_for_a_while() {
+        let (mut app, server) = app_with_command_capture(Config::default(), 600.0);
+        app.focused_panel = FocusedPanel::Queue;
+        for track in create_test_results(2) {
+            app.queue.push_back(track);
         }
-        assert_eq!(app.timestamp_input.as_deref(), Some("12:3"));
+        app.queue.advance(RepeatMode::Off, false);
+        app.player_manager.as_mut().unwrap().status.time_pos = 30.0;
 
-        handle_key_event(&mut app, KeyEvent::from(KeyCode::Backspace));
-        assert_eq!(app.timestamp_input.as_deref(), Some("12:"));
-        handle_key_event(&mut app, KeyEvent::from(KeyCode::Enter));
-        assert_eq!(app.status_message.as_deref(), Some("Invalid timestamp"));
-        assert_eq!(app.timestamp_input.as_deref(), Some("12:"));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('p')));
 
-        handle_key_event(&mut app, KeyEvent::from(KeyCode::Esc));
-        assert!(app.timestamp_input.is_none());
+        assert_eq!(server.join().unwrap(), json!(["seek", "0", "absolute"]));
+        assert_eq!(app.queue.len(), 1);
+        assert!(app.queue.has_previous());
     }
 
     #[test]
-    fn timestamp_enter_seeks_absolute_and_clamps_to_duration() {
-        let (mut app, server) = app_with_command_capture(Config::default(), 100.0);
-        app.timestamp_input = Some("2:30".to_string());
+    fn p_reports_when_there_is_nothing_to_go_back_to() {
+        let mut app = App::new("test".to_string(), 10, Config::default());
+        app.focused_panel = FocusedPanel::Queue;
 
-        handle_key_event(&mut app, KeyEvent::from(KeyCode::Enter));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('p')));
 
-        assert!(app.timestamp_input.is_none());
-        assert_eq!(server.join().unwrap(), json!(["seek", "100", "absolute"]));
+        assert_eq!(app.status_message.as_deref(), Some("No previous track"));
     }
 
     #[test]
@@ -1349,6 +3026,12 @@ mod tests {
         cycle_focus_forward(&mut app);
         assert_eq!(app.focused_panel, FocusedPanel::Queue);
 
+        cycle_focus_forward(&mut app);
+        assert_eq!(app.focused_panel, FocusedPanel::Playlists);
+
+        cycle_focus_forward(&mut app);
+        assert_eq!(app.focused_panel, FocusedPanel::History);
+
         cycle_focus_forward(&mut app);
         assert_eq!(app.focused_panel, FocusedPanel::SearchBar);
     }
@@ -1358,6 +3041,12 @@ mod tests {
         let mut app = App::new("test".to_string(), 10, Config::default());
         app.focused_panel = FocusedPanel::SearchBar;
 
+        cycle_focus_backward(&mut app);
+        assert_eq!(app.focused_panel, FocusedPanel::History);
+
+        cycle_focus_backward(&mut app);
+        assert_eq!(app.focused_panel, FocusedPanel::Playlists);
+
         cycle_focus_backward(&mut app);
         assert_eq!(app.focused_panel, FocusedPanel::Queue);
 
@@ -1486,11 +3175,27 @@ mod tests {
         assert!(app.config.audio_only);
     }
 
+    #[test]
+    fn settings_theme_row_cycles_and_applies_the_built_in_themes() {
+        let mut app = App::new("test query".to_string(), 10, Config::default());
+        app.settings_open = true;
+        app.settings_selected_index = 22;
+
+        handle_browse_keys(&mut app, KeyEvent::from(KeyCode::Enter));
+        assert_eq!(app.config.theme, "light");
+        assert_eq!(app.theme, Theme::load("light").unwrap());
+
+        for _ in 0..3 {
+            handle_browse_keys(&mut app, KeyEvent::from(KeyCode::Enter));
+        }
+        assert_eq!(app.config.theme, "dark");
+    }
+
     #[test]
     fn test_settings_enter_edit_mode() {
         let mut app = App::new("test query".to_string(), 10, Config::default());
         app.settings_open = true;
-        app.settings_selected_index = 13; // Download Dir (index 12 is Download Mode checkbox)
+        app.settings_selected_index = 14; // Download Dir (index 13 is Download Mode checkbox)
 
         let key = KeyEvent::from(KeyCode::Enter);
         handle_browse_keys(&mut app, key);
@@ -1530,13 +3235,13 @@ mod tests {
         handle_browse_keys(&mut app, key);
         assert!(app.config.bandwidth_limit);
 
-        // Navigate to download dir (index 13)
-        // From index 3: 4→5→6→7→8→12→13 (7 down presses; index 12 is Download Mode checkbox)
-        for _ in 0..7 {
+        // Navigate to download dir (index 14)
+        // From index 3: 4→5→6→7→8→9→13→14 (8 down presses; index 13 is Download Mode checkbox)
+        for _ in 0..8 {
             let key = KeyEvent::from(KeyCode::Down);
             handle_browse_keys(&mut app, key);
         }
-        assert_eq!(app.settings_selected_index, 13);
+        assert_eq!(app.settings_selected_index, 14);
 
         // Enter edit mode
         let key = KeyEvent::from(KeyCode::Enter);
@@ -1821,6 +3526,144 @@ mod tests {
         assert_eq!(app.queue_selected_index, 0);
     }
 
+    // --- Queue reordering and marks ---
+
+    fn queue_ids(app: &App) -> Vec<&str> {
+        app.queue.iter().map(|track| track.id.as_str()).collect()
+    }
+
+    fn app_with_queue(ids: &[&str]) -> App {
+        let mut app = App::new("test".to_string(), 10, Config::default());
+        for id in ids {
+            app.queue.push_back(create_test_track(id, id));
+        }
+        app.focused_panel = FocusedPanel::Queue;
+        app
+    }
+
+    #[test]
+    fn rebound_keys_replace_the_defaults_they_override() {
+        let config: Config =
+            toml::from_str("[keys.queue]\nmove_down = \"ctrl+down\"\nmark = [\"y\"]\n").unwrap();
+        let mut app = App::new("test".to_string(), 10, config);
+        for id in ["1", "2", "3"] {
+            app.queue.push_back(create_test_track(id, id));
+        }
+        app.focused_panel = FocusedPanel::Queue;
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('J')));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('x')));
+        assert_eq!(queue_ids(&app), ["1", "2", "3"]);
+        assert_eq!(app.queue.marked_count(), 0);
+
+        handle_key_event(
+            &mut app,
+            KeyEvent::new(KeyCode::Down, KeyModifiers::CONTROL),
+        );
+        assert_eq!(queue_ids(&app), ["2", "1", "3"]);
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('y')));
+        assert!(app.queue.is_marked(1));
+        // Untouched actions keep their default keys.
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('K')));
+        assert_eq!(queue_ids(&app), ["1", "2", "3"]);
+    }
+
+    #[test]
+    fn shift_j_and_k_carry_the_selected_track() {
+        let mut app = app_with_queue(&["1", "2", "3"]);
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('J')));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('J')));
+        assert_eq!(queue_ids(&app), ["2", "3", "1"]);
+        assert_eq!(app.queue_selected_index, 2);
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('J')));
+        assert_eq!(queue_ids(&app), ["2", "3", "1"]);
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('K')));
+        assert_eq!(queue_ids(&app), ["2", "1", "3"]);
+        assert_eq!(app.queue_selected_index, 1);
+    }
+
+    #[test]
+    fn the_playing_track_keeps_the_front_while_reordering() {
+        let (client_stream, _server_stream) = UnixStream::pair().unwrap();
+        let mut app = app_with_queue(&["playing", "a", "b", "c"]);
+        app.player_manager = Some(PlayerManager::from_test_stream(client_stream));
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('J')));
+        assert_eq!(queue_ids(&app), ["playing", "a", "b", "c"]);
+
+        app.queue_selected_index = 2;
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('x')));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('x')));
+        assert_eq!(app.queue.marked_count(), 2);
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('K')));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('K')));
+        assert_eq!(queue_ids(&app), ["playing", "b", "c", "a"]);
+        assert_eq!(app.queue_selected_index, 2);
+    }
+
+    #[test]
+    fn grabbed_tracks_follow_the_cursor_until_dropped() {
+        let mut app = app_with_queue(&["1", "2", "3", "4"]);
+        app.queue_selected_index = 1;
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('M')));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Down));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('j')));
+        // Tab and Esc belong to the grab, not focus cycling or quitting.
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Tab));
+        assert_eq!(app.focused_panel, FocusedPanel::Queue);
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Esc));
+
+        assert!(!app.should_quit);
+        assert!(app.queue_grab.is_none());
+        assert_eq!(queue_ids(&app), ["1", "3", "4", "2"]);
+        assert_eq!(app.queue_selected_index, 3);
+        assert_eq!(app.queue.marked_count(), 0);
+    }
+
+    #[test]
+    fn delete_removes_every_marked_track() {
+        let mut app = app_with_queue(&["1", "2", "3", "4"]);
+        app.queue.toggle_mark(1);
+        app.queue.toggle_mark(3);
+        app.queue_selected_index = 3;
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Delete));
+
+        assert_eq!(queue_ids(&app), ["1", "3"]);
+        assert_eq!(app.queue_selected_index, 1);
+        assert_eq!(app.status_message.as_deref(), Some("Removed 2 tracks"));
+
+        app.queue.toggle_mark(0);
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('X')));
+        assert_eq!(app.queue.marked_count(), 0);
+    }
+
+    #[test]
+    fn capital_n_plays_a_result_right_after_the_current_track() {
+        let (client_stream, _server_stream) = UnixStream::pair().unwrap();
+        let mut app = app_with_queue(&["playing", "later"]);
+        app.player_manager = Some(PlayerManager::from_test_stream(client_stream));
+        app.queue_selected_index = 1;
+        app.results = create_test_results(3);
+        app.total_results = 3;
+        app.focused_panel = FocusedPanel::Results;
+        app.selected_index = 2;
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('N')));
+
+        assert_eq!(queue_ids(&app), ["playing", "id3", "later"]);
+        assert_eq!(app.queue_selected_index, 2);
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Playing next: \"Video 3\"")
+        );
+        assert_eq!(app.pending_action, AppAction::None);
+    }
+
     // --- Queue Enter (play / reorder) ---
 
     #[test]
@@ -1870,6 +3713,10 @@ mod tests {
         cycle_focus_forward(&mut app);
         assert_eq!(app.focused_panel, FocusedPanel::Queue);
         cycle_focus_forward(&mut app);
+        assert_eq!(app.focused_panel, FocusedPanel::Playlists);
+        cycle_focus_forward(&mut app);
+        assert_eq!(app.focused_panel, FocusedPanel::History);
+        cycle_focus_forward(&mut app);
         assert_eq!(app.focused_panel, FocusedPanel::SearchBar);
     }
 
@@ -1881,6 +3728,10 @@ mod tests {
         cycle_focus_backward(&mut app);
         assert_eq!(app.focused_panel, FocusedPanel::SearchBar);
         cycle_focus_backward(&mut app);
+        assert_eq!(app.focused_panel, FocusedPanel::History);
+        cycle_focus_backward(&mut app);
+        assert_eq!(app.focused_panel, FocusedPanel::Playlists);
+        cycle_focus_backward(&mut app);
         assert_eq!(app.focused_panel, FocusedPanel::Queue);
         cycle_focus_backward(&mut app);
         assert_eq!(app.focused_panel, FocusedPanel::Results);
@@ -1932,6 +3783,152 @@ mod tests {
         assert_eq!(app.query, "new query");
     }
 
+    #[test]
+    fn test_video_urls_and_ids_open_directly_instead_of_searching() {
+        let mut app = App::new("old".to_string(), 10, Config::default());
+        app.focused_panel = FocusedPanel::SearchBar;
+
+        app.search_input = "https://youtu.be/dQw4w9WgXcQ?t=1m30s".to_string();
+        handle_search_bar_keys(&mut app, KeyEvent::from(KeyCode::Enter));
+        assert_eq!(
+            app.pending_action,
+            AppAction::OpenVideo {
+                video_id: "dQw4w9WgXcQ".to_string(),
+                start: Some(90.0),
+                fallback_query: None,
+            }
+        );
+        assert_eq!(app.search_phase, Some(SearchPhase::LookingUpVideo));
+        assert_eq!(app.focused_panel, FocusedPanel::Results);
+
+        app.search_input = "dQw4w9WgXcQ".to_string();
+        handle_search_bar_keys(&mut app, KeyEvent::from(KeyCode::Enter));
+        assert_eq!(
+            app.pending_action,
+            AppAction::OpenVideo {
+                video_id: "dQw4w9WgXcQ".to_string(),
+                start: None,
+                fallback_query: Some("dQw4w9WgXcQ".to_string()),
+            }
+        );
+
+        app.search_input = "programming".to_string();
+        handle_search_bar_keys(&mut app, KeyEvent::from(KeyCode::Enter));
+        assert_eq!(
+            app.pending_action,
+            AppAction::NewSearch("programming".to_string())
+        );
+    }
+
+    #[test]
+    fn test_filter_editor_applies_filters_and_reruns_the_search() {
+        let mut app = App::new("lofi".to_string(), 10, Config::default());
+        app.focused_panel = FocusedPanel::Results;
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('f')));
+        for code in [
+            KeyCode::Right,
+            KeyCode::Down,
+            KeyCode::Left,
+            KeyCode::Down,
+            KeyCode::Down,
+            KeyCode::Char(' '),
+        ] {
+            handle_key_event(&mut app, KeyEvent::from(code));
+        }
+        // Editing does not touch the applied filters until Enter.
+        assert_eq!(app.search_filters, SearchFilters::default());
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Enter));
+        assert!(app.filter_editor.is_none());
+        assert_eq!(
+            app.search_filters,
+            SearchFilters {
+                sort: SortOrder::Date,
+                duration: DurationFilter::Long,
+                live: LiveFilter::LiveOnly,
+                ..SearchFilters::default()
+            }
+        );
+        assert_eq!(app.pending_action, AppAction::NewSearch("lofi".to_string()));
+
+        app.pending_action = AppAction::None;
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('f')));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('x')));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Esc));
+        assert!(app.filter_editor.is_none());
+        assert!(!app.search_filters.is_default());
+        assert_eq!(app.pending_action, AppAction::None);
+    }
+
+    #[test]
+    fn test_search_history_is_recorded_and_recalled_with_up_and_down() {
+        let mut app = App::new(String::new(), 10, Config::default());
+        for query in ["first", "second"] {
+            app.focused_panel = FocusedPanel::SearchBar;
+            app.search_input = query.to_string();
+            handle_key_event(&mut app, KeyEvent::from(KeyCode::Enter));
+        }
+        app.focused_panel = FocusedPanel::SearchBar;
+        app.search_input = "draft".to_string();
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Up));
+        assert_eq!(app.search_input, "second");
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Up));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Up));
+        assert_eq!(app.search_input, "first");
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Down));
+        assert_eq!(app.search_input, "second");
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Down));
+        assert_eq!(app.search_input, "draft");
+
+        app.search_input = "fi".to_string();
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Right));
+        assert_eq!(app.search_input, "first");
+    }
+
+    #[test]
+    fn test_ctrl_r_fuzzy_finds_and_reruns_a_past_search() {
+        let mut app = App::new(String::new(), 10, Config::default());
+        for query in ["lofi hip hop", "jazz piano", "lo-fi beats"] {
+            app.focused_panel = FocusedPanel::SearchBar;
+            app.search_input = query.to_string();
+            handle_key_event(&mut app, KeyEvent::from(KeyCode::Enter));
+        }
+        app.focused_panel = FocusedPanel::SearchBar;
+
+        handle_key_event(
+            &mut app,
+            KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL),
+        );
+        for c in "lfi".chars() {
+            handle_key_event(&mut app, KeyEvent::from(KeyCode::Char(c)));
+        }
+        assert_eq!(app.history_search_match(), Some("lofi hip hop"));
+        handle_key_event(
+            &mut app,
+            KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL),
+        );
+        assert_eq!(app.history_search_match(), Some("lo-fi beats"));
+
+        // Tab and Esc belong to the history search while it is open.
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Esc));
+        assert!(app.history_search.is_none());
+        assert_eq!(app.focused_panel, FocusedPanel::SearchBar);
+
+        handle_key_event(
+            &mut app,
+            KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL),
+        );
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('z')));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Enter));
+        assert_eq!(
+            app.pending_action,
+            AppAction::NewSearch("jazz piano".to_string())
+        );
+        assert!(app.history_search.is_none());
+    }
+
     #[test]
     fn test_search_bar_backspace_removes_last_char() {
         let mut app = App::new("test".to_string(), 10, Config::default());
@@ -2020,4 +4017,160 @@ mod tests {
             Some("Download directory cannot be empty")
         );
     }
+
+    #[test]
+    fn i_opens_details_for_the_selected_result_queue_track_or_playing_track() {
+        let mut app = app_with_queue(&["a", "b"]);
+        app.results = create_test_results(3);
+        app.selected_index = 1;
+        app.focused_panel = FocusedPanel::Results;
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('i')));
+        assert_eq!(app.details.as_ref().unwrap().track.id, "id2");
+
+        // The view owns the keyboard until it is closed.
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('a')));
+        assert!(app.queue.len() == 2 && app.details.is_some());
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Esc));
+        assert!(app.details.is_none() && !app.should_quit);
+
+        app.focused_panel = FocusedPanel::Queue;
+        app.queue_selected_index = 1;
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('i')));
+        assert_eq!(app.details.as_ref().unwrap().track.id, "b");
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('i')));
+        assert!(app.details.is_none());
+
+        app.video_view = true;
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('i')));
+        assert!(app.details.is_none());
+        assert!(app.status_message.is_some());
+    }
+
+    #[test]
+    fn details_scroll_stops_where_the_text_runs_out() {
+        let mut app = App::new("test".to_string(), 10, Config::default());
+        app.details = Some(DetailsView::new(create_test_track("a", "A")));
+        app.screen_areas.borrow_mut().details_scroll_limit = 12;
+        let scroll = |app: &App| app.details.as_ref().unwrap().scroll;
+
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Up));
+        assert_eq!(scroll(&app), 0);
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('j')));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::PageDown));
+        assert_eq!(scroll(&app), 11);
+        assert!(handle_mouse_event(
+            &mut app,
+            mouse(MouseEventKind::ScrollDown, 0, 0)
+        ));
+        assert_eq!(scroll(&app), 12);
+        assert!(!handle_mouse_event(
+            &mut app,
+            mouse(MouseEventKind::ScrollDown, 0, 0)
+        ));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Home));
+        assert_eq!(scroll(&app), 0);
+    }
+
+    // --- Mouse ---
+
+    fn draw(app: &App) {
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal.draw(|frame| render_ui(frame, app)).unwrap();
+    }
+
+    fn drawn_list(app: &App, panel: FocusedPanel) -> Rect {
+        let areas = app.screen_areas.borrow();
+        areas
+            .lists
+            .iter()
+            .find(|list| list.panel == panel)
+            .map(|list| list.area)
+            .unwrap()
+    }
+
+    fn mouse(kind: MouseEventKind, column: u16, row: u16) -> MouseEvent {
+        MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    fn left_click(column: u16, row: u16) -> MouseEvent {
+        mouse(MouseEventKind::Down(MouseButton::Left), column, row)
+    }
+
+    #[test]
+    fn clicks_focus_panels_select_rows_and_double_clicks_play() {
+        let mut app = app_with_queue(&["a", "b"]);
+        app.results = create_test_results(5);
+        draw(&app);
+
+        let queue = drawn_list(&app, FocusedPanel::Queue);
+        assert!(handle_mouse_event(
+            &mut app,
+            left_click(queue.x + 3, queue.y + 2)
+        ));
+        assert_eq!(app.queue_selected_index, 1);
+
+        // Results take two lines each; this is the second line of the third.
+        let results = drawn_list(&app, FocusedPanel::Results);
+        let third = left_click(results.x + 3, results.y + 6);
+        handle_mouse_event(&mut app, third);
+        assert_eq!(app.focused_panel, FocusedPanel::Results);
+        assert_eq!(app.selected_index, 2);
+        assert_eq!(app.pending_action, AppAction::None);
+        handle_mouse_event(&mut app, third);
+        assert_eq!(app.pending_action, AppAction::Play(2));
+
+        handle_mouse_event(&mut app, left_click(results.x + 3, 1));
+        assert_eq!(app.focused_panel, FocusedPanel::SearchBar);
+    }
+
+    #[test]
+    fn the_wheel_scrolls_the_list_under_the_pointer_and_turns_pages() {
+        let mut app = App::new("test".to_string(), 10, Config::default());
+        app.results = create_test_results(15);
+        app.exhausted = true;
+        app.focused_panel = FocusedPanel::Queue;
+        draw(&app);
+        let results = drawn_list(&app, FocusedPanel::Results);
+        let scroll = |kind| mouse(kind, results.x + 3, results.y + 3);
+
+        handle_mouse_event(&mut app, scroll(MouseEventKind::ScrollDown));
+        assert_eq!(app.focused_panel, FocusedPanel::Results);
+        assert_eq!(app.selected_index, 1);
+
+        app.selected_index = 9;
+        handle_mouse_event(&mut app, scroll(MouseEventKind::ScrollDown));
+        assert_eq!((app.page, app.selected_index), (1, 0));
+        handle_mouse_event(&mut app, scroll(MouseEventKind::ScrollUp));
+        assert_eq!((app.page, app.selected_index), (0, 9));
+    }
+
+    #[test]
+    fn clicking_the_progress_bar_seeks_to_that_point() {
+        let (mut app, server) = app_with_command_capture(Config::default(), 200.0);
+        draw(&app);
+        let bar = app.screen_areas.borrow().progress_bar.unwrap();
+
+        handle_mouse_event(&mut app, left_click(bar.right() - 1, bar.y));
+
+        assert_eq!(server.join().unwrap(), json!(["seek", "200", "absolute"]));
+    }
+
+    #[test]
+    fn the_mouse_is_ignored_while_a_modal_is_open() {
+        let mut app = app_with_queue(&["a", "b"]);
+        draw(&app);
+        let queue = drawn_list(&app, FocusedPanel::Queue);
+        app.settings_open = true;
+
+        assert!(!handle_mouse_event(
+            &mut app,
+            left_click(queue.x + 3, queue.y + 2)
+        ));
+        assert_eq!(app.queue_selected_index, 0);
+    }
 }
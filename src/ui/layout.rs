@@ -1,7 +1,18 @@
 use std::borrow::Cow;
 
-use crate::ui::app::{App, FocusedPanel, InputMode, SearchPhase, SettingsField};
+use crate::details::{DetailsState, DetailsView, group_digits};
+use crate::keymap::{Action, Context};
+use crate::queue::RepeatMode;
+use crate::session::QueueSnapshot;
+use crate::subtitles::SubtitleState;
+use crate::thumbnails::Preview;
+use crate::ui::app::{
+    App, FilterEditor, FocusedPanel, InputMode, ListArea, PlaylistPrompt, PlaylistPromptKind,
+    ScreenAreas, SearchPhase, SettingsField, TranscriptView,
+};
+use crate::ui::theme::Theme;
 use crate::video::{Frame as VideoFrame, VideoDisplay};
+use crate::watch_history::WatchMark;
 use ratatui::{
     Frame,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
@@ -9,22 +20,31 @@ use ratatui::{
     text::{Line, Span},
     widgets::{
         Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph, Scrollbar,
-        ScrollbarOrientation, ScrollbarState,
+        ScrollbarOrientation, ScrollbarState, Wrap,
     },
 };
 
 const MIN_USABLE_WIDTH: u16 = 24;
 const MIN_USABLE_HEIGHT: u16 = 8;
 const WIDE_LAYOUT_WIDTH: u16 = 72;
+/// Width of the thumbnail preview, border included, and the narrowest the
+/// results may get before the preview is left out.
+const PREVIEW_WIDTH: u16 = 36;
+const MIN_RESULTS_WIDTH: u16 = 44;
 
 pub fn render_ui(f: &mut Frame, app: &App) {
+    *app.screen_areas.borrow_mut() = ScreenAreas::default();
     let area = f.area();
     if area.width < MIN_USABLE_WIDTH || area.height < MIN_USABLE_HEIGHT {
-        render_small_terminal(f, area);
+        render_small_terminal(f, &app.theme, area);
         return;
     }
 
-    let footer_height = footer_height(app.player_manager.is_some(), area.height);
+    let footer_height = footer_height(
+        app.player_manager.is_some(),
+        app.config.subtitles,
+        area.height,
+    );
 
     if app.video_view {
         // The video widget takes over everywhere the search bar and
@@ -58,9 +78,37 @@ pub fn render_ui(f: &mut Frame, app: &App) {
     if app.settings_open {
         render_settings_modal(f, app);
     }
+
+    if let Some(prompt) = &app.playlist_prompt {
+        render_playlist_prompt(f, app, prompt);
+    }
+
+    if let Some(editor) = &app.filter_editor {
+        render_filter_editor(f, &app.theme, editor);
+    }
+
+    if let Some(details) = &app.details {
+        render_details(f, app, details);
+    }
+
+    if let Some(selected) = app.chapter_picker {
+        render_chapter_picker(f, app, selected);
+    }
+
+    if let Some(view) = &app.transcript {
+        render_transcript(f, app, view);
+    }
+
+    if let Some(selected) = app.language_picker {
+        render_language_picker(f, app, selected);
+    }
+
+    if let Some(snapshot) = &app.restore_prompt {
+        render_restore_prompt(f, &app.theme, snapshot);
+    }
 }
 
-fn render_small_terminal(f: &mut Frame, area: Rect) {
+fn render_small_terminal(f: &mut Frame, theme: &Theme, area: Rect) {
     let text = if area.height >= 3 {
         vec![
             Line::from("Terminal too small"),
@@ -72,38 +120,78 @@ fn render_small_terminal(f: &mut Frame, area: Rect) {
 
     let notice = Paragraph::new(text)
         .alignment(Alignment::Center)
-        .style(Style::default().fg(Color::Yellow))
+        .style(Style::default().fg(theme.highlight))
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
-                .border_style(Style::default().fg(Color::Cyan))
+                .border_style(Style::default().fg(theme.accent))
                 .title(" youtui "),
         );
     f.render_widget(notice, area);
 }
 
 fn render_search_bar(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    app.screen_areas.borrow_mut().search_bar = Some(area);
     let is_focused = app.focused_panel == FocusedPanel::SearchBar;
 
     let (border_style, title_style) = if is_focused {
         (
-            Style::default().fg(Color::Cyan),
+            Style::default().fg(theme.accent),
             Style::default()
-                .fg(Color::Cyan)
+                .fg(theme.accent)
                 .add_modifier(Modifier::BOLD),
         )
     } else {
         (
-            Style::default().fg(Color::DarkGray),
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(theme.muted),
+            Style::default().fg(theme.muted),
         )
     };
 
-    let display_text = if is_focused {
-        visible_input(&app.search_input, area.width.saturating_sub(2) as usize)
+    let available_width = area.width.saturating_sub(2) as usize;
+    let hint_style = Style::default().fg(theme.muted);
+    let (title, display_text) = if let Some(history_search) = &app.history_search {
+        let found = app.history_search_match();
+        let mut spans = vec![Span::raw(visible_input(
+            &history_search.pattern,
+            available_width / 2,
+        ))];
+        spans.push(Span::styled("  → ", hint_style));
+        spans.push(match found {
+            Some(query) => Span::styled(query.to_string(), Style::default().fg(theme.highlight)),
+            None => Span::styled("no match", hint_style),
+        });
+        (
+            " 🔍 History search (Ctrl+R older · Enter search · Esc cancel) ",
+            Line::from(spans),
+        )
+    } else if is_focused {
+        let mut spans = vec![Span::raw(visible_input(&app.search_input, available_width))];
+        // Show the rest of the newest matching past query after the cursor,
+        // when it fits; Right accepts it.
+        if let Some(rest) = app
+            .search_history
+            .completion(&app.search_input)
+            .map(|query| {
+                query
+                    .chars()
+                    .skip(app.search_input.chars().count())
+                    .collect::<String>()
+            })
+            .filter(|rest| {
+                Line::from(app.search_input.as_str()).width()
+                    + 1
+                    + Line::from(rest.as_str()).width()
+                    <= available_width
+            })
+        {
+            spans.push(Span::styled(rest, hint_style));
+        }
+        (" 🔍 Search ", Line::from(spans))
     } else {
-        app.query.clone()
+        (" 🔍 Search ", Line::from(app.query.clone()))
     };
 
     let search_bar = Paragraph::new(display_text)
@@ -111,10 +199,10 @@ fn render_search_bar(f: &mut Frame, app: &App, area: Rect) {
             Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
-                .title(Span::styled(" 🔍 Search ", title_style))
+                .title(Span::styled(title, title_style))
                 .border_style(border_style),
         )
-        .style(Style::default().fg(Color::White));
+        .style(Style::default().fg(theme.text));
 
     f.render_widget(search_bar, area);
 }
@@ -126,31 +214,139 @@ fn render_main_content(f: &mut Frame, app: &App, area: Rect) {
             .constraints([Constraint::Percentage(68), Constraint::Percentage(32)])
             .split(area);
 
-        render_results(f, app, chunks[0]);
-        render_queue_panel(f, app, chunks[1]);
+        render_results_and_preview(f, app, chunks[0]);
+        render_side_panels(f, app, chunks[1], Direction::Vertical);
     } else if area.height >= 20 {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
             .split(area);
 
-        render_results(f, app, chunks[0]);
-        render_queue_panel(f, app, chunks[1]);
+        render_results_and_preview(f, app, chunks[0]);
+        render_side_panels(f, app, chunks[1], Direction::Horizontal);
     } else if app.focused_panel == FocusedPanel::Queue {
         render_queue_panel(f, app, area);
+    } else if app.focused_panel == FocusedPanel::Playlists {
+        render_playlists_panel(f, app, area);
+    } else if app.focused_panel == FocusedPanel::History {
+        render_watch_history_panel(f, app, area);
     } else {
         // On short, narrow terminals, preserve a useful number of rows and show
         // the focused panel instead of squeezing both panels into unusability.
+        render_results_and_preview(f, app, area);
+    }
+}
+
+/// The results, with the selected result's thumbnail beside them when
+/// previews are on and there is room.
+fn render_results_and_preview(f: &mut Frame, app: &App, area: Rect) {
+    if !app.config.thumbnails || area.width < PREVIEW_WIDTH + MIN_RESULTS_WIDTH {
         render_results(f, app, area);
+        return;
+    }
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(0), Constraint::Length(PREVIEW_WIDTH)])
+        .split(area);
+    render_results(f, app, chunks[0]);
+    render_preview(f, app, chunks[1]);
+}
+
+fn render_preview(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(theme.muted))
+        .title(Span::styled(" Preview ", Style::default().fg(theme.muted)));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let Some(result) = app.current_page_results().get(app.selected_index) else {
+        return;
+    };
+    // Thumbnails are 16:9 and a cell is about twice as tall as it is wide.
+    let image_rows = (inner.width * 9).div_ceil(32).min(inner.height);
+    let image_area = Rect {
+        height: image_rows,
+        ..inner
+    };
+    app.screen_areas.borrow_mut().thumbnail = Some(image_area);
+    match app.thumbnails.preview(&result.id) {
+        Preview::Loading => render_video_message(f, image_area, "loading thumbnail…", theme.muted),
+        Preview::Unavailable => render_video_message(f, image_area, "no thumbnail", theme.muted),
+        Preview::Pixels(protocol) => {
+            f.render_widget(ratatui_image::Image::new(protocol), image_area)
+        }
+        Preview::Blocks(frame) => {
+            let offset = image_area.width.saturating_sub(frame.width) / 2;
+            let centered = Rect {
+                x: image_area.x + offset,
+                width: image_area.width - offset,
+                ..image_area
+            };
+            f.render_widget(Paragraph::new(frame_to_lines(frame)), centered);
+        }
+    }
+
+    let details = vec![
+        Line::from(Span::styled(
+            result.title.as_str(),
+            Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            result.channel.as_str(),
+            Style::default().fg(theme.accent),
+        )),
+        Line::from(vec![
+            Span::styled(
+                result.duration.as_str(),
+                Style::default().fg(theme.positive),
+            ),
+            Span::styled("  ·  ", Style::default().fg(theme.muted)),
+            Span::styled(result.views.as_str(), Style::default().fg(theme.secondary)),
+        ]),
+    ];
+    let details_area = Rect {
+        y: inner.y + image_rows + 1,
+        height: inner.height.saturating_sub(image_rows + 1),
+        ..inner
+    };
+    f.render_widget(
+        Paragraph::new(details).wrap(Wrap { trim: true }),
+        details_area,
+    );
+}
+
+/// The queue with the saved playlists beside or below it. The watch history
+/// takes the playlists' place while it has focus.
+fn render_side_panels(f: &mut Frame, app: &App, area: Rect, direction: Direction) {
+    let constraints = if direction == Direction::Vertical {
+        [Constraint::Percentage(60), Constraint::Percentage(40)]
+    } else {
+        [Constraint::Percentage(50), Constraint::Percentage(50)]
+    };
+    let chunks = Layout::default()
+        .direction(direction)
+        .constraints(constraints)
+        .split(area);
+
+    render_queue_panel(f, app, chunks[0]);
+    if app.focused_panel == FocusedPanel::History {
+        render_watch_history_panel(f, app, chunks[1]);
+    } else {
+        render_playlists_panel(f, app, chunks[1]);
     }
 }
 
 /// Height of the footer (status + controls, or just controls) for a given
 /// terminal height and whether a player is active. Shared with the runner's
 /// status-poll loop so the ffmpeg pane it sizes matches what gets rendered.
-pub fn footer_height(has_player: bool, area_height: u16) -> u16 {
+/// The footer holds the player status and, when shown, the subtitle line
+/// beneath it, above the controls line.
+pub fn footer_height(has_player: bool, subtitle_line: bool, area_height: u16) -> u16 {
     if has_player && area_height >= 14 {
-        4
+        4 + u16::from(subtitle_line)
     } else {
         2
     }
@@ -159,35 +355,39 @@ pub fn footer_height(has_player: bool, area_height: u16) -> u16 {
 /// Terminal cell dimensions available to the video pane for a given
 /// terminal size, mirroring the layout `render_ui` uses when `video_view`
 /// is active (full width, height minus the footer).
-pub fn video_pane_size(has_player: bool, area_width: u16, area_height: u16) -> (u16, u16) {
-    let rows = area_height.saturating_sub(footer_height(has_player, area_height));
+pub fn video_pane_size(
+    has_player: bool,
+    subtitle_line: bool,
+    area_width: u16,
+    area_height: u16,
+) -> (u16, u16) {
+    let rows = area_height.saturating_sub(footer_height(has_player, subtitle_line, area_height));
     (area_width, rows)
 }
 
 fn render_video_view(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
     match app.video.render_state() {
-        VideoDisplay::Error(message) => render_video_message(f, area, message, Color::Red),
-        VideoDisplay::Loading => render_video_message(f, area, "loading video…", Color::Yellow),
-        VideoDisplay::Placeholder => {
-            render_video_message(f, area, "no video playing", Color::DarkGray)
-        }
+        VideoDisplay::Error(message) => render_video_message(f, area, message, theme.error),
+        VideoDisplay::Loading => render_video_message(f, area, "loading video…", theme.highlight),
+        VideoDisplay::Placeholder => render_video_message(f, area, "no video playing", theme.muted),
         VideoDisplay::Frame(frame, paused) => {
             let lines = frame_to_lines(frame);
             f.render_widget(Paragraph::new(lines), area);
             if paused {
-                render_pause_overlay(f, area);
+                render_pause_overlay(f, theme, area);
             }
         }
         VideoDisplay::Pixels(protocol, paused) => {
             f.render_widget(ratatui_image::Image::new(protocol), area);
             if paused {
-                render_pause_overlay(f, area);
+                render_pause_overlay(f, theme, area);
             }
         }
         VideoDisplay::Shm(transport, paused) => {
             transport.render(area, f.buffer_mut());
             if paused {
-                render_pause_overlay(f, area);
+                render_pause_overlay(f, theme, area);
             }
         }
     }
@@ -208,7 +408,7 @@ fn render_video_message(f: &mut Frame, area: Rect, message: &str, color: Color)
     f.render_widget(paragraph, centered);
 }
 
-fn render_pause_overlay(f: &mut Frame, area: Rect) {
+fn render_pause_overlay(f: &mut Frame, theme: &Theme, area: Rect) {
     let text = " ⏸ PAUSED ";
     let width = (Line::from(text).width() as u16).min(area.width);
     let overlay = Rect::new(
@@ -220,8 +420,8 @@ fn render_pause_overlay(f: &mut Frame, area: Rect) {
     let paragraph = Paragraph::new(Span::styled(
         text,
         Style::default()
-            .fg(Color::White)
-            .bg(Color::Black)
+            .fg(theme.text)
+            .bg(theme.background)
             .add_modifier(Modifier::BOLD),
     ))
     .alignment(Alignment::Center);
@@ -295,19 +495,20 @@ fn pad_column(text: &str, width: usize) -> String {
 }
 
 fn render_results(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
     let is_focused = app.focused_panel == FocusedPanel::Results;
 
     let (border_style, title_style) = if is_focused {
         (
-            Style::default().fg(Color::Cyan),
+            Style::default().fg(theme.accent),
             Style::default()
-                .fg(Color::Cyan)
+                .fg(theme.accent)
                 .add_modifier(Modifier::BOLD),
         )
     } else {
         (
-            Style::default().fg(Color::DarkGray),
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(theme.muted),
+            Style::default().fg(theme.muted),
         )
     };
 
@@ -319,26 +520,26 @@ fn render_results(f: &mut Frame, app: &App, area: Rect) {
             vec![
                 ListItem::new(""),
                 ListItem::new(Line::from(vec![
-                    Span::styled("  Searching for ", Style::default().fg(Color::DarkGray)),
+                    Span::styled("  Searching for ", Style::default().fg(theme.muted)),
                     Span::styled(
                         app.query.as_str(),
                         Style::default()
-                            .fg(Color::Yellow)
+                            .fg(theme.highlight)
                             .add_modifier(Modifier::BOLD),
                     ),
                 ])),
                 ListItem::new(Line::from(Span::styled(
                     "  Results will appear as they arrive · Esc cancels",
-                    Style::default().fg(Color::Gray),
+                    Style::default().fg(theme.secondary),
                 ))),
             ]
         } else {
             vec![
                 ListItem::new(""),
                 ListItem::new(Line::from(vec![
-                    Span::styled("  No results", Style::default().fg(Color::DarkGray)),
-                    Span::styled("  ·  ", Style::default().fg(Color::DarkGray)),
-                    Span::styled("press / to search", Style::default().fg(Color::Yellow)),
+                    Span::styled("  No results", Style::default().fg(theme.muted)),
+                    Span::styled("  ·  ", Style::default().fg(theme.muted)),
+                    Span::styled("press / to search", Style::default().fg(theme.highlight)),
                 ])),
             ]
         }
@@ -361,41 +562,48 @@ fn render_results(f: &mut Frame, app: &App, area: Rect) {
             .enumerate()
             .map(|(i, result)| {
                 let num = start_idx.saturating_add(i).saturating_add(1);
-                let title_line = Line::from(vec![
-                    Span::styled(
-                        format!("{:>3}. ", num),
-                        Style::default().fg(Color::DarkGray),
-                    ),
+                let mut title_spans = vec![
+                    Span::styled(format!("{:>3}. ", num), Style::default().fg(theme.muted)),
                     Span::styled(
                         result.title.as_str(),
-                        Style::default()
-                            .fg(Color::White)
-                            .add_modifier(Modifier::BOLD),
+                        Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
                     ),
-                ]);
+                ];
+                match app.watch_history.mark(&result.id) {
+                    Some(WatchMark::Watched) => title_spans.push(Span::styled(
+                        "  ✓ watched",
+                        Style::default().fg(theme.muted),
+                    )),
+                    Some(WatchMark::Partial(progress)) => title_spans.push(Span::styled(
+                        format!("  ◐ {:.0}%", progress * 100.0),
+                        Style::default().fg(theme.muted),
+                    )),
+                    None => {}
+                }
+                let title_line = Line::from(title_spans);
 
                 let mut meta_spans = vec![
                     Span::raw("     "),
                     Span::styled(
                         pad_column(&result.duration, duration_width),
-                        Style::default().fg(Color::Green),
+                        Style::default().fg(theme.positive),
                     ),
-                    Span::styled("  ·  ", Style::default().fg(Color::DarkGray)),
+                    Span::styled("  ·  ", Style::default().fg(theme.muted)),
                     Span::styled(
                         pad_column(&result.channel, channel_width),
-                        Style::default().fg(Color::Cyan),
+                        Style::default().fg(theme.accent),
                     ),
-                    Span::styled("  ·  ", Style::default().fg(Color::DarkGray)),
+                    Span::styled("  ·  ", Style::default().fg(theme.muted)),
                     Span::styled(
                         pad_column(&result.views, views_width),
-                        Style::default().fg(Color::Gray),
+                        Style::default().fg(theme.secondary),
                     ),
                 ];
                 if !result.published.is_empty() {
-                    meta_spans.push(Span::styled("  ·  ", Style::default().fg(Color::DarkGray)));
+                    meta_spans.push(Span::styled("  ·  ", Style::default().fg(theme.muted)));
                     meta_spans.push(Span::styled(
                         result.published.as_str(),
-                        Style::default().fg(Color::Gray),
+                        Style::default().fg(theme.secondary),
                     ));
                 }
                 let meta_line = Line::from(meta_spans);
@@ -423,11 +631,18 @@ fn render_results(f: &mut Frame, app: &App, area: Rect) {
         )
     };
 
+    let filters = app.search_filters.summary();
+    let filter_info = if filters.is_empty() || app.showing_collection() {
+        String::new()
+    } else {
+        format!(" · {}", filters.join(" · "))
+    };
+
     let block = Block::default()
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
         .title(Span::styled(
-            format!(" Results ({}) ", page_info),
+            format!(" Results ({page_info}){filter_info} "),
             title_style,
         ))
         .border_style(border_style);
@@ -436,12 +651,7 @@ fn render_results(f: &mut Frame, app: &App, area: Rect) {
         .block(block)
         .highlight_symbol("▶ ")
         .scroll_padding(1)
-        .highlight_style(
-            Style::default()
-                .bg(Color::Blue)
-                .fg(Color::White)
-                .add_modifier(Modifier::BOLD),
-        );
+        .highlight_style(theme.selection().add_modifier(Modifier::BOLD));
 
     let mut state = ListState::default();
     if is_focused && !results.is_empty() {
@@ -451,6 +661,7 @@ fn render_results(f: &mut Frame, app: &App, area: Rect) {
     }
 
     f.render_stateful_widget(list, area, &mut state);
+    record_list(app, FocusedPanel::Results, area, &state, 2, results.len());
 
     // Scrollbar on right edge
     let visible_rows = area.height.saturating_sub(2) as usize / 2;
@@ -496,50 +707,72 @@ fn search_progress_label(app: &App) -> Option<String> {
                 target_page.saturating_add(1)
             )
         }
+        Some(SearchPhase::FetchingAll) => {
+            format!("Fetching all entries · {} so far", app.results.len())
+        }
+        Some(SearchPhase::LookingUpVideo) => "Looking up video".to_string(),
         None => "Searching".to_string(),
     })
 }
 
 fn render_queue_panel(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
     let is_focused = app.focused_panel == FocusedPanel::Queue;
 
     let (border_style, title_style) = if is_focused {
         (
-            Style::default().fg(Color::Cyan),
+            Style::default().fg(theme.accent),
             Style::default()
-                .fg(Color::Cyan)
+                .fg(theme.accent)
                 .add_modifier(Modifier::BOLD),
         )
     } else {
         (
-            Style::default().fg(Color::DarkGray),
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(theme.muted),
+            Style::default().fg(theme.muted),
         )
     };
 
-    let queue_title = if app.queue.is_empty() {
+    let mut queue_title = if app.queue.is_empty() {
         " Queue ".to_string()
     } else {
         format!(" Queue ({}) ", app.queue.len())
     };
+    if app.config.shuffle {
+        queue_title.push_str("· shuffle ");
+    }
+    if app.config.repeat != RepeatMode::Off {
+        queue_title.push_str(&format!("· repeat {} ", app.config.repeat.label()));
+    }
+    if app.config.radio {
+        queue_title.push_str("· radio ");
+    }
+    if app.queue_grab.is_some() {
+        queue_title.push_str("· moving ");
+    } else {
+        let marked = app.queue.marked_count();
+        if marked > 0 {
+            queue_title.push_str(&format!("· {marked} marked "));
+        }
+    }
 
     let items: Vec<ListItem> = if app.queue.is_empty() {
         vec![
             ListItem::new(""),
             ListItem::new(Line::from(Span::styled(
                 "  No tracks queued",
-                Style::default().fg(Color::DarkGray),
+                Style::default().fg(theme.muted),
             ))),
             ListItem::new(""),
             ListItem::new(Line::from(vec![
-                Span::styled("  Press ", Style::default().fg(Color::DarkGray)),
+                Span::styled("  Press ", Style::default().fg(theme.muted)),
                 Span::styled(
                     "[Enter]",
                     Style::default()
-                        .fg(Color::Yellow)
+                        .fg(theme.highlight)
                         .add_modifier(Modifier::BOLD),
                 ),
-                Span::styled(" on a result", Style::default().fg(Color::DarkGray)),
+                Span::styled(" on a result", Style::default().fg(theme.muted)),
             ])),
         ]
     } else {
@@ -549,23 +782,26 @@ fn render_queue_panel(f: &mut Frame, app: &App, area: Rect) {
             .map(|(i, track)| {
                 let is_playing = i == 0 && app.player_manager.is_some();
 
-                let (num_style, track_style, prefix) = if is_playing {
+                let (num_style, mut track_style, prefix) = if is_playing {
                     (
                         Style::default()
-                            .fg(Color::Green)
+                            .fg(theme.positive)
                             .add_modifier(Modifier::BOLD),
                         Style::default()
-                            .fg(Color::Green)
+                            .fg(theme.positive)
                             .add_modifier(Modifier::BOLD),
                         "▶ ",
                     )
                 } else {
                     (
-                        Style::default().fg(Color::DarkGray),
-                        Style::default().fg(Color::White),
-                        "  ",
+                        Style::default().fg(theme.muted),
+                        Style::default().fg(theme.text),
+                        if app.queue.is_marked(i) { "• " } else { "  " },
                     )
                 };
+                if app.queue.is_marked(i) {
+                    track_style = track_style.fg(theme.marked);
+                }
 
                 let line = Line::from(vec![
                     Span::styled(format!("{:>2}. ", i + 1), num_style),
@@ -588,12 +824,7 @@ fn render_queue_panel(f: &mut Frame, app: &App, area: Rect) {
         .block(block)
         .highlight_symbol("› ")
         .scroll_padding(1)
-        .highlight_style(
-            Style::default()
-                .bg(Color::Blue)
-                .fg(Color::White)
-                .add_modifier(Modifier::BOLD),
-        );
+        .highlight_style(theme.selection().add_modifier(Modifier::BOLD));
 
     let mut state = ListState::default();
     if is_focused && !app.queue.is_empty() {
@@ -604,6 +835,7 @@ fn render_queue_panel(f: &mut Frame, app: &App, area: Rect) {
     }
 
     f.render_stateful_widget(list, area, &mut state);
+    record_list(app, FocusedPanel::Queue, area, &state, 1, app.queue.len());
 
     let visible_rows = area.height.saturating_sub(2) as usize;
     if app.queue.len() > visible_rows.max(1) {
@@ -619,8 +851,215 @@ fn render_queue_panel(f: &mut Frame, app: &App, area: Rect) {
     }
 }
 
+fn render_playlists_panel(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let is_focused = app.focused_panel == FocusedPanel::Playlists;
+
+    let (border_style, title_style) = if is_focused {
+        (
+            Style::default().fg(theme.accent),
+            Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        )
+    } else {
+        (
+            Style::default().fg(theme.muted),
+            Style::default().fg(theme.muted),
+        )
+    };
+
+    let title = if app.playlists.is_empty() {
+        " Playlists ".to_string()
+    } else {
+        format!(" Playlists ({}) ", app.playlists.len())
+    };
+
+    let items: Vec<ListItem> = if app.playlists.is_empty() {
+        vec![
+            ListItem::new(Line::from(Span::styled(
+                "  No saved playlists",
+                Style::default().fg(theme.muted),
+            ))),
+            ListItem::new(Line::from(vec![
+                Span::styled("  Press ", Style::default().fg(theme.muted)),
+                Span::styled(
+                    "[N]",
+                    Style::default()
+                        .fg(theme.highlight)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(" to save the queue", Style::default().fg(theme.muted)),
+            ])),
+        ]
+    } else {
+        app.playlists
+            .iter()
+            .map(|playlist| {
+                ListItem::new(Line::from(vec![
+                    Span::styled(playlist.name.as_str(), Style::default().fg(theme.text)),
+                    Span::styled(
+                        format!("  {}", playlist.tracks.len()),
+                        Style::default().fg(theme.muted),
+                    ),
+                ]))
+            })
+            .collect()
+    };
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .title(Span::styled(title, title_style))
+        .border_style(border_style);
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_symbol("› ")
+        .scroll_padding(1)
+        .highlight_style(theme.selection().add_modifier(Modifier::BOLD));
+
+    let mut state = ListState::default();
+    if is_focused && !app.playlists.is_empty() {
+        state.select(Some(
+            app.playlist_selected_index
+                .min(app.playlists.len().saturating_sub(1)),
+        ));
+    }
+
+    f.render_stateful_widget(list, area, &mut state);
+    record_list(
+        app,
+        FocusedPanel::Playlists,
+        area,
+        &state,
+        1,
+        app.playlists.len(),
+    );
+}
+
+fn render_watch_history_panel(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let title_style = Style::default()
+        .fg(theme.accent)
+        .add_modifier(Modifier::BOLD);
+    let title = if app.watch_history.is_empty() {
+        " History ".to_string()
+    } else {
+        format!(" History ({}) ", app.watch_history.len())
+    };
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    let items: Vec<ListItem> = if app.watch_history.is_empty() {
+        vec![ListItem::new(Line::from(Span::styled(
+            "  Nothing played yet",
+            Style::default().fg(theme.muted),
+        )))]
+    } else {
+        app.watch_history
+            .iter()
+            .map(|entry| {
+                let progress = if entry.finished {
+                    "✓".to_string()
+                } else {
+                    format!("{:.0}%", entry.progress() * 100.0)
+                };
+                ListItem::new(vec![
+                    Line::from(Span::styled(
+                        entry.track.title.as_str(),
+                        Style::default().fg(theme.text),
+                    )),
+                    Line::from(vec![
+                        Span::styled(
+                            format!("  {progress:>4}  "),
+                            Style::default().fg(theme.positive),
+                        ),
+                        Span::styled(
+                            played_ago(now.saturating_sub(entry.started_at)),
+                            Style::default().fg(theme.muted),
+                        ),
+                    ]),
+                ])
+            })
+            .collect()
+    };
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .title(Span::styled(title, title_style))
+        .border_style(Style::default().fg(theme.accent));
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_symbol("› ")
+        .scroll_padding(1)
+        .highlight_style(theme.selection().add_modifier(Modifier::BOLD));
+
+    let mut state = ListState::default();
+    if !app.watch_history.is_empty() {
+        state.select(Some(
+            app.watch_history_selected_index
+                .min(app.watch_history.len().saturating_sub(1)),
+        ));
+    }
+
+    f.render_stateful_widget(list, area, &mut state);
+    record_list(
+        app,
+        FocusedPanel::History,
+        area,
+        &state,
+        2,
+        app.watch_history.len(),
+    );
+}
+
+/// Note where a list was drawn and how far it scrolled, for mouse clicks.
+fn record_list(
+    app: &App,
+    panel: FocusedPanel,
+    area: Rect,
+    state: &ListState,
+    item_height: u16,
+    len: usize,
+) {
+    app.screen_areas.borrow_mut().lists.push(ListArea {
+        panel,
+        area,
+        offset: state.offset(),
+        item_height,
+        len,
+    });
+}
+
+/// When a history entry was played; finer than search results' upload ages
+/// since these are exact.
+fn played_ago(age_seconds: u64) -> String {
+    match age_seconds {
+        0..60 => "just now".to_string(),
+        60..3600 => format!("{} min ago", age_seconds / 60),
+        _ => crate::search::format_relative_age(age_seconds as i64),
+    }
+}
+
 fn render_footer(f: &mut Frame, app: &App, area: Rect) {
-    if app.player_manager.is_some() && area.height >= 4 {
+    if app.player_manager.is_some() && area.height >= 5 && app.config.subtitles {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(2),
+                Constraint::Length(1),
+                Constraint::Length(2),
+            ])
+            .split(area);
+
+        render_status_line(f, app, chunks[0]);
+        render_subtitle_line(f, app, chunks[1]);
+        render_controls_line(f, app, chunks[2]);
+    } else if app.player_manager.is_some() && area.height >= 4 {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([Constraint::Length(2), Constraint::Length(2)])
@@ -634,6 +1073,7 @@ fn render_footer(f: &mut Frame, app: &App, area: Rect) {
 }
 
 fn render_status_line(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
     if let Some(ref player) = app.player_manager {
         let status = &player.status;
 
@@ -644,24 +1084,52 @@ fn render_status_line(f: &mut Frame, app: &App, area: Rect) {
 
         // Line 1: play/pause icon + title
         let play_icon = if status.paused { "⏸" } else { "▶" };
-        let title_line = Line::from(vec![
+        let mut title_line = Line::from(vec![
             Span::styled(
                 format!(" {} ", play_icon),
                 Style::default()
-                    .fg(Color::Green)
-                    .bg(Color::Black)
+                    .fg(theme.positive)
+                    .bg(theme.background)
                     .add_modifier(Modifier::BOLD),
             ),
             Span::styled(
                 status.title.clone(),
                 Style::default()
-                    .fg(Color::White)
-                    .bg(Color::Black)
+                    .fg(theme.text)
+                    .bg(theme.background)
                     .add_modifier(Modifier::BOLD),
             ),
         ]);
+        if let Some(index) = status.chapter
+            && let Some(chapter) = status.chapters.get(index)
+        {
+            title_line.push_span(Span::styled(
+                format!("  ›  {}", chapter.title),
+                Style::default().fg(theme.secondary).bg(theme.background),
+            ));
+            title_line.push_span(Span::styled(
+                format!(" ({}/{})", index + 1, status.chapters.len()),
+                Style::default().fg(theme.muted).bg(theme.background),
+            ));
+        }
+        if let Some(offer) = &app.resume_offer {
+            title_line.push_span(Span::styled(
+                format!(
+                    "   Resume from {}? ",
+                    format_duration(offer.position as u64)
+                ),
+                Style::default().fg(theme.secondary).bg(theme.background),
+            ));
+            title_line.push_span(Span::styled(
+                "[R]",
+                Style::default()
+                    .fg(theme.highlight)
+                    .bg(theme.background)
+                    .add_modifier(Modifier::BOLD),
+            ));
+        }
         f.render_widget(
-            Paragraph::new(title_line).style(Style::default().bg(Color::Black)),
+            Paragraph::new(title_line).style(Style::default().bg(theme.background)),
             chunks[0],
         );
 
@@ -669,19 +1137,22 @@ fn render_status_line(f: &mut Frame, app: &App, area: Rect) {
             let prompt = Line::from(vec![
                 Span::styled(
                     " Jump to: ",
-                    Style::default().fg(Color::Gray).bg(Color::Black),
+                    Style::default().fg(theme.secondary).bg(theme.background),
                 ),
                 Span::styled(
                     input,
                     Style::default()
-                        .fg(Color::Yellow)
-                        .bg(Color::Black)
+                        .fg(theme.highlight)
+                        .bg(theme.background)
                         .add_modifier(Modifier::BOLD),
                 ),
-                Span::styled("▌", Style::default().fg(Color::Yellow).bg(Color::Black)),
+                Span::styled(
+                    "▌",
+                    Style::default().fg(theme.highlight).bg(theme.background),
+                ),
             ]);
             f.render_widget(
-                Paragraph::new(prompt).style(Style::default().bg(Color::Black)),
+                Paragraph::new(prompt).style(Style::default().bg(theme.background)),
                 chunks[1],
             );
             return;
@@ -711,177 +1182,385 @@ fn render_status_line(f: &mut Frame, app: &App, area: Rect) {
             0
         };
 
-        let filled = "━".repeat(thumb_pos);
-        let empty = "─".repeat(bar_width.saturating_sub(thumb_pos + 1));
+        if bar_width > 0 {
+            app.screen_areas.borrow_mut().progress_bar =
+                Some(Rect::new(chunks[1].x + 1, chunks[1].y, bar_width as u16, 1));
+        }
 
-        let mut spans: Vec<Span> = vec![
-            Span::styled(" ", Style::default().bg(Color::Black)),
-            Span::styled(
-                filled,
-                Style::default()
-                    .fg(Color::Cyan)
-                    .bg(Color::Black)
-                    .add_modifier(Modifier::BOLD),
-            ),
-        ];
+        // Chapter starts are ticks on the bar and SponsorBlock segments are
+        // runs of their own, all on the same scale as the thumb.
+        let column = |seconds: f64| {
+            let frac = (seconds / status.duration).clamp(0.0, 1.0);
+            (frac * bar_width.saturating_sub(1) as f64) as usize
+        };
+        let known_duration = status.duration > 0.0;
+        let ticks: Vec<usize> = status
+            .chapters
+            .iter()
+            .filter(|chapter| chapter.start > 0.0 && known_duration)
+            .map(|chapter| column(chapter.start))
+            .collect();
+        let segments: Vec<(usize, usize)> = status
+            .segments
+            .iter()
+            .filter(|_| known_duration)
+            .map(|segment| (column(segment.start), column(segment.end)))
+            .collect();
+        let in_segment = |at: usize| segments.iter().any(|&(from, to)| (from..=to).contains(&at));
+        let segment_style = Style::default().fg(theme.positive).bg(theme.background);
+        let cells = |columns: std::ops::Range<usize>, line: &str, tick: &str, style: Style| {
+            let columns: Vec<usize> = columns.collect();
+            columns
+                .chunk_by(|&a, &b| in_segment(a) == in_segment(b))
+                .map(|run| {
+                    let skipped = in_segment(run[0]);
+                    let text: String = run
+                        .iter()
+                        .map(|at| match (ticks.contains(at), skipped) {
+                            (true, _) => tick,
+                            (false, true) => "═",
+                            (false, false) => line,
+                        })
+                        .collect();
+                    Span::styled(text, if skipped { segment_style } else { style })
+                })
+                .collect::<Vec<_>>()
+        };
+        let filled = cells(
+            0..thumb_pos,
+            "━",
+            "╋",
+            Style::default()
+                .fg(theme.progress)
+                .bg(theme.background)
+                .add_modifier(Modifier::BOLD),
+        );
+        let empty = cells(
+            thumb_pos + 1..bar_width,
+            "─",
+            "┼",
+            Style::default().fg(theme.muted).bg(theme.background),
+        );
+
+        let mut spans: Vec<Span> = vec![Span::styled(" ", Style::default().bg(theme.background))];
+        spans.extend(filled);
 
         if bar_width > 0 {
             spans.push(Span::styled(
                 "●",
                 Style::default()
-                    .fg(Color::Cyan)
-                    .bg(Color::Black)
+                    .fg(theme.progress)
+                    .bg(theme.background)
                     .add_modifier(Modifier::BOLD),
             ));
         }
 
+        spans.extend(empty);
         spans.extend([
-            Span::styled(empty, Style::default().fg(Color::DarkGray).bg(Color::Black)),
             Span::styled(
                 format!(" {elapsed} "),
                 Style::default()
-                    .fg(Color::Cyan)
-                    .bg(Color::Black)
+                    .fg(theme.accent)
+                    .bg(theme.background)
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::styled("/", Style::default().fg(Color::DarkGray).bg(Color::Black)),
+            Span::styled("/", Style::default().fg(theme.muted).bg(theme.background)),
             Span::styled(
                 format!(" {duration} "),
-                Style::default().fg(Color::Gray).bg(Color::Black),
+                Style::default().fg(theme.secondary).bg(theme.background),
             ),
-            Span::styled("│", Style::default().fg(Color::DarkGray).bg(Color::Black)),
+            Span::styled("│", Style::default().fg(theme.muted).bg(theme.background)),
             Span::styled(
                 format!(" 🔊 {}% ", status.volume),
                 Style::default()
-                    .fg(Color::Cyan)
-                    .bg(Color::Black)
+                    .fg(theme.accent)
+                    .bg(theme.background)
                     .add_modifier(Modifier::BOLD),
             ),
         ]);
 
         f.render_widget(
-            Paragraph::new(Line::from(spans)).style(Style::default().bg(Color::Black)),
+            Paragraph::new(Line::from(spans)).style(Style::default().bg(theme.background)),
             chunks[1],
         );
     }
 }
 
+/// The subtitle being spoken, centred beneath the player status.
+fn render_subtitle_line(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let position = app
+        .player_manager
+        .as_ref()
+        .map_or(0.0, |player| player.status.time_pos);
+    let muted = Style::default().fg(theme.muted);
+    let span = match app.subtitles.state() {
+        Some(SubtitleState::Loaded(transcript)) if transcript.cues.is_empty() => Span::styled(
+            format!(
+                "No {} subtitles · {} picks another language",
+                app.config.subtitle_language,
+                app.keymap
+                    .short_label(Context::Global, &[Action::SubtitleLanguage])
+            ),
+            muted,
+        ),
+        Some(SubtitleState::Loaded(transcript)) => match transcript.cue_at(position) {
+            Some(index) => Span::styled(
+                transcript.cues[index].text.clone(),
+                Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+            ),
+            None => Span::raw(""),
+        },
+        Some(SubtitleState::Failed(_)) => Span::styled("Subtitles unavailable", muted),
+        Some(SubtitleState::Loading) | None => Span::styled("Loading subtitles…", muted),
+    };
+    f.render_widget(
+        Paragraph::new(Line::from(span))
+            .alignment(Alignment::Center)
+            .style(Style::default().bg(theme.background)),
+        area,
+    );
+}
+
 fn render_controls_line(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let keys = |context, actions: &[Action]| app.keymap.short_label(context, actions);
+    let fixed = |key: &str| key.to_string();
     let line = if let Some(message) = app.status_message.as_deref() {
         Line::from(vec![
             Span::styled(
                 "[!] ",
-                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                Style::default()
+                    .fg(theme.error)
+                    .add_modifier(Modifier::BOLD),
             ),
-            Span::styled(message, Style::default().fg(Color::Yellow)),
+            Span::styled(message, Style::default().fg(theme.highlight)),
         ])
     } else if app.video_view {
         controls_line(
+            theme,
             &[
-                ("v", "Back"),
-                ("Space", "Pause"),
-                ("< >", "Seek"),
-                ("n", "Next"),
-                ("q", "Quit"),
+                (keys(Context::Global, &[Action::ToggleVideo]), "Back"),
+                (keys(Context::Global, &[Action::Pause]), "Pause"),
+                (
+                    keys(Context::Global, &[Action::Rewind, Action::FastForward]),
+                    "Seek",
+                ),
+                (
+                    keys(Context::Video, &[Action::NextTrack, Action::PreviousTrack]),
+                    "Next/Prev",
+                ),
+                (
+                    keys(
+                        Context::Global,
+                        &[Action::PreviousChapter, Action::NextChapter],
+                    ),
+                    "Chapter",
+                ),
+                (keys(Context::Global, &[Action::Subtitles]), "Subtitles"),
+                (keys(Context::Video, &[Action::Details]), "Details"),
+                (keys(Context::Global, &[Action::Quit]), "Quit"),
             ],
             area.width as usize,
         )
     } else {
         match app.focused_panel {
-            FocusedPanel::SearchBar => controls_line(
-                &[
-                    ("Enter", "Search"),
-                    ("Esc", "Cancel"),
-                    ("Tab", "Panel"),
-                    ("F2", "Settings"),
-                ],
-                area.width as usize,
-            ),
+            FocusedPanel::SearchBar => {
+                // Only Settings keys that cannot be typed reach it from here.
+                let settings = app
+                    .keymap
+                    .chords(Context::Global, Action::Settings)
+                    .iter()
+                    .find(|chord| !chord.is_text())
+                    .map(ToString::to_string)
+                    .unwrap_or_default();
+                controls_line(
+                    theme,
+                    &[
+                        (keys(Context::Search, &[Action::Submit]), "Search"),
+                        (fixed("Esc"), "Cancel"),
+                        (
+                            keys(Context::Search, &[Action::OlderSearch, Action::NewerSearch]),
+                            "History",
+                        ),
+                        (
+                            keys(Context::Search, &[Action::FindSearch]),
+                            "Find past search",
+                        ),
+                        (
+                            keys(Context::Search, &[Action::AcceptSuggestion]),
+                            "Accept suggestion",
+                        ),
+                        (fixed("Tab"), "Panel"),
+                        (settings, "Settings"),
+                    ],
+                    area.width as usize,
+                )
+            }
             FocusedPanel::Results => {
                 if app.loading {
-                    let mut controls = vec![("Esc", "Cancel")];
+                    let mut controls = vec![(fixed("Esc"), "Cancel")];
                     if !app.current_page_results().is_empty() {
-                        controls.extend([("Enter", "Queue"), ("↑↓/jk", "Move"), ("n/p", "Page")]);
+                        controls.extend([
+                            (keys(Context::Results, &[Action::Play]), "Queue"),
+                            (keys(Context::Results, &[Action::Up, Action::Down]), "Move"),
+                            (
+                                keys(Context::Results, &[Action::NextPage, Action::PreviousPage]),
+                                "Page",
+                            ),
+                        ]);
                     }
-                    controls.extend([("/", "New search"), ("?", "Help"), ("Tab", "Panel")]);
-                    controls_line(&controls, area.width as usize)
+                    controls.extend([
+                        (keys(Context::Results, &[Action::Search]), "New search"),
+                        (keys(Context::Results, &[Action::Help]), "Help"),
+                        (fixed("Tab"), "Panel"),
+                    ]);
+                    controls_line(theme, &controls, area.width as usize)
                 } else if !app.number_input.is_empty() {
-                    Line::from(vec![
-                        Span::styled("Go to: ", Style::default().fg(Color::Gray)),
+                    let mut spans = vec![
+                        Span::styled("Go to: ", Style::default().fg(theme.secondary)),
                         Span::styled(
                             app.number_input.clone(),
                             Style::default()
-                                .fg(Color::Yellow)
+                                .fg(theme.highlight)
                                 .add_modifier(Modifier::BOLD),
                         ),
-                        Span::styled("_", Style::default().fg(Color::Yellow)),
+                        Span::styled("_", Style::default().fg(theme.highlight)),
                         Span::raw("   "),
-                        Span::styled(
-                            "[Enter]",
-                            Style::default()
-                                .fg(Color::Yellow)
-                                .add_modifier(Modifier::BOLD),
-                        ),
-                        Span::styled(" Confirm   ", Style::default().fg(Color::Gray)),
-                        Span::styled(
-                            "[Bksp]",
-                            Style::default()
-                                .fg(Color::Yellow)
-                                .add_modifier(Modifier::BOLD),
-                        ),
-                        Span::styled(" Clear", Style::default().fg(Color::Gray)),
-                    ])
+                    ];
+                    spans.extend(
+                        controls_line(
+                            theme,
+                            &[
+                                (keys(Context::Results, &[Action::Play]), "Confirm"),
+                                (keys(Context::Results, &[Action::Add]), "Add to queue"),
+                                (fixed("Bksp"), "Clear"),
+                            ],
+                            usize::MAX,
+                        )
+                        .spans,
+                    );
+                    Line::from(spans)
                 } else {
+                    let queue_all = if app.showing_collection() {
+                        (keys(Context::Results, &[Action::QueueAll]), "Queue all")
+                    } else {
+                        (keys(Context::Global, &[Action::Quit]), "Quit")
+                    };
                     controls_line(
+                        theme,
                         &[
-                            ("q", "Quit"),
-                            ("Enter", "Queue"),
-                            ("↑↓/jk", "Move"),
-                            ("n/p", "Page"),
-                            ("/", "Search"),
-                            ("?", "Help"),
-                            ("Space", "Pause"),
-                            ("< >", "Seek"),
-                            ("Tab", "Panel"),
+                            queue_all,
+                            (keys(Context::Results, &[Action::Play]), "Queue"),
+                            (keys(Context::Results, &[Action::Add]), "Add"),
+                            (keys(Context::Results, &[Action::Up, Action::Down]), "Move"),
+                            (
+                                keys(Context::Results, &[Action::NextPage, Action::PreviousPage]),
+                                "Page",
+                            ),
+                            (keys(Context::Results, &[Action::Search]), "Search"),
+                            (keys(Context::Results, &[Action::Filters]), "Filters"),
+                            (keys(Context::Results, &[Action::Details]), "Details"),
+                            (keys(Context::Results, &[Action::Help]), "Help"),
+                            (keys(Context::Global, &[Action::Pause]), "Pause"),
+                            (
+                                keys(Context::Global, &[Action::Rewind, Action::FastForward]),
+                                "Seek",
+                            ),
+                            (fixed("Tab"), "Panel"),
                         ],
                         area.width as usize,
                     )
                 }
             }
+            FocusedPanel::Queue if app.queue_grab.is_some() => controls_line(
+                theme,
+                &[
+                    (keys(Context::Queue, &[Action::Up, Action::Down]), "Carry"),
+                    (keys(Context::Queue, &[Action::Play]), "Drop"),
+                ],
+                area.width as usize,
+            ),
             FocusedPanel::Queue => {
                 let mut controls = if app.loading {
-                    vec![("Esc", "Cancel search")]
+                    vec![(fixed("Esc"), "Cancel search")]
                 } else {
-                    vec![("q", "Quit")]
+                    vec![(keys(Context::Global, &[Action::Quit]), "Quit")]
                 };
                 controls.extend([
-                    ("Enter", "Play"),
-                    ("↑↓/jk", "Move"),
-                    ("Del", "Remove"),
-                    ("n", "Next"),
-                    ("c", "Clear"),
-                    ("?", "Help"),
-                    ("Tab", "Panel"),
+                    (keys(Context::Queue, &[Action::Play]), "Play"),
+                    (keys(Context::Queue, &[Action::Up, Action::Down]), "Move"),
+                    (keys(Context::Queue, &[Action::Remove]), "Remove"),
+                    (
+                        keys(Context::Queue, &[Action::MoveDown, Action::MoveUp]),
+                        "Reorder",
+                    ),
+                    (keys(Context::Queue, &[Action::Mark]), "Mark"),
+                    (
+                        keys(Context::Queue, &[Action::NextTrack, Action::PreviousTrack]),
+                        "Next/Prev",
+                    ),
+                    (keys(Context::Queue, &[Action::Clear]), "Clear"),
+                    (keys(Context::Queue, &[Action::Details]), "Details"),
+                    (keys(Context::Queue, &[Action::Help]), "Help"),
+                    (fixed("Tab"), "Panel"),
                 ]);
-                controls_line(&controls, area.width as usize)
+                controls_line(theme, &controls, area.width as usize)
             }
-        }
-    };
-
-    let footer = Paragraph::new(line).block(
-        Block::default()
-            .borders(Borders::TOP)
-            .border_style(Style::default().fg(Color::DarkGray)),
-    );
-
-    f.render_widget(footer, area);
+            FocusedPanel::History => controls_line(
+                theme,
+                &[
+                    (keys(Context::History, &[Action::Play]), "Play now"),
+                    (keys(Context::History, &[Action::Add]), "Add to queue"),
+                    (keys(Context::History, &[Action::Up, Action::Down]), "Move"),
+                    (keys(Context::History, &[Action::Remove]), "Forget"),
+                    (fixed("Tab"), "Panel"),
+                ],
+                area.width as usize,
+            ),
+            FocusedPanel::Playlists => controls_line(
+                theme,
+                &[
+                    (keys(Context::Playlists, &[Action::QueueAll]), "Queue all"),
+                    (
+                        keys(Context::Playlists, &[Action::NewPlaylist]),
+                        "New from queue",
+                    ),
+                    (keys(Context::Playlists, &[Action::AddQueue]), "Add queue"),
+                    (keys(Context::Playlists, &[Action::Rename]), "Rename"),
+                    (keys(Context::Playlists, &[Action::Remove]), "Delete"),
+                    (
+                        keys(Context::Playlists, &[Action::Import, Action::Export]),
+                        "Import/Export",
+                    ),
+                    (fixed("Tab"), "Panel"),
+                ],
+                area.width as usize,
+            ),
+        }
+    };
+
+    let footer = Paragraph::new(line).block(
+        Block::default()
+            .borders(Borders::TOP)
+            .border_style(Style::default().fg(theme.muted)),
+    );
+
+    f.render_widget(footer, area);
 }
 
 /// Build a styled controls hint line: [Key] Action  [Key] Action ...
-fn controls_line(controls: &[(&str, &str)], available_width: usize) -> Line<'static> {
+/// Controls whose action has no key bound are left out.
+fn controls_line<K: AsRef<str>>(
+    theme: &Theme,
+    controls: &[(K, &str)],
+    available_width: usize,
+) -> Line<'static> {
     let mut spans: Vec<Span<'static>> = Vec::new();
     let mut used_width = 0;
-    for (i, (key, desc)) in controls.iter().enumerate() {
+    let bound = controls.iter().filter(|(key, _)| !key.as_ref().is_empty());
+    for (i, (key, desc)) in bound.enumerate() {
+        let key = key.as_ref();
         let separator_width = usize::from(i > 0) * 3;
         let control_width = Line::from(format!("[{key}] {desc}")).width();
         if i > 0 && used_width + separator_width + control_width > available_width {
@@ -893,12 +1572,12 @@ fn controls_line(controls: &[(&str, &str)], available_width: usize) -> Line<'sta
         spans.push(Span::styled(
             format!("[{}]", key),
             Style::default()
-                .fg(Color::Yellow)
+                .fg(theme.highlight)
                 .add_modifier(Modifier::BOLD),
         ));
         spans.push(Span::styled(
             format!(" {}", desc),
-            Style::default().fg(Color::Gray),
+            Style::default().fg(theme.secondary),
         ));
         used_width += separator_width + control_width;
     }
@@ -906,86 +1585,367 @@ fn controls_line(controls: &[(&str, &str)], available_width: usize) -> Line<'sta
 }
 
 fn render_help_overlay(f: &mut Frame, app: &App) {
-    let show_full_help = f.area().width >= 72 && f.area().height >= 38;
+    let theme = &app.theme;
+    let show_full_help = f.area().width >= 72 && f.area().height >= 61;
     let mut help_text = if show_full_help {
         vec![
             Line::from(Span::styled(
                 "  Focus Navigation",
                 Style::default()
-                    .fg(Color::Yellow)
+                    .fg(theme.highlight)
                     .add_modifier(Modifier::BOLD),
             )),
-            help_row("    Tab         ", "Cycle focus  Search › Results › Queue"),
-            help_row("    Shift+Tab   ", "Reverse cycle"),
+            help_row(
+                theme,
+                "    Tab         ",
+                "Search › Results › Queue › Playlists › History",
+            ),
+            help_row(theme, "    Shift+Tab   ", "Reverse cycle"),
             Line::from(""),
             Line::from(Span::styled(
                 "  Search Bar",
                 Style::default()
-                    .fg(Color::Yellow)
+                    .fg(theme.highlight)
                     .add_modifier(Modifier::BOLD),
             )),
-            help_row("    Enter       ", "Submit search"),
-            help_row("    Esc         ", "Clear and return to Results"),
+            bound_row(
+                app,
+                "    ",
+                Context::Search,
+                &[Action::Submit],
+                "Submit search",
+            ),
+            bound_row(
+                app,
+                "    ",
+                Context::Search,
+                &[Action::Cancel],
+                "Clear and return to Results",
+            ),
+            bound_row(
+                app,
+                "    ",
+                Context::Search,
+                &[Action::OlderSearch, Action::NewerSearch],
+                "Recall earlier / later searches",
+            ),
+            bound_row(
+                app,
+                "    ",
+                Context::Search,
+                &[Action::FindSearch, Action::AcceptSuggestion],
+                "Fuzzy-find a past search / accept suggestion",
+            ),
             Line::from(""),
             Line::from(Span::styled(
                 "  Results",
                 Style::default()
-                    .fg(Color::Yellow)
+                    .fg(theme.highlight)
                     .add_modifier(Modifier::BOLD),
             )),
-            help_row("    ↑↓ / j k    ", "Move selection"),
-            help_row("    Enter       ", "Add to queue and play"),
+            bound_row(
+                app,
+                "    ",
+                Context::Results,
+                &[Action::Up, Action::Down],
+                "Move selection up / down",
+            ),
+            bound_row(
+                app,
+                "    ",
+                Context::Results,
+                &[Action::Play],
+                "Add to queue and play",
+            ),
             help_row(
+                theme,
                 "    Digits      ",
-                "Pick displayed or page-local #, then Enter",
+                "Pick displayed or page-local # or 3-7, then Enter",
+            ),
+            bound_row(
+                app,
+                "    ",
+                Context::Results,
+                &[Action::Add, Action::AddPage],
+                "Add to queue without playing / add page",
+            ),
+            bound_row(
+                app,
+                "    ",
+                Context::Results,
+                &[Action::NextPage, Action::PreviousPage],
+                "Next / Previous page",
+            ),
+            bound_row(
+                app,
+                "    ",
+                Context::Results,
+                &[Action::PlayNext],
+                "Play next, right after the current track",
+            ),
+            bound_row(
+                app,
+                "    ",
+                Context::Results,
+                &[Action::QueueAll],
+                "Queue a whole playlist or channel",
+            ),
+            bound_row(
+                app,
+                "    ",
+                Context::Results,
+                &[Action::Filters],
+                "Search filters: sort, length, date, live",
+            ),
+            bound_row(
+                app,
+                "    ",
+                Context::Results,
+                &[Action::Search],
+                "Focus search bar",
+            ),
+            bound_row(
+                app,
+                "    ",
+                Context::Results,
+                &[Action::Details],
+                "Details: description, likes, tags, chapters",
             ),
-            help_row("    n / p       ", "Next / Previous page"),
-            help_row("    / or s      ", "Focus search bar"),
             Line::from(""),
             Line::from(Span::styled(
                 "  Queue",
                 Style::default()
-                    .fg(Color::Yellow)
+                    .fg(theme.highlight)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            bound_row(
+                app,
+                "    ",
+                Context::Queue,
+                &[Action::Up, Action::Down],
+                "Navigate queue",
+            ),
+            bound_row(
+                app,
+                "    ",
+                Context::Queue,
+                &[Action::Play],
+                "Jump to track",
+            ),
+            bound_row(
+                app,
+                "    ",
+                Context::Queue,
+                &[Action::Remove],
+                "Remove track (or all marked tracks)",
+            ),
+            bound_row(
+                app,
+                "    ",
+                Context::Queue,
+                &[Action::MoveDown, Action::MoveUp],
+                "Move track (or marked tracks) down / up",
+            ),
+            bound_row(
+                app,
+                "    ",
+                Context::Queue,
+                &[Action::Grab],
+                format!(
+                    "Grab: {} carry, {} drops",
+                    app.keymap
+                        .short_label(Context::Queue, &[Action::Up, Action::Down]),
+                    app.keymap.short_label(Context::Queue, &[Action::Play]),
+                ),
+            ),
+            bound_row(
+                app,
+                "    ",
+                Context::Queue,
+                &[Action::Mark, Action::ClearMarks],
+                "Mark track / clear marks",
+            ),
+            bound_row(
+                app,
+                "    ",
+                Context::Queue,
+                &[Action::NextTrack, Action::PreviousTrack],
+                "Next / previous track (or restart)",
+            ),
+            bound_row(app, "    ", Context::Queue, &[Action::Clear], "Clear queue"),
+            Line::from(""),
+            Line::from(Span::styled(
+                "  Playlists",
+                Style::default()
+                    .fg(theme.highlight)
                     .add_modifier(Modifier::BOLD),
             )),
-            help_row("    ↑↓ / j k    ", "Navigate queue"),
-            help_row("    Enter       ", "Jump to track"),
-            help_row("    Del / Bksp  ", "Remove track"),
-            help_row("    n / c       ", "Next track / Clear queue"),
+            bound_row(
+                app,
+                "    ",
+                Context::Playlists,
+                &[Action::QueueAll],
+                "Add playlist to queue",
+            ),
+            bound_row(
+                app,
+                "    ",
+                Context::Playlists,
+                &[Action::NewPlaylist, Action::AddQueue],
+                "Save queue as new / add queue to playlist",
+            ),
+            bound_row(
+                app,
+                "    ",
+                Context::Playlists,
+                &[Action::Rename, Action::Remove],
+                "Rename / Delete",
+            ),
+            bound_row(
+                app,
+                "    ",
+                Context::Playlists,
+                &[Action::Import, Action::Export],
+                "Import / Export M3U, M3U8 or JSON",
+            ),
+            Line::from(""),
+            Line::from(Span::styled(
+                "  History",
+                Style::default()
+                    .fg(theme.highlight)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            bound_row(
+                app,
+                "    ",
+                Context::History,
+                &[Action::Play, Action::Add],
+                "Play again now / add to queue",
+            ),
+            bound_row(
+                app,
+                "    ",
+                Context::History,
+                &[Action::Remove],
+                "Forget the entry",
+            ),
             Line::from(""),
             Line::from(Span::styled(
                 "  Playback (global)",
                 Style::default()
-                    .fg(Color::Yellow)
+                    .fg(theme.highlight)
                     .add_modifier(Modifier::BOLD),
             )),
-            help_row("    Space       ", "Play / Pause"),
-            help_row("    < / >       ", "Seek -/+ 10 seconds"),
-            help_row(
-                "    ← / →       ",
+            bound_row(
+                app,
+                "    ",
+                Context::Global,
+                &[Action::Pause],
+                "Play / Pause",
+            ),
+            bound_row(
+                app,
+                "    ",
+                Context::Global,
+                &[Action::Rewind, Action::FastForward],
+                "Seek -/+ 10 seconds",
+            ),
+            bound_row(
+                app,
+                "    ",
+                Context::Global,
+                &[Action::SeekBack, Action::SeekForward],
                 format!("Seek ±{}s", app.config.seek_step),
             ),
-            help_row(
-                "    Shift+←/→   ",
+            bound_row(
+                app,
+                "    ",
+                Context::Global,
+                &[Action::SeekBackLarge, Action::SeekForwardLarge],
                 format!("Seek ±{}s", app.config.seek_step_large),
             ),
-            help_row("    t           ", "Jump to timestamp"),
-            help_row("    + / -       ", "Volume up / down"),
-            help_row("    m           ", "Mute toggle"),
+            bound_row(
+                app,
+                "    ",
+                Context::Global,
+                &[Action::JumpToTime, Action::Resume],
+                "Jump to timestamp / resume where you left off",
+            ),
+            bound_row(
+                app,
+                "    ",
+                Context::Global,
+                &[Action::VolumeUp, Action::VolumeDown],
+                "Volume up / down",
+            ),
+            bound_row(app, "    ", Context::Global, &[Action::Mute], "Mute toggle"),
+            bound_row(
+                app,
+                "    ",
+                Context::Global,
+                &[
+                    Action::PreviousChapter,
+                    Action::NextChapter,
+                    Action::Chapters,
+                ],
+                "Previous / next chapter / list chapters",
+            ),
+            bound_row(
+                app,
+                "    ",
+                Context::Global,
+                &[
+                    Action::Subtitles,
+                    Action::SubtitleLanguage,
+                    Action::Transcript,
+                ],
+                "Subtitles on/off / language / transcript",
+            ),
+            bound_row(
+                app,
+                "    ",
+                Context::Global,
+                &[Action::Shuffle, Action::Repeat],
+                "Shuffle / repeat: off, all, one",
+            ),
             Line::from(""),
             Line::from(Span::styled(
                 "  Other",
                 Style::default()
-                    .fg(Color::Yellow)
+                    .fg(theme.highlight)
                     .add_modifier(Modifier::BOLD),
             )),
-            help_row("    v           ", "Toggle terminal video view"),
-            help_row("    ? / h       ", "Toggle this help"),
-            help_row("    S / F2      ", "Settings"),
-            help_row("    q           ", "Quit (outside this Help window)"),
+            bound_row(
+                app,
+                "    ",
+                Context::Global,
+                &[Action::ToggleVideo],
+                "Toggle terminal video view",
+            ),
+            bound_row(
+                app,
+                "    ",
+                Context::Results,
+                &[Action::Help],
+                "Toggle this help",
+            ),
+            bound_row(
+                app,
+                "    ",
+                Context::Global,
+                &[Action::Settings],
+                "Settings",
+            ),
+            bound_row(
+                app,
+                "    ",
+                Context::Global,
+                &[Action::Quit],
+                "Quit (outside this Help window)",
+            ),
         ]
     } else {
-        compact_help_text(app.focused_panel)
+        compact_help_text(app)
     };
 
     let preferred_width = if show_full_help { 76 } else { 60 };
@@ -994,7 +1954,7 @@ fn render_help_overlay(f: &mut Frame, app: &App) {
     let visible_lines = area.height.saturating_sub(2) as usize;
     if help_text.len() > visible_lines {
         help_text.truncate(visible_lines.saturating_sub(1));
-        help_text.push(help_row("  Esc / ?     ", "Close help"));
+        help_text.push(help_row(theme, "  Esc / ?     ", "Close help"));
     }
 
     f.render_widget(Clear, area);
@@ -1005,61 +1965,225 @@ fn render_help_overlay(f: &mut Frame, app: &App) {
         .title(Span::styled(
             " Help ",
             Style::default()
-                .fg(Color::Cyan)
+                .fg(theme.accent)
                 .add_modifier(Modifier::BOLD),
         ))
         .title_bottom(Span::styled(
             " Esc / ? closes ",
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(theme.muted),
         ))
-        .border_style(Style::default().fg(Color::Cyan))
-        .style(Style::default().bg(Color::Black));
+        .border_style(Style::default().fg(theme.accent))
+        .style(Style::default().bg(theme.background));
 
     let paragraph = Paragraph::new(help_text).block(block);
     f.render_widget(paragraph, area);
 }
 
-fn compact_help_text(panel: FocusedPanel) -> Vec<Line<'static>> {
+fn compact_help_text(app: &App) -> Vec<Line<'static>> {
+    let theme = &app.theme;
     let mut lines = vec![
         Line::from(Span::styled(
             " Keyboard shortcuts",
             Style::default()
-                .fg(Color::Yellow)
+                .fg(theme.highlight)
                 .add_modifier(Modifier::BOLD),
         )),
-        help_row("  Tab / ⇧Tab  ", "Next / previous panel"),
+        help_row(theme, "  Tab / ⇧Tab  ", "Next / previous panel"),
     ];
+    let play = |context| app.keymap.short_label(context, &[Action::Play]);
 
-    match panel {
+    match app.focused_panel {
         FocusedPanel::SearchBar => {
-            lines.push(help_row("  Enter       ", "Search"));
-            lines.push(help_row("  Esc         ", "Cancel search editing"));
+            lines.push(bound_row(
+                app,
+                "  ",
+                Context::Search,
+                &[Action::Submit],
+                "Search",
+            ));
+            lines.push(bound_row(
+                app,
+                "  ",
+                Context::Search,
+                &[Action::Cancel],
+                "Cancel search editing",
+            ));
+            lines.push(bound_row(
+                app,
+                "  ",
+                Context::Search,
+                &[Action::OlderSearch, Action::FindSearch],
+                "Recall / find past searches",
+            ));
         }
         FocusedPanel::Results => {
-            lines.push(help_row("  ↑↓ / j k    ", "Move; Enter queues"));
-            lines.push(help_row("  n / p       ", "Next / previous page"));
-            lines.push(help_row("  /           ", "Edit search"));
+            lines.push(bound_row(
+                app,
+                "  ",
+                Context::Results,
+                &[Action::Up, Action::Down],
+                format!("Move; {} queues", play(Context::Results)),
+            ));
+            lines.push(bound_row(
+                app,
+                "  ",
+                Context::Results,
+                &[Action::NextPage, Action::PreviousPage],
+                "Next / previous page",
+            ));
+            lines.push(bound_row(
+                app,
+                "  ",
+                Context::Results,
+                &[Action::Add, Action::AddPage, Action::PlayNext],
+                "Add / add page / play next",
+            ));
+            lines.push(bound_row(
+                app,
+                "  ",
+                Context::Results,
+                &[Action::Search, Action::Details],
+                "Edit search / video details",
+            ));
         }
         FocusedPanel::Queue => {
-            lines.push(help_row("  ↑↓ / j k    ", "Move; Enter plays"));
-            lines.push(help_row("  Del         ", "Remove selected track"));
-            lines.push(help_row("  n / c       ", "Next track / clear queue"));
+            lines.push(bound_row(
+                app,
+                "  ",
+                Context::Queue,
+                &[Action::Up, Action::Down],
+                format!("Move; {} plays", play(Context::Queue)),
+            ));
+            lines.push(bound_row(
+                app,
+                "  ",
+                Context::Queue,
+                &[Action::Remove, Action::Mark],
+                "Remove track or marked / mark",
+            ));
+            lines.push(bound_row(
+                app,
+                "  ",
+                Context::Queue,
+                &[Action::MoveDown, Action::MoveUp, Action::Grab],
+                "Move down / up / grab to carry",
+            ));
+            lines.push(bound_row(
+                app,
+                "  ",
+                Context::Queue,
+                &[Action::NextTrack, Action::PreviousTrack, Action::Clear],
+                "Next / previous track / clear",
+            ));
+            lines.push(bound_row(
+                app,
+                "  ",
+                Context::Global,
+                &[Action::Shuffle, Action::Repeat],
+                "Shuffle / cycle repeat",
+            ));
+        }
+        FocusedPanel::History => {
+            lines.push(bound_row(
+                app,
+                "  ",
+                Context::History,
+                &[Action::Play],
+                "Play again now",
+            ));
+            lines.push(bound_row(
+                app,
+                "  ",
+                Context::History,
+                &[Action::Add, Action::Remove],
+                "Add to queue / forget entry",
+            ));
+        }
+        FocusedPanel::Playlists => {
+            lines.push(bound_row(
+                app,
+                "  ",
+                Context::Playlists,
+                &[Action::QueueAll],
+                "Add playlist to queue",
+            ));
+            lines.push(bound_row(
+                app,
+                "  ",
+                Context::Playlists,
+                &[Action::NewPlaylist, Action::AddQueue],
+                "New from queue / add queue",
+            ));
+            lines.push(bound_row(
+                app,
+                "  ",
+                Context::Playlists,
+                &[
+                    Action::Rename,
+                    Action::Remove,
+                    Action::Import,
+                    Action::Export,
+                ],
+                "Rename / delete / import/export",
+            ));
         }
     }
 
     lines.extend([
-        help_row("  Space / <>  ", "Pause / seek"),
-        help_row("  v           ", "Toggle video view"),
-        help_row("  S/F2 / q    ", "Settings / quit"),
-        help_row("  Esc / ?     ", "Close help"),
+        bound_row(
+            app,
+            "  ",
+            Context::Global,
+            &[Action::Pause, Action::Rewind, Action::FastForward],
+            "Pause / seek back / forward",
+        ),
+        bound_row(
+            app,
+            "  ",
+            Context::Global,
+            &[Action::ToggleVideo],
+            "Toggle video view",
+        ),
+        bound_row(
+            app,
+            "  ",
+            Context::Global,
+            &[Action::Settings, Action::Quit],
+            "Settings / quit",
+        ),
+        help_row(theme, "  Esc / ?     ", "Close help"),
     ]);
     lines
 }
 
-fn help_row(key: &'static str, desc: impl Into<Cow<'static, str>>) -> Line<'static> {
+/// A help row listing every key bound to `actions`, one action per slash,
+/// in a twelve-column key field after `indent`.
+fn bound_row(
+    app: &App,
+    indent: &str,
+    context: Context,
+    actions: &[Action],
+    desc: impl Into<Cow<'static, str>>,
+) -> Line<'static> {
+    let theme = &app.theme;
+    let keys = actions
+        .iter()
+        .map(|&action| app.keymap.label(context, action))
+        .filter(|label| !label.is_empty())
+        .collect::<Vec<_>>()
+        .join(" / ");
+    let keys = if keys.is_empty() { "unbound" } else { &keys };
+    help_row(theme, format!("{indent}{keys:<11} "), desc)
+}
+
+fn help_row(
+    theme: &Theme,
+    key: impl Into<Cow<'static, str>>,
+    desc: impl Into<Cow<'static, str>>,
+) -> Line<'static> {
     Line::from(vec![
-        Span::styled(key, Style::default().fg(Color::Cyan)),
-        Span::styled(desc, Style::default().fg(Color::White)),
+        Span::styled(key, Style::default().fg(theme.accent)),
+        Span::styled(desc, Style::default().fg(theme.text)),
     ])
 }
 
@@ -1081,26 +2205,624 @@ fn popup_rect(preferred_width: u16, preferred_height: u16, area: Rect) -> Rect {
     )
 }
 
-fn format_duration(seconds: u64) -> String {
-    let hrs = seconds / 3600;
-    let mins = (seconds % 3600) / 60;
-    let secs = seconds % 60;
-    if hrs > 0 {
-        format!("{}:{:02}:{:02}", hrs, mins, secs)
-    } else {
-        format!("{}:{:02}", mins, secs)
+fn format_duration(seconds: u64) -> String {
+    let hrs = seconds / 3600;
+    let mins = (seconds % 3600) / 60;
+    let secs = seconds % 60;
+    if hrs > 0 {
+        format!("{}:{:02}:{:02}", hrs, mins, secs)
+    } else {
+        format!("{}:{:02}", mins, secs)
+    }
+}
+
+fn render_restore_prompt(f: &mut Frame, theme: &Theme, snapshot: &QueueSnapshot) {
+    let area = popup_rect(60, 8, f.area());
+    f.render_widget(Clear, area);
+
+    let count = snapshot.tracks.len();
+    let plural = if count == 1 { "" } else { "s" };
+    let mut lines = vec![Line::from(Span::styled(
+        format!("{count} track{plural} were queued when youtui last closed."),
+        Style::default().fg(theme.text),
+    ))];
+    if let Some(front) = snapshot.tracks.first() {
+        lines.push(Line::from(vec![
+            Span::styled("▶ ", Style::default().fg(theme.accent)),
+            Span::styled(
+                front.title.clone(),
+                Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+            ),
+        ]));
+        let resume = if snapshot.time_pos >= 1.0 {
+            format!(
+                "  resumes paused at {}",
+                format_duration(snapshot.time_pos as u64)
+            )
+        } else {
+            "  resumes paused from the start".to_string()
+        };
+        lines.push(Line::from(Span::styled(
+            resume,
+            Style::default().fg(theme.muted),
+        )));
+    }
+    lines.push(Line::from(""));
+    lines.push(controls_line(
+        theme,
+        &[("y/Enter", "Restore"), ("n/Esc", "Start fresh")],
+        area.width.saturating_sub(2) as usize,
+    ));
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .title(Span::styled(
+            " Restore queue? ",
+            Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .border_style(Style::default().fg(theme.accent))
+        .style(Style::default().bg(theme.background));
+
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn render_playlist_prompt(f: &mut Frame, app: &App, prompt: &PlaylistPrompt) {
+    let theme = &app.theme;
+    let area = popup_rect(64, 6, f.area());
+    f.render_widget(Clear, area);
+
+    let selected_name = app
+        .playlists
+        .get(app.playlist_selected_index)
+        .map_or("", |playlist| playlist.name.as_str());
+    let (title, label) = match prompt.kind {
+        PlaylistPromptKind::Create => (" New playlist ", "Name for the current queue:".to_string()),
+        PlaylistPromptKind::Rename => (
+            " Rename playlist ",
+            format!("New name for \"{selected_name}\":"),
+        ),
+        PlaylistPromptKind::Import => (
+            " Import playlist ",
+            "Path to a .m3u, .m3u8 or .json file:".to_string(),
+        ),
+        PlaylistPromptKind::Export => (
+            " Export playlist ",
+            "Save as .m3u, .m3u8 or .json:".to_string(),
+        ),
+        PlaylistPromptKind::Delete => {
+            (" Delete playlist? ", format!("Delete \"{selected_name}\"?"))
+        }
+    };
+
+    let inner_width = area.width.saturating_sub(2) as usize;
+    let mut lines = vec![Line::from(Span::styled(
+        label,
+        Style::default().fg(theme.text),
+    ))];
+    if prompt.kind == PlaylistPromptKind::Delete {
+        lines.push(Line::from(""));
+        lines.push(controls_line(
+            theme,
+            &[("y/Enter", "Delete"), ("n/Esc", "Keep")],
+            inner_width,
+        ));
+    } else {
+        lines.push(Line::from(Span::styled(
+            visible_input(&prompt.input, inner_width),
+            Style::default()
+                .fg(theme.highlight)
+                .add_modifier(Modifier::BOLD),
+        )));
+        lines.push(Line::from(""));
+        lines.push(controls_line(
+            theme,
+            &[("Enter", "OK"), ("Esc", "Cancel")],
+            inner_width,
+        ));
+    }
+
+    let mut block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .title(Span::styled(
+            title,
+            Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .border_style(Style::default().fg(theme.accent))
+        .style(Style::default().bg(theme.background));
+    if let Some(message) = app.status_message.as_deref() {
+        block = block.title_bottom(Span::styled(
+            format!(" ! {message} "),
+            Style::default()
+                .fg(theme.error)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn render_filter_editor(f: &mut Frame, theme: &Theme, editor: &FilterEditor) {
+    let area = popup_rect(52, 8, f.area());
+    f.render_widget(Clear, area);
+
+    let draft = &editor.draft;
+    let items = vec![
+        cycle_item(theme, 0, "Sort by", draft.sort.label(), editor.selected),
+        cycle_item(theme, 1, "Length", draft.duration.label(), editor.selected),
+        cycle_item(
+            theme,
+            2,
+            "Uploaded",
+            draft.uploaded.label(),
+            editor.selected,
+        ),
+        cycle_item(theme, 3, "Live", draft.live.label(), editor.selected),
+    ];
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .title(Span::styled(
+            " Search filters ",
+            Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .title_bottom(Span::styled(
+            " ←→ change · x reset · Enter apply · Esc cancel ",
+            Style::default().fg(theme.muted),
+        ))
+        .border_style(Style::default().fg(theme.accent))
+        .style(Style::default().bg(theme.background));
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(inner);
+    f.render_widget(List::new(items), rows[1]);
+}
+
+fn render_chapter_picker(f: &mut Frame, app: &App, selected: usize) {
+    let theme = &app.theme;
+    let (chapters, playing) = app
+        .player_manager
+        .as_ref()
+        .map_or((&[][..], None), |player| {
+            (player.status.chapters.as_slice(), player.status.chapter)
+        });
+    let area = popup_rect(60, chapters.len() as u16 + 2, f.area());
+    f.render_widget(Clear, area);
+
+    let items: Vec<ListItem> = chapters
+        .iter()
+        .enumerate()
+        .map(|(index, chapter)| {
+            let marker = if Some(index) == playing { "▶ " } else { "  " };
+            ListItem::new(Line::from(vec![
+                Span::styled(marker, Style::default().fg(theme.positive)),
+                Span::styled(
+                    format!("{:>8}  ", format_duration(chapter.start as u64)),
+                    Style::default().fg(theme.positive),
+                ),
+                Span::styled(chapter.title.clone(), Style::default().fg(theme.text)),
+            ]))
+        })
+        .collect();
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .title(Span::styled(
+            " Chapters ",
+            Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .title_bottom(Span::styled(
+            " Enter jumps · Esc closes ",
+            Style::default().fg(theme.muted),
+        ))
+        .border_style(Style::default().fg(theme.accent))
+        .style(Style::default().bg(theme.background));
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(theme.selection().add_modifier(Modifier::BOLD));
+    let mut state = ListState::default();
+    state.select(Some(selected.min(chapters.len().saturating_sub(1))));
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+fn render_language_picker(f: &mut Frame, app: &App, selected: usize) {
+    let theme = &app.theme;
+    let languages = app
+        .subtitles
+        .transcript()
+        .map_or(&[][..], |transcript| transcript.languages.as_slice());
+    let area = popup_rect(50, languages.len().max(1) as u16 + 2, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .title(Span::styled(
+            " Subtitle language ",
+            Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .title_bottom(Span::styled(
+            " Enter picks · Esc closes ",
+            Style::default().fg(theme.muted),
+        ))
+        .border_style(Style::default().fg(theme.accent))
+        .style(Style::default().bg(theme.background));
+
+    if languages.is_empty() {
+        let message = match app.subtitles.state() {
+            Some(SubtitleState::Loaded(_)) => "This video has no subtitles",
+            Some(SubtitleState::Failed(_)) => "Could not read the subtitle languages",
+            _ => "Loading languages…",
+        };
+        f.render_widget(
+            Paragraph::new(Span::styled(message, Style::default().fg(theme.muted))).block(block),
+            area,
+        );
+        return;
+    }
+
+    let items: Vec<ListItem> = languages
+        .iter()
+        .map(|language| {
+            let marker = if language.code == app.config.subtitle_language {
+                "▶ "
+            } else {
+                "  "
+            };
+            let mut spans = vec![
+                Span::styled(marker, Style::default().fg(theme.positive)),
+                Span::styled(language.name.clone(), Style::default().fg(theme.text)),
+                Span::styled(
+                    format!("  {}", language.code),
+                    Style::default().fg(theme.secondary),
+                ),
+            ];
+            if language.automatic {
+                spans.push(Span::styled(" · auto", Style::default().fg(theme.muted)));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(theme.selection().add_modifier(Modifier::BOLD))
+        .scroll_padding(2);
+    let mut state = ListState::default();
+    state.select(Some(selected.min(languages.len() - 1)));
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+/// The transcript view: every subtitle line with its time, the one being
+/// spoken marked, and lines matching the search highlighted.
+fn render_transcript(f: &mut Frame, app: &App, view: &TranscriptView) {
+    let theme = &app.theme;
+    let area = popup_rect(80, 40, f.area());
+    f.render_widget(Clear, area);
+
+    let language = app.subtitles.transcript().and_then(|transcript| {
+        transcript
+            .languages
+            .iter()
+            .find(|language| language.code == app.config.subtitle_language)
+    });
+    let title = match language {
+        Some(language) => format!(" Transcript · {} ", language.name),
+        None => format!(" Transcript · {} ", app.config.subtitle_language),
+    };
+    let hint = if view.searching {
+        Span::styled(
+            format!(" /{}▌ ", view.query),
+            Style::default().fg(theme.highlight),
+        )
+    } else if view.query.is_empty() {
+        Span::styled(
+            " / searches · Enter seeks · Esc closes ",
+            Style::default().fg(theme.muted),
+        )
+    } else {
+        Span::styled(
+            format!(
+                " n/N next/previous \"{}\" · Enter seeks · Esc closes ",
+                view.query
+            ),
+            Style::default().fg(theme.muted),
+        )
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .title(Span::styled(
+            title,
+            Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .title_bottom(hint)
+        .border_style(Style::default().fg(theme.accent))
+        .style(Style::default().bg(theme.background));
+
+    let transcript = match app.subtitles.state() {
+        Some(SubtitleState::Loaded(transcript)) if !transcript.cues.is_empty() => transcript,
+        state => {
+            let message = match state {
+                Some(SubtitleState::Loaded(_)) => format!(
+                    "No {} subtitles. Press {} to pick another language.",
+                    app.config.subtitle_language,
+                    app.keymap
+                        .short_label(Context::Global, &[Action::SubtitleLanguage])
+                ),
+                Some(SubtitleState::Failed(error)) => format!("Could not read subtitles: {error}"),
+                _ => "Loading subtitles…".to_string(),
+            };
+            f.render_widget(
+                Paragraph::new(Span::styled(message, Style::default().fg(theme.muted)))
+                    .wrap(Wrap { trim: true })
+                    .block(block),
+                area,
+            );
+            return;
+        }
+    };
+
+    let position = app
+        .player_manager
+        .as_ref()
+        .map_or(0.0, |player| player.status.time_pos);
+    let speaking = transcript.cue_at(position);
+    let query = view.query.to_lowercase();
+    let items: Vec<ListItem> = transcript
+        .cues
+        .iter()
+        .enumerate()
+        .map(|(index, cue)| {
+            let marker = if Some(index) == speaking {
+                "▶ "
+            } else {
+                "  "
+            };
+            let matches = !query.is_empty() && cue.text.to_lowercase().contains(&query);
+            ListItem::new(Line::from(vec![
+                Span::styled(marker, Style::default().fg(theme.positive)),
+                Span::styled(
+                    format!("{:>8}  ", format_duration(cue.start as u64)),
+                    Style::default().fg(theme.positive),
+                ),
+                Span::styled(
+                    cue.text.clone(),
+                    Style::default().fg(if matches { theme.highlight } else { theme.text }),
+                ),
+            ]))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(theme.selection().add_modifier(Modifier::BOLD))
+        .scroll_padding(3);
+    let mut state = ListState::default();
+    state.select(Some(view.line(transcript, position)));
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+/// The details view: the track's summary straight away, then likes, the
+/// upload date, chapters, tags and the description once yt-dlp has read
+/// them. Text is wrapped here rather than by the paragraph so the scroll
+/// limit is known.
+fn render_details(f: &mut Frame, app: &App, details: &DetailsView) {
+    let theme = &app.theme;
+    let area = popup_rect(80, 40, f.area());
+    f.render_widget(Clear, area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .title(Span::styled(
+            " Details ",
+            Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .title_bottom(Span::styled(
+            " ↑↓ scroll · Esc closes ",
+            Style::default().fg(theme.muted),
+        ))
+        .border_style(Style::default().fg(theme.accent))
+        .style(Style::default().bg(theme.background));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    // One column of padding each side, and one more for the scrollbar.
+    let width = inner.width.saturating_sub(3).max(1) as usize;
+    let track = &details.track;
+    let heading = |title: &'static str| {
+        Line::from(Span::styled(
+            title,
+            Style::default()
+                .fg(theme.highlight)
+                .add_modifier(Modifier::BOLD),
+        ))
+    };
+    let wrapped = |text: &str, style: Style| {
+        wrap_words(text, width)
+            .into_iter()
+            .map(move |line| Line::from(Span::styled(line, style)))
+    };
+
+    let mut lines: Vec<Line> = wrapped(
+        &track.title,
+        Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+    )
+    .collect();
+    lines.extend(wrapped(&track.channel, Style::default().fg(theme.accent)));
+    lines.push(Line::from(""));
+
+    let loaded = match &details.state {
+        DetailsState::Loaded(loaded) => Some(loaded),
+        _ => None,
+    };
+    let mut stats = vec![track.duration.clone()];
+    match loaded.and_then(|loaded| loaded.views) {
+        Some(views) => stats.push(format!("{} views", group_digits(views))),
+        None if !track.views.is_empty() => stats.push(track.views.clone()),
+        None => {}
+    }
+    if let Some(likes) = loaded.and_then(|loaded| loaded.likes) {
+        stats.push(format!("{} likes", group_digits(likes)));
+    }
+    match loaded.and_then(|loaded| loaded.uploaded.as_deref()) {
+        Some(uploaded) => stats.push(format!("uploaded {uploaded}")),
+        None if !track.published.is_empty() => stats.push(track.published.clone()),
+        None => {}
+    }
+    stats.retain(|stat| !stat.is_empty());
+    lines.extend(wrapped(
+        &stats.join(" · "),
+        Style::default().fg(theme.secondary),
+    ));
+
+    match &details.state {
+        DetailsState::Loading => {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                "Loading details…",
+                Style::default().fg(theme.muted),
+            )));
+        }
+        DetailsState::Failed(message) => {
+            lines.push(Line::from(""));
+            lines.extend(wrapped(
+                &format!("Could not load details: {message}"),
+                Style::default().fg(theme.error),
+            ));
+        }
+        DetailsState::Loaded(loaded) => {
+            if !loaded.chapters.is_empty() {
+                lines.push(Line::from(""));
+                lines.push(heading("Chapters"));
+                for chapter in &loaded.chapters {
+                    lines.push(Line::from(vec![
+                        Span::styled(
+                            format!("{:>8}  ", format_duration(chapter.start as u64)),
+                            Style::default().fg(theme.positive),
+                        ),
+                        Span::styled(chapter.title.clone(), Style::default().fg(theme.text)),
+                    ]));
+                }
+            }
+            if !loaded.tags.is_empty() {
+                lines.push(Line::from(""));
+                lines.push(heading("Tags"));
+                let tags = loaded
+                    .tags
+                    .iter()
+                    .map(|tag| format!("#{}", tag.replace(' ', "_")))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                lines.extend(wrapped(&tags, Style::default().fg(theme.secondary)));
+            }
+            lines.push(Line::from(""));
+            lines.push(heading("Description"));
+            if loaded.description.is_empty() {
+                lines.push(Line::from(Span::styled(
+                    "No description",
+                    Style::default().fg(theme.muted),
+                )));
+            }
+            for paragraph in loaded.description.lines() {
+                if paragraph.trim().is_empty() {
+                    lines.push(Line::from(""));
+                } else {
+                    lines.extend(wrapped(paragraph, Style::default().fg(theme.text)));
+                }
+            }
+        }
+    }
+
+    let visible = inner.height as usize;
+    let limit = lines.len().saturating_sub(visible).min(u16::MAX as usize) as u16;
+    app.screen_areas.borrow_mut().details_scroll_limit = limit;
+    let scroll = details.scroll.min(limit);
+    let text_area = Rect {
+        x: inner.x + 1,
+        width: inner.width.saturating_sub(2),
+        ..inner
+    };
+    f.render_widget(Paragraph::new(lines).scroll((scroll, 0)), text_area);
+    if limit > 0 {
+        let mut scrollbar_state = ScrollbarState::new(limit as usize + 1).position(scroll as usize);
+        f.render_stateful_widget(
+            Scrollbar::new(ScrollbarOrientation::VerticalRight),
+            inner,
+            &mut scrollbar_state,
+        );
+    }
+}
+
+/// Break `text` into lines of at most `width` columns at spaces, splitting
+/// words longer than a whole line.
+fn wrap_words(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut line_width = 0;
+    for word in text.split_whitespace() {
+        let word_width = Span::raw(word).width();
+        if line_width > 0 && line_width + 1 + word_width > width {
+            lines.push(std::mem::take(&mut line));
+            line_width = 0;
+        }
+        if line_width > 0 {
+            line.push(' ');
+            line_width += 1;
+        }
+        if word_width <= width.saturating_sub(line_width) {
+            line.push_str(word);
+            line_width += word_width;
+            continue;
+        }
+        for c in word.chars() {
+            let c_width = Span::raw(c.to_string()).width();
+            if line_width > 0 && line_width + c_width > width {
+                lines.push(std::mem::take(&mut line));
+                line_width = 0;
+            }
+            line.push(c);
+            line_width += c_width;
+        }
     }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
 }
 
 fn render_settings_modal(f: &mut Frame, app: &App) {
-    let area = popup_rect(76, 27, f.area());
+    let theme = &app.theme;
+    let area = popup_rect(76, 32, f.area());
     f.render_widget(Clear, area);
 
     let items = settings_items(app);
     let bottom_title = if let Some(message) = app.status_message.as_deref() {
         Span::styled(
             format!(" ! {message} "),
-            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            Style::default()
+                .fg(theme.error)
+                .add_modifier(Modifier::BOLD),
         )
     } else {
         Span::styled(
@@ -1109,7 +2831,7 @@ fn render_settings_modal(f: &mut Frame, app: &App) {
             } else {
                 " ↑↓/Tab move · Enter change · Esc close "
             },
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(theme.muted),
         )
     };
 
@@ -1123,12 +2845,12 @@ fn render_settings_modal(f: &mut Frame, app: &App) {
                 " Settings "
             },
             Style::default()
-                .fg(Color::Cyan)
+                .fg(theme.accent)
                 .add_modifier(Modifier::BOLD),
         ))
         .title_bottom(bottom_title)
-        .border_style(Style::default().fg(Color::Cyan))
-        .style(Style::default().bg(Color::Black));
+        .border_style(Style::default().fg(theme.accent))
+        .style(Style::default().bg(theme.background));
 
     let item_count = items.len();
     let list = List::new(items)
@@ -1146,12 +2868,14 @@ fn render_settings_modal(f: &mut Frame, app: &App) {
 }
 
 fn settings_items(app: &App) -> Vec<ListItem<'static>> {
+    let theme = &app.theme;
     let selected = app.settings_selected_index;
     let editing = &app.settings_editing;
     let buffered_value = app.settings_text_input.as_deref();
     let results_per_page = app.config.results_per_page.to_string();
     let seek_step = app.config.seek_step.to_string();
     let seek_step_large = app.config.seek_step_large.to_string();
+    let resume_margin = app.config.resume_margin.to_string();
     let download_dir = if editing == &Some(SettingsField::DownloadDir) {
         buffered_value.unwrap_or(&app.config.download_dir)
     } else {
@@ -1177,26 +2901,46 @@ fn settings_items(app: &App) -> Vec<ListItem<'static>> {
     } else {
         &seek_step_large
     };
+    let resume_margin = if editing == &Some(SettingsField::ResumeMargin) {
+        buffered_value.unwrap_or(&resume_margin)
+    } else {
+        &resume_margin
+    };
 
     vec![
-        section_header("  Playback"),
-        section_rule(),
-        checkbox_item(2, "Audio Only", app.config.audio_only, selected),
+        section_header(theme, "  Playback"),
+        section_rule(theme),
+        checkbox_item(theme, 2, "Audio Only", app.config.audio_only, selected),
         checkbox_item(
+            theme,
             3,
             "Bandwidth Limit (360p video / 128k audio)",
             app.config.bandwidth_limit,
             selected,
         ),
-        checkbox_item(4, "Keep Temporary Files", app.config.keep_temp, selected),
         checkbox_item(
+            theme,
+            4,
+            "Keep Temporary Files",
+            app.config.keep_temp,
+            selected,
+        ),
+        checkbox_item(
+            theme,
             5,
             "Include YouTube Shorts",
             app.config.include_shorts,
             selected,
         ),
-        checkbox_item(6, "Auto Play Queue", app.config.auto_play_queue, selected),
+        checkbox_item(
+            theme,
+            6,
+            "Auto Play Queue",
+            app.config.auto_play_queue,
+            selected,
+        ),
         text_field_item(
+            theme,
             7,
             "Seek step (s)",
             seek_step,
@@ -1205,6 +2949,7 @@ fn settings_items(app: &App) -> Vec<ListItem<'static>> {
             SettingsField::SeekStep,
         ),
         text_field_item(
+            theme,
             8,
             "Large seek step (s)",
             seek_step_large,
@@ -1212,17 +2957,28 @@ fn settings_items(app: &App) -> Vec<ListItem<'static>> {
             editing,
             SettingsField::SeekStepLarge,
         ),
+        text_field_item(
+            theme,
+            9,
+            "Resume margin (s)",
+            resume_margin,
+            selected,
+            editing,
+            SettingsField::ResumeMargin,
+        ),
         ListItem::new(""),
-        section_header("  Downloads"),
-        section_rule(),
+        section_header(theme, "  Downloads"),
+        section_rule(theme),
         checkbox_item(
-            12,
+            theme,
+            13,
             "Download Mode (save permanently)",
             app.config.download_mode,
             selected,
         ),
         text_field_item(
-            13,
+            theme,
+            14,
             "Download Directory",
             download_dir,
             selected,
@@ -1230,10 +2986,11 @@ fn settings_items(app: &App) -> Vec<ListItem<'static>> {
             SettingsField::DownloadDir,
         ),
         ListItem::new(""),
-        section_header("  Display"),
-        section_rule(),
+        section_header(theme, "  Display"),
+        section_rule(theme),
         text_field_item(
-            17,
+            theme,
+            18,
             "Results Per Page",
             results_per_page,
             selected,
@@ -1241,16 +2998,53 @@ fn settings_items(app: &App) -> Vec<ListItem<'static>> {
             SettingsField::ResultsPerPage,
         ),
         cycle_item(
-            18,
+            theme,
+            19,
             "Video Renderer (auto/pixels/blocks)",
             app.config.video_render.label(),
             selected,
         ),
+        checkbox_item(
+            theme,
+            20,
+            "Notify on Track Change (in background)",
+            app.config.notify,
+            selected,
+        ),
+        checkbox_item(
+            theme,
+            21,
+            "Radio (continue with related videos)",
+            app.config.radio,
+            selected,
+        ),
+        cycle_item(
+            theme,
+            22,
+            "Theme (dark/light/high-contrast/no-color)",
+            &app.config.theme,
+            selected,
+        ),
+        checkbox_item(
+            theme,
+            23,
+            "Thumbnail Previews (wide terminals)",
+            app.config.thumbnails,
+            selected,
+        ),
+        checkbox_item(
+            theme,
+            24,
+            "SponsorBlock (skip sponsored segments)",
+            app.config.sponsorblock,
+            selected,
+        ),
         ListItem::new(""),
-        section_header("  Advanced"),
-        section_rule(),
+        section_header(theme, "  Advanced"),
+        section_rule(theme),
         text_field_item(
-            22,
+            theme,
+            28,
             "Custom Format",
             custom_format,
             selected,
@@ -1259,43 +3053,44 @@ fn settings_items(app: &App) -> Vec<ListItem<'static>> {
         ),
         ListItem::new(Line::from(Span::styled(
             "  (leave empty for auto)",
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(theme.muted),
         ))),
         ListItem::new(""),
         ListItem::new(Line::from(vec![
-            Span::styled("  ✓ ", Style::default().fg(Color::Green)),
+            Span::styled("  ✓ ", Style::default().fg(theme.positive)),
             Span::styled(
                 "Text changes save when editing ends   ",
-                Style::default().fg(Color::Gray),
+                Style::default().fg(theme.secondary),
             ),
             Span::styled(
                 "[Esc]",
                 Style::default()
-                    .fg(Color::Yellow)
+                    .fg(theme.highlight)
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::styled(" Close", Style::default().fg(Color::Gray)),
+            Span::styled(" Close", Style::default().fg(theme.secondary)),
         ])),
     ]
 }
 
-fn section_header(title: &'static str) -> ListItem<'static> {
+fn section_header(theme: &Theme, title: &'static str) -> ListItem<'static> {
     ListItem::new(Line::from(Span::styled(
         title,
         Style::default()
-            .fg(Color::Yellow)
+            .fg(theme.highlight)
             .add_modifier(Modifier::BOLD),
     )))
 }
 
-fn section_rule() -> ListItem<'static> {
+fn section_rule(theme: &Theme) -> ListItem<'static> {
     ListItem::new(Line::from(Span::styled(
         "  ────────────────────────────────────",
-        Style::default().fg(Color::DarkGray),
+        Style::default().fg(theme.muted),
     )))
 }
 
 fn checkbox_item(
+    theme: &Theme,
     idx: usize,
     label: &'static str,
     checked: bool,
@@ -1306,27 +3101,14 @@ fn checkbox_item(
 
     let (checkbox_style, label_style, bg) = if is_selected {
         (
-            Style::default()
-                .fg(if checked {
-                    Color::Green
-                } else {
-                    Color::DarkGray
-                })
-                .bg(Color::Blue),
-            Style::default()
-                .fg(Color::White)
-                .bg(Color::Blue)
-                .add_modifier(Modifier::BOLD),
-            Style::default().bg(Color::Blue),
+            theme.selection(),
+            theme.selection().add_modifier(Modifier::BOLD),
+            theme.selection(),
         )
     } else {
         (
-            Style::default().fg(if checked {
-                Color::Green
-            } else {
-                Color::DarkGray
-            }),
-            Style::default().fg(Color::White),
+            Style::default().fg(if checked { theme.positive } else { theme.muted }),
+            Style::default().fg(theme.text),
             Style::default(),
         )
     };
@@ -1341,6 +3123,7 @@ fn checkbox_item(
 }
 
 fn text_field_item(
+    theme: &Theme,
     idx: usize,
     label: &'static str,
     value: &str,
@@ -1355,24 +3138,18 @@ fn text_field_item(
 
     let line = if is_selected {
         Line::from(vec![
-            Span::styled("  ", Style::default().bg(Color::Blue)),
+            Span::styled("  ", theme.selection()),
             Span::styled(
                 format!("{}: ", label),
-                Style::default()
-                    .fg(Color::Cyan)
-                    .bg(Color::Blue)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(
-                value_owned,
-                Style::default().fg(Color::White).bg(Color::Blue),
+                theme.selection().add_modifier(Modifier::BOLD),
             ),
+            Span::styled(value_owned, theme.selection()),
         ])
     } else {
         Line::from(vec![
             Span::raw("  "),
-            Span::styled(format!("{}: ", label), Style::default().fg(Color::Cyan)),
-            Span::styled(value_owned, Style::default().fg(Color::White)),
+            Span::styled(format!("{}: ", label), Style::default().fg(theme.accent)),
+            Span::styled(value_owned, Style::default().fg(theme.text)),
         ])
     };
 
@@ -1382,32 +3159,27 @@ fn text_field_item(
 /// A settings row whose value cycles through fixed choices on Enter, styled
 /// like `text_field_item` but with `‹ value ›` instead of an edit box.
 fn cycle_item(
+    theme: &Theme,
     idx: usize,
     label: &'static str,
-    value: &'static str,
+    value: &str,
     selected: usize,
 ) -> ListItem<'static> {
     let value_owned = format!("‹ {value} ›");
     let line = if idx == selected {
         Line::from(vec![
-            Span::styled("  ", Style::default().bg(Color::Blue)),
+            Span::styled("  ", theme.selection()),
             Span::styled(
                 format!("{}: ", label),
-                Style::default()
-                    .fg(Color::Cyan)
-                    .bg(Color::Blue)
-                    .add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(
-                value_owned,
-                Style::default().fg(Color::White).bg(Color::Blue),
+                theme.selection().add_modifier(Modifier::BOLD),
             ),
+            Span::styled(value_owned, theme.selection()),
         ])
     } else {
         Line::from(vec![
             Span::raw("  "),
-            Span::styled(format!("{}: ", label), Style::default().fg(Color::Cyan)),
-            Span::styled(value_owned, Style::default().fg(Color::White)),
+            Span::styled(format!("{}: ", label), Style::default().fg(theme.accent)),
+            Span::styled(value_owned, Style::default().fg(theme.text)),
         ])
     };
 
@@ -1416,11 +3188,18 @@ fn cycle_item(
 
 #[cfg(test)]
 mod tests {
+    use crossterm::event::{KeyCode, KeyEvent};
     use ratatui::{Terminal, backend::TestBackend};
 
     use super::*;
     use crate::config::Config;
+    use crate::details::{Chapter, VideoDetails};
+    use crate::player_manager::PlayerManager;
     use crate::search::SearchResult;
+    use crate::sponsorblock::Segment;
+    use crate::subtitles::{Cue, Subtitles, Transcript};
+    use crate::ui::handle_key_event;
+    use crate::worker::wait_until;
 
     fn app_with_unicode_content() -> App {
         let mut app = App::new("音楽と café 🎵".to_string(), 10, Config::default());
@@ -1516,7 +3295,7 @@ mod tests {
 
         app.input_mode = InputMode::Browse;
         app.settings_open = true;
-        app.settings_selected_index = 21;
+        app.settings_selected_index = 22;
         terminal.draw(|frame| render_ui(frame, &app)).unwrap();
     }
 
@@ -1569,4 +3348,403 @@ mod tests {
         assert!(screen.contains("Esc"));
         assert!(screen.contains("Cancel"));
     }
+
+    #[test]
+    fn restore_prompt_names_the_front_track_and_resume_point() {
+        let backend = TestBackend::new(100, 30);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut app = app_with_unicode_content();
+        app.restore_prompt = Some(QueueSnapshot {
+            tracks: app.results.clone(),
+            selected_index: 0,
+            time_pos: 754.0,
+        });
+
+        terminal.draw(|frame| render_ui(frame, &app)).unwrap();
+
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(screen.contains("Restore queue?"));
+        assert!(screen.contains("1 track were queued"));
+        assert!(screen.contains("12:34"));
+    }
+
+    #[test]
+    fn playlists_panel_and_prompt_render_beside_the_queue() {
+        let backend = TestBackend::new(100, 30);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut app = app_with_unicode_content();
+        app.playlists
+            .create("Road trip", app.results.clone())
+            .unwrap();
+        app.focused_panel = FocusedPanel::Playlists;
+        app.playlist_prompt = Some(PlaylistPrompt {
+            kind: PlaylistPromptKind::Rename,
+            input: "Road trip 2".to_string(),
+        });
+
+        terminal.draw(|frame| render_ui(frame, &app)).unwrap();
+
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(screen.contains("Playlists (1)"));
+        assert!(screen.contains("Rename playlist"));
+        assert!(screen.contains("Road trip 2█"));
+    }
+
+    #[test]
+    fn filter_editor_and_active_filters_are_shown() {
+        let backend = TestBackend::new(100, 30);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut app = app_with_unicode_content();
+        app.search_filters.sort = crate::search::SortOrder::Date;
+        app.filter_editor = Some(FilterEditor {
+            draft: app.search_filters,
+            selected: 1,
+        });
+
+        terminal.draw(|frame| render_ui(frame, &app)).unwrap();
+
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(screen.contains("· Newest first"));
+        assert!(screen.contains("Search filters"));
+        assert!(screen.contains("Length: ‹ Any length ›"));
+    }
+
+    #[test]
+    fn search_bar_suggests_the_rest_of_a_past_query() {
+        let backend = TestBackend::new(100, 30);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut app = App::new(String::new(), 10, Config::default());
+        app.search_history.record("daft punk live").unwrap();
+        app.focused_panel = FocusedPanel::SearchBar;
+        app.search_input = "daft p".to_string();
+
+        terminal.draw(|frame| render_ui(frame, &app)).unwrap();
+
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(screen.contains("daft p█unk live"));
+    }
+
+    #[test]
+    fn history_panel_and_watched_marks_are_shown() {
+        let backend = TestBackend::new(100, 30);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut app = app_with_unicode_content();
+        app.watch_history.start(app.results[0].clone());
+        app.watch_history.update_progress(210.0, 225.0);
+        app.focused_panel = FocusedPanel::History;
+
+        terminal.draw(|frame| render_ui(frame, &app)).unwrap();
+
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(screen.contains("History (1)"));
+        assert!(screen.contains("just now"));
+        assert!(screen.contains("✓ watched"));
+    }
+
+    #[test]
+    fn queue_title_shows_shuffle_and_repeat_modes() {
+        let backend = TestBackend::new(100, 30);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut app = app_with_unicode_content();
+        app.focused_panel = FocusedPanel::Queue;
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('z')));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('o')));
+        handle_key_event(&mut app, KeyEvent::from(KeyCode::Char('o')));
+
+        terminal.draw(|frame| render_ui(frame, &app)).unwrap();
+
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(screen.contains("· shuffle · repeat one"), "{screen}");
+    }
+
+    #[test]
+    fn footer_and_help_show_rebound_keys() {
+        let backend = TestBackend::new(120, 30);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut app = app_with_unicode_content();
+        app.config.keys = toml::from_str("[queue]\nmark = \"y\"\nclear = \"ctrl+l\"\n").unwrap();
+        app.keymap = app.config.keymap().unwrap();
+        app.focused_panel = FocusedPanel::Queue;
+
+        terminal.draw(|frame| render_ui(frame, &app)).unwrap();
+        let screen = screen_text(&terminal);
+        assert!(screen.contains("[y] Mark"), "{screen}");
+        assert!(screen.contains("[Ctrl+L] Clear"), "{screen}");
+
+        app.input_mode = InputMode::Help;
+        terminal.draw(|frame| render_ui(frame, &app)).unwrap();
+        let screen = screen_text(&terminal);
+        assert!(screen.contains("Del Bksp / y"), "{screen}");
+        assert!(screen.contains("Next / previous track / clear"), "{screen}");
+    }
+
+    #[test]
+    fn the_preview_pane_appears_beside_wide_results() {
+        let mut terminal = Terminal::new(TestBackend::new(140, 35)).unwrap();
+        let mut app = app_with_unicode_content();
+        terminal.draw(|frame| render_ui(frame, &app)).unwrap();
+        assert!(!screen_text(&terminal).contains("Preview"));
+
+        app.config.thumbnails = true;
+        terminal.draw(|frame| render_ui(frame, &app)).unwrap();
+        let screen = screen_text(&terminal);
+        assert!(screen.contains("Preview"), "{screen}");
+        assert!(screen.contains("loading thumbnail"), "{screen}");
+        let thumbnail = app.screen_areas.borrow().thumbnail.unwrap();
+        assert_eq!((thumbnail.width, thumbnail.height), (34, 10));
+
+        // Too narrow for both: the results keep the room.
+        let mut terminal = Terminal::new(TestBackend::new(90, 35)).unwrap();
+        terminal.draw(|frame| render_ui(frame, &app)).unwrap();
+        assert!(!screen_text(&terminal).contains("Preview"));
+    }
+
+    #[test]
+    fn every_screen_color_comes_from_the_theme() {
+        let backend = TestBackend::new(100, 35);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut app = app_with_unicode_content();
+        app.theme = Theme::NO_COLOR;
+
+        for settings_open in [false, true] {
+            app.settings_open = settings_open;
+            terminal.draw(|frame| render_ui(frame, &app)).unwrap();
+            for cell in terminal.backend().buffer().content() {
+                assert_eq!((cell.fg, cell.bg), (Color::Reset, Color::Reset));
+            }
+        }
+    }
+
+    #[test]
+    fn the_details_view_shows_the_metadata_and_scrolls_to_its_end() {
+        let mut terminal = Terminal::new(TestBackend::new(60, 20)).unwrap();
+        let mut app = app_with_unicode_content();
+        let track = app.results[0].clone();
+        app.details = Some(DetailsView::new(track));
+        terminal.draw(|frame| render_ui(frame, &app)).unwrap();
+        let screen = screen_text(&terminal);
+        assert!(screen.contains("Loading details…"), "{screen}");
+        assert_eq!(app.screen_areas.borrow().details_scroll_limit, 0);
+
+        let details = app.details.as_mut().unwrap();
+        details.state = DetailsState::Loaded(VideoDetails {
+            description: "line\n".repeat(30) + "The end",
+            views: Some(1_234_567),
+            likes: Some(8910),
+            uploaded: Some("2024-03-05".to_string()),
+            tags: vec!["live jazz".to_string()],
+            chapters: vec![Chapter {
+                start: 95.0,
+                title: "Take Five".to_string(),
+            }],
+        });
+        terminal.draw(|frame| render_ui(frame, &app)).unwrap();
+        let screen = screen_text(&terminal);
+        for text in ["1,234,567 views", "8,910 likes", "2024-03-05"] {
+            assert!(screen.contains(text), "{text} missing from {screen}");
+        }
+        assert!(screen.contains("1:35  Take Five"), "{screen}");
+        assert!(screen.contains("#live_jazz"), "{screen}");
+        assert!(!screen.contains("The end"), "{screen}");
+
+        let limit = app.screen_areas.borrow().details_scroll_limit;
+        assert!(limit > 0);
+        app.details.as_mut().unwrap().scroll = u16::MAX;
+        terminal.draw(|frame| render_ui(frame, &app)).unwrap();
+        assert!(screen_text(&terminal).contains("The end"));
+    }
+
+    #[test]
+    fn chapters_show_in_the_status_line_the_progress_bar_and_the_picker() {
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        let mut app = app_with_unicode_content();
+        let (client_stream, _server_stream) = std::os::unix::net::UnixStream::pair().unwrap();
+        let mut player = PlayerManager::from_test_stream(client_stream);
+        player.status.title = "Long mix".to_string();
+        player.status.duration = 400.0;
+        player.status.time_pos = 150.0;
+        player.status.chapters = [(0.0, "Opening"), (100.0, "Middle"), (300.0, "Finale")]
+            .map(|(start, title)| Chapter {
+                start,
+                title: title.to_string(),
+            })
+            .to_vec();
+        player.status.chapter = Some(1);
+        app.player_manager = Some(player);
+
+        terminal.draw(|frame| render_ui(frame, &app)).unwrap();
+        let screen = screen_text(&terminal);
+        assert!(screen.contains("Long mix  ›  Middle (2/3)"), "{screen}");
+        // One tick behind the thumb and one ahead; none for the first chapter.
+        let bar = app.screen_areas.borrow().progress_bar.unwrap();
+        let row: String = (bar.x..bar.right())
+            .map(|x| terminal.backend().buffer()[(x, bar.y)].symbol().to_string())
+            .collect();
+        assert_eq!(row.matches('╋').count(), 1, "{row}");
+        assert_eq!(row.matches('┼').count(), 1, "{row}");
+        assert!(row.find('╋') < row.find('●') && row.find('●') < row.find('┼'));
+
+        app.chapter_picker = Some(2);
+        terminal.draw(|frame| render_ui(frame, &app)).unwrap();
+        let screen = screen_text(&terminal);
+        assert!(screen.contains("Chapters"), "{screen}");
+        assert!(screen.contains("▶     1:40  Middle"), "{screen}");
+        assert!(screen.contains("5:00  Finale"), "{screen}");
+    }
+
+    #[test]
+    fn sponsorblock_segments_are_marked_on_the_progress_bar() {
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        let mut app = app_with_unicode_content();
+        let (client_stream, _server_stream) = std::os::unix::net::UnixStream::pair().unwrap();
+        let mut player = PlayerManager::from_test_stream(client_stream);
+        player.status.duration = 400.0;
+        player.status.time_pos = 200.0;
+        player.status.segments = vec![
+            Segment {
+                start: 0.0,
+                end: 40.0,
+                category: "intro".to_string(),
+            },
+            Segment {
+                start: 300.0,
+                end: 340.0,
+                category: "sponsor".to_string(),
+            },
+        ];
+        app.player_manager = Some(player);
+
+        terminal.draw(|frame| render_ui(frame, &app)).unwrap();
+        let bar = app.screen_areas.borrow().progress_bar.unwrap();
+        let row: String = (bar.x..bar.right())
+            .map(|x| terminal.backend().buffer()[(x, bar.y)].symbol().to_string())
+            .collect();
+        let thumb = row.find('●').unwrap();
+        let (played, ahead) = row.split_at(thumb);
+        assert!(played.starts_with('═') && played.contains('━'), "{row}");
+        assert!(ahead.contains('═') && ahead.ends_with('─'), "{row}");
+        // Each segment covers about a tenth of the bar.
+        let marked = row.matches('═').count();
+        assert!((bar.width as usize / 5..=bar.width as usize / 5 + 2).contains(&marked));
+    }
+
+    #[test]
+    fn the_spoken_subtitle_shows_under_the_status_and_in_the_transcript() {
+        fn canned(
+            _: &str,
+            _: &str,
+            _: &std::sync::atomic::AtomicBool,
+        ) -> anyhow::Result<Transcript> {
+            Ok(Transcript {
+                cues: [(0.0, "First words"), (10.0, "Second thoughts")]
+                    .map(|(start, text)| Cue {
+                        start,
+                        end: start + 5.0,
+                        text: text.to_string(),
+                    })
+                    .to_vec(),
+                languages: Vec::new(),
+            })
+        }
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        let mut app = app_with_unicode_content();
+        let (client_stream, _server_stream) = std::os::unix::net::UnixStream::pair().unwrap();
+        let mut player = PlayerManager::from_test_stream(client_stream);
+        player.status.duration = 60.0;
+        player.status.time_pos = 12.0;
+        app.player_manager = Some(player);
+        app.config.subtitles = true;
+        app.subtitles = Subtitles::with_fetcher(canned);
+
+        terminal.draw(|frame| render_ui(frame, &app)).unwrap();
+        assert!(screen_text(&terminal).contains("Loading subtitles…"));
+
+        wait_until("subtitles never arrived", || {
+            app.subtitles
+                .update(Some("abcdefghijk"), &app.config.subtitle_language, true);
+            app.subtitles.transcript().is_some()
+        });
+        terminal.draw(|frame| render_ui(frame, &app)).unwrap();
+        let screen = screen_text(&terminal);
+        assert!(screen.contains("Second thoughts"), "{screen}");
+        // The line sits centred between the status and the controls.
+        let buffer = terminal.backend().buffer();
+        let row = |y: u16| {
+            (0..100)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect::<String>()
+        };
+        let line = row(27);
+        assert_eq!(line.trim(), "Second thoughts");
+        let leading = line.len() - line.trim_start().len();
+        let trailing = line.len() - line.trim_end().len();
+        assert!(leading.abs_diff(trailing) <= 1, "{line:?}");
+        assert!(!screen.contains("First words"), "{screen}");
+
+        // Between cues the line is blank.
+        app.player_manager.as_mut().unwrap().status.time_pos = 7.0;
+        terminal.draw(|frame| render_ui(frame, &app)).unwrap();
+        assert!(!screen_text(&terminal).contains("Second thoughts"));
+
+        app.transcript = Some(TranscriptView::default());
+        terminal.draw(|frame| render_ui(frame, &app)).unwrap();
+        let screen = screen_text(&terminal);
+        assert!(screen.contains("Transcript · en"), "{screen}");
+        assert!(screen.contains("0:00  First words"), "{screen}");
+        assert!(screen.contains("0:10  Second thoughts"), "{screen}");
+    }
+
+    #[test]
+    fn details_text_wraps_at_spaces_and_splits_long_words() {
+        assert_eq!(wrap_words("one two three", 7), ["one two", "three"]);
+        assert_eq!(wrap_words("abcdefghij xy", 4), ["abcd", "efgh", "ij", "xy"]);
+        assert_eq!(wrap_words("  ", 10), [""]);
+    }
+
+    fn screen_text(terminal: &Terminal<TestBackend>) -> String {
+        terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect()
+    }
 }
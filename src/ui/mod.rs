@@ -3,8 +3,9 @@ pub mod events;
 pub mod layout;
 pub mod runner;
 pub mod terminal;
+pub mod theme;
 
 pub use app::{App, FocusedPanel};
-pub use events::handle_key_event;
+pub use events::{handle_key_event, handle_mouse_event};
 pub use runner::run_app;
 pub use terminal::{TerminalGuard, init_terminal};
@@ -1,4 +1,4 @@
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
 use std::sync::{
     Arc,
     atomic::{AtomicBool, Ordering},
@@ -7,16 +7,27 @@ use std::sync::{
 use std::thread::{self, JoinHandle};
 use std::time::{Duration, Instant};
 
-use anyhow::Result;
+use anyhow::{Result, bail};
 use colored::Colorize;
 use crossterm::event::{self, Event};
+use ratatui::layout::{Rect, Size};
+use serde_json::{Value, json};
 
 use crate::cleanup::{INTERRUPTED, ManagedTempDir};
 use crate::config::clamp_results_per_page;
+use crate::details::DetailsView;
+#[cfg(target_os = "linux")]
+use crate::mpris::{MprisService, PlayerState, Track};
+use crate::notify::Notifier;
+use crate::player::PlaybackResult;
 use crate::player_manager::PlayerManager;
-use crate::search::PaginatedSearch;
-use crate::ui::app::{AppAction, SearchPhase};
-use crate::ui::{App, handle_key_event, layout::render_ui, terminal::Tui};
+use crate::radio::{self, Radio};
+use crate::remote::{RemoteCommand, RemoteServer};
+use crate::search::{PaginatedSearch, SearchFilters, SearchResult};
+use crate::session::SessionSaver;
+use crate::sponsorblock;
+use crate::ui::app::{AppAction, SearchPhase, tracks_label};
+use crate::ui::{App, handle_key_event, handle_mouse_event, layout::render_ui, terminal::Tui};
 
 const TICK_RATE: Duration = Duration::from_millis(250);
 const SEARCH_POLL_RATE: Duration = Duration::from_millis(50);
@@ -28,15 +39,17 @@ struct SearchCacheKey {
     query: String,
     page_size: usize,
     filter_shorts: bool,
+    filters: SearchFilters,
 }
 
 impl SearchCacheKey {
-    fn new(query: &str, page_size: usize, filter_shorts: bool) -> Self {
+    fn new(query: &str, page_size: usize, filter_shorts: bool, filters: SearchFilters) -> Self {
         Self {
             // Preserve case: a search can be a case-sensitive video/channel ID.
             query: query.trim().to_string(),
             page_size: clamp_results_per_page(page_size),
             filter_shorts,
+            filters,
         }
     }
 }
@@ -61,7 +74,12 @@ impl SearchCache {
         }
 
         self.remove_expired();
-        let key = SearchCacheKey::new(state.query(), state.page_size, state.filter_shorts);
+        let key = SearchCacheKey::new(
+            state.query(),
+            state.page_size,
+            state.filter_shorts,
+            state.filters,
+        );
         let now = Instant::now();
         let fetched_at = self
             .entries
@@ -90,9 +108,10 @@ impl SearchCache {
         query: &str,
         page_size: usize,
         filter_shorts: bool,
+        filters: SearchFilters,
     ) -> Option<PaginatedSearch> {
         self.remove_expired();
-        let key = SearchCacheKey::new(query, page_size, filter_shorts);
+        let key = SearchCacheKey::new(query, page_size, filter_shorts, filters);
         let index = self.entries.iter().position(|entry| entry.key == key)?;
         let entry = self.entries.remove(index)?;
         let state = entry.state.clone();
@@ -111,6 +130,7 @@ enum SearchRequest {
     New,
     NextPage { target_page: usize },
     BackgroundPrefetch,
+    EnqueueAll,
 }
 
 enum SearchMessage {
@@ -120,10 +140,20 @@ enum SearchMessage {
         state: PaginatedSearch,
     },
     Finished(SearchOutcome),
+    VideoLookup(VideoLookup),
+}
+
+/// The result of resolving a video URL or ID typed into the search bar.
+struct VideoLookup {
+    generation: u64,
+    start: Option<f64>,
+    fallback_query: Option<String>,
+    result: Result<SearchResult>,
 }
 
 struct SearchOutcome {
     generation: u64,
+    request: SearchRequest,
     state: PaginatedSearch,
     result: Result<()>,
 }
@@ -168,8 +198,20 @@ pub fn run_app(
 ) -> Result<()> {
     let (search_tx, search_rx) = mpsc::channel();
     let mut search_runtime = SearchRuntime::default();
+    let mut session = SessionSaver::default();
     let mut last_tick = Instant::now();
     let mut dirty = true;
+    let remote = match RemoteServer::start() {
+        Ok(server) => Some(server),
+        Err(error) => {
+            app.status_message = Some(format!("Remote control unavailable: {error:#}"));
+            None
+        }
+    };
+    #[cfg(target_os = "linux")]
+    let mut mpris = start_mpris(&mut app);
+    let mut notifier = None;
+    let mut radio = Radio::default();
 
     loop {
         if app.should_quit || INTERRUPTED.load(Ordering::SeqCst) {
@@ -201,6 +243,53 @@ pub fn run_app(
             dirty = true;
         }
 
+        if let Some(remote) = &remote {
+            while let Some(request) = remote.try_recv() {
+                request.handle(|command| handle_remote_command(&mut app, command));
+                dirty = true;
+            }
+        }
+
+        #[cfg(target_os = "linux")]
+        if let Some(mpris) = &mpris {
+            while let Some(command) = mpris.try_recv() {
+                // Media keys have nowhere to show "Nothing is playing"; player
+                // failures still reach the status line.
+                let _ = handle_remote_command(&mut app, command);
+                dirty = true;
+            }
+        }
+
+        if continue_radio(&mut app, &mut radio) {
+            dirty = true;
+        }
+
+        if announce_started_track(&mut app, &mut notifier) {
+            dirty = true;
+        }
+
+        if refresh_thumbnail(&mut app) {
+            dirty = true;
+        }
+
+        if app.details.as_mut().is_some_and(DetailsView::update) {
+            dirty = true;
+        }
+
+        // Subtitles are only read while something shows them.
+        let subtitles_wanted =
+            app.config.subtitles || app.transcript.is_some() || app.language_picker.is_some();
+        let playing = app
+            .player_manager
+            .as_ref()
+            .and_then(|player| player.current_video_id.as_deref());
+        if app
+            .subtitles
+            .update(playing, &app.config.subtitle_language, subtitles_wanted)
+        {
+            dirty = true;
+        }
+
         if dirty {
             terminal.draw(|frame| render_ui(frame, &app))?;
             dirty = false;
@@ -221,7 +310,10 @@ pub fn run_app(
                     handle_key_event(&mut app, key);
                     dirty = true;
                 }
+                Event::Mouse(mouse) => dirty |= handle_mouse_event(&mut app, mouse),
                 Event::Resize(_, _) => dirty = true,
+                Event::FocusGained => app.terminal_focused = true,
+                Event::FocusLost => app.terminal_focused = false,
                 _ => {}
             }
         }
@@ -231,6 +323,32 @@ pub fn run_app(
             if poll_player(&mut app, (terminal_size.width, terminal_size.height)) {
                 dirty = true;
             }
+            #[cfg(target_os = "linux")]
+            if let Some(service) = &mut mpris
+                && let Err(error) = service.update(mpris_state(&app))
+            {
+                app.status_message = Some(format!("Media controls stopped: {error:#}"));
+                mpris = None;
+                dirty = true;
+            }
+            // Each saver records a write attempt even when it fails, so a
+            // read-only config directory does not turn every tick into another
+            // failing write. Until the user answers the restore prompt, the
+            // empty queue must not overwrite the saved session.
+            if app.restore_prompt.is_none()
+                && let Err(error) = session.save_if_changed(app.queue_snapshot())
+            {
+                app.status_message = Some(format!("Could not save queue: {error}"));
+                dirty = true;
+            }
+            if let Err(error) = app.watch_history.save_if_due() {
+                app.status_message = Some(format!("Could not save watch history: {error}"));
+                dirty = true;
+            }
+            if let Err(error) = app.resume_positions.save_if_due() {
+                app.status_message = Some(format!("Could not save resume positions: {error}"));
+                dirty = true;
+            }
             last_tick = Instant::now();
         }
     }
@@ -239,6 +357,13 @@ pub fn run_app(
         worker.cancel_and_join();
     }
 
+    if app.restore_prompt.is_none() {
+        session.flush(app.queue_snapshot())?;
+    }
+    app.watch_history.stop(false);
+    app.watch_history.flush()?;
+    app.resume_positions.flush()?;
+
     Ok(())
 }
 
@@ -259,7 +384,38 @@ fn process_pending_action(
     search_runtime: &mut SearchRuntime,
 ) -> Result<bool> {
     match std::mem::replace(&mut app.pending_action, AppAction::None) {
-        AppAction::Play(index) => play_result(terminal, app, index, temp_dir),
+        AppAction::Play(index) => play_result(terminal, app, index, None, temp_dir),
+        AppAction::PlayFrom(index, start) => {
+            play_result(terminal, app, index, Some(start), temp_dir)
+        }
+        AppAction::OpenVideo {
+            video_id,
+            start,
+            fallback_query,
+        } => {
+            search_runtime.cache.insert(search);
+            search_runtime.generation = search_runtime.generation.wrapping_add(1);
+            if let Some(worker) = search_runtime.worker.take() {
+                worker.cancel_and_join();
+            }
+            app.results.clear();
+            app.total_results = 0;
+            app.exhausted = false;
+            app.page = 0;
+            app.selected_index = 0;
+            app.loading = true;
+            app.search_phase = Some(SearchPhase::LookingUpVideo);
+            app.status_message = None;
+            spawn_video_lookup(
+                video_id,
+                start,
+                fallback_query,
+                search_runtime.generation,
+                search_tx,
+                app,
+            );
+            Ok(true)
+        }
         AppAction::NewSearch(query) => {
             search_runtime.cache.insert(search);
             app.results.clear();
@@ -271,11 +427,12 @@ fn process_pending_action(
             app.search_phase = Some(SearchPhase::Initial);
             app.status_message = None;
 
-            if let Some(cached) =
-                search_runtime
-                    .cache
-                    .get(&query, app.page_size, !app.config.include_shorts)
-            {
+            if let Some(cached) = search_runtime.cache.get(
+                &query,
+                app.page_size,
+                !app.config.include_shorts,
+                app.search_filters,
+            ) {
                 search_runtime.generation = search_runtime.generation.wrapping_add(1);
                 if let Some(worker) = search_runtime.worker.take() {
                     worker.cancel_and_join();
@@ -284,7 +441,8 @@ fn process_pending_action(
                 return Ok(true);
             }
 
-            *search = PaginatedSearch::new(&query, app.page_size, !app.config.include_shorts);
+            *search = PaginatedSearch::new(&query, app.page_size, !app.config.include_shorts)
+                .with_filters(app.search_filters);
             spawn_search(
                 search.clone(),
                 0,
@@ -328,6 +486,25 @@ fn process_pending_action(
             );
             Ok(true)
         }
+        AppAction::EnqueueAll => {
+            if search.exhausted {
+                enqueue_all_results(app, &search.results);
+                return Ok(true);
+            }
+            app.loading = true;
+            app.search_phase = Some(SearchPhase::FetchingAll);
+            app.status_message = None;
+            spawn_search(
+                search.clone(),
+                app.page,
+                SearchRequest::EnqueueAll,
+                search_tx,
+                &mut search_runtime.generation,
+                &mut search_runtime.worker,
+                app,
+            );
+            Ok(true)
+        }
         AppAction::CancelSearch => {
             search_runtime.generation = search_runtime.generation.wrapping_add(1);
             if let Some(worker) = search_runtime.worker.take() {
@@ -363,17 +540,27 @@ fn spawn_search(
     let spawn_result = thread::Builder::new()
         .name(format!("youtui-search-{current_generation}"))
         .spawn(move || {
-            let result = state
-                .ensure_page_with_cancel_and_progress(page, &worker_cancelled, |progress| {
-                    let _ = tx.send(SearchMessage::Progress {
-                        generation: current_generation,
-                        request,
-                        state: progress.clone(),
-                    });
-                })
-                .map(|_| ());
+            let on_progress = |progress: &PaginatedSearch| {
+                let _ = tx.send(SearchMessage::Progress {
+                    generation: current_generation,
+                    request,
+                    state: progress.clone(),
+                });
+            };
+            let result = match request {
+                SearchRequest::EnqueueAll => state.ensure_results_with_cancel_and_progress(
+                    usize::MAX,
+                    &worker_cancelled,
+                    on_progress,
+                ),
+                _ => {
+                    state.ensure_page_with_cancel_and_progress(page, &worker_cancelled, on_progress)
+                }
+            }
+            .map(|_| ());
             let _ = tx.send(SearchMessage::Finished(SearchOutcome {
                 generation: current_generation,
+                request,
                 state,
                 result,
             }));
@@ -394,6 +581,36 @@ fn spawn_search(
     }
 }
 
+/// Resolve one video on a detached thread. A lookup is a single short yt-dlp
+/// call, so a superseded one is left to finish and its message is ignored.
+fn spawn_video_lookup(
+    video_id: String,
+    start: Option<f64>,
+    fallback_query: Option<String>,
+    generation: u64,
+    tx: &Sender<SearchMessage>,
+    app: &mut App,
+) {
+    let tx = tx.clone();
+    let spawn_result = thread::Builder::new()
+        .name(format!("youtui-lookup-{generation}"))
+        .spawn(move || {
+            let result = crate::search::fetch_video(&video_id);
+            let _ = tx.send(SearchMessage::VideoLookup(VideoLookup {
+                generation,
+                start,
+                fallback_query,
+                result,
+            }));
+        });
+
+    if let Err(error) = spawn_result {
+        app.loading = false;
+        app.search_phase = None;
+        app.status_message = Some(format!("Could not look up video: {error}"));
+    }
+}
+
 fn drain_search_results(
     rx: &Receiver<SearchMessage>,
     active_generation: u64,
@@ -417,10 +634,21 @@ fn drain_search_results(
             Ok(SearchMessage::Finished(outcome)) => {
                 if outcome.generation == active_generation {
                     let cacheable = outcome.result.is_ok() || !outcome.state.results.is_empty();
+                    let enqueue_all =
+                        outcome.request == SearchRequest::EnqueueAll && outcome.result.is_ok();
                     apply_search_outcome(outcome, app, search);
                     if cacheable {
                         search_cache.insert(search);
                     }
+                    if enqueue_all {
+                        enqueue_all_results(app, &search.results);
+                    }
+                    changed = true;
+                }
+            }
+            Ok(SearchMessage::VideoLookup(lookup)) => {
+                if lookup.generation == active_generation {
+                    apply_video_lookup(lookup, app, search);
                     changed = true;
                 }
             }
@@ -459,13 +687,15 @@ fn apply_search_progress(
         Some(SearchPhase::Initial) => match request {
             SearchRequest::New => Some(0),
             SearchRequest::NextPage { target_page } => Some(target_page),
-            SearchRequest::BackgroundPrefetch => None,
+            SearchRequest::BackgroundPrefetch | SearchRequest::EnqueueAll => None,
         },
         // A prefetch is intentionally invisible to page selection. Keep its
         // declared target stable even once that page is complete: the worker
         // was only asked to fill this target, so advertising another page here
         // would briefly claim work that is not in flight.
-        Some(SearchPhase::Prefetch { .. }) => None,
+        Some(
+            SearchPhase::Prefetch { .. } | SearchPhase::FetchingAll | SearchPhase::LookingUpVideo,
+        ) => None,
         None => None,
     };
 
@@ -527,10 +757,47 @@ fn apply_search_outcome(outcome: SearchOutcome, app: &mut App, search: &mut Pagi
     }
 }
 
+fn apply_video_lookup(lookup: VideoLookup, app: &mut App, search: &mut PaginatedSearch) {
+    match lookup.result {
+        Ok(result) => {
+            let mut state =
+                PaginatedSearch::new(&app.query, app.page_size, !app.config.include_shorts);
+            state.results.push(result);
+            state.exhausted = true;
+            apply_cached_search(state, app, search);
+            app.pending_action = match lookup.start {
+                Some(start) => AppAction::PlayFrom(0, start),
+                None => AppAction::Play(0),
+            };
+        }
+        Err(error) => match lookup.fallback_query {
+            // A bare ID may just as well have been meant as search words.
+            Some(query) => {
+                app.search_phase = Some(SearchPhase::Initial);
+                app.pending_action = AppAction::NewSearch(query);
+            }
+            None => {
+                app.loading = false;
+                app.search_phase = None;
+                app.status_message = Some(format!("Could not open video: {error}"));
+            }
+        },
+    }
+}
+
+fn enqueue_all_results(app: &mut App, results: &[SearchResult]) {
+    let added = app.enqueue_tracks(results.iter().cloned());
+    // Starting playback reports its own failures.
+    if app.status_message.is_none() {
+        app.status_message = Some(format!("Queued all {}", tracks_label(added)));
+    }
+}
+
 fn play_result(
     terminal: &mut Tui,
     app: &mut App,
     index: usize,
+    start: Option<f64>,
     temp_dir: &ManagedTempDir,
 ) -> Result<bool> {
     let Some(result) = app.results.get(index).cloned() else {
@@ -540,8 +807,19 @@ fn play_result(
     let background_playback =
         crate::player::supports_background_playback(app.config.player) && !app.config.download_mode;
     if background_playback {
+        let plays_now = app.queue.is_empty();
+        let video_id = result.id.clone();
         app.queue.push_back(result);
         start_queue_if_idle(app);
+        // A start time only applies when this track begins playing now, not
+        // when it waits behind others in the queue.
+        if let Some(start) = start
+            && plays_now
+            && let Some(player) = app.player_manager.as_mut()
+            && player.current_video_id.as_deref() == Some(video_id.as_str())
+        {
+            player.seek_when_loaded(start);
+        }
         return Ok(true);
     }
 
@@ -565,8 +843,12 @@ fn play_result(
     );
     *terminal = crate::ui::terminal::init_terminal()?;
 
-    if let Err(error) = playback_result {
-        app.status_message = Some(format!("Playback failed: {error}"));
+    match playback_result {
+        Err(error) => app.status_message = Some(format!("Playback failed: {error}")),
+        Ok(PlaybackResult::Error(message)) => {
+            app.status_message = Some(format!("Playback failed: {message}"));
+        }
+        Ok(_) => {}
     }
     Ok(true)
 }
@@ -580,7 +862,7 @@ fn start_queue_if_idle(app: &mut App) {
         return;
     }
 
-    let Some(track) = app.queue.get(0) else {
+    let Some(track) = app.queue.get(0).cloned() else {
         return;
     };
     let url = format!("https://www.youtube.com/watch?v={}", track.id);
@@ -603,7 +885,10 @@ fn start_queue_if_idle(app: &mut App) {
     };
 
     match result {
-        Ok(()) => app.status_message = None,
+        Ok(()) => {
+            app.status_message = None;
+            app.started_track = Some(track);
+        }
         Err(error) => {
             app.player_manager = None;
             app.status_message = Some(format!("Could not start playback: {error}"));
@@ -611,13 +896,266 @@ fn start_queue_if_idle(app: &mut App) {
     }
 }
 
+/// Carry out a command from the remote-control socket and return the data
+/// to answer with.
+fn handle_remote_command(app: &mut App, command: RemoteCommand) -> Result<Value> {
+    match command {
+        RemoteCommand::Play if !player_is_loaded(app) && !app.queue.is_empty() => {
+            app.load_queue_front(true);
+            Ok(Value::Null)
+        }
+        RemoteCommand::TogglePause | RemoteCommand::Pause | RemoteCommand::Play => {
+            remote_player_command(app, |player| {
+                let paused = match command {
+                    RemoteCommand::Pause => true,
+                    RemoteCommand::Play => false,
+                    _ => !player.status.paused,
+                };
+                if paused == player.status.paused {
+                    Ok(())
+                } else {
+                    player.toggle_pause()
+                }
+            })
+        }
+        RemoteCommand::Next => {
+            if app.queue.is_empty() {
+                bail!("The queue is empty");
+            }
+            app.handle_next_video(true);
+            Ok(Value::Null)
+        }
+        RemoteCommand::Previous => {
+            if !player_is_loaded(app) && !app.queue.has_previous() {
+                bail!("There is no previous track");
+            }
+            app.handle_previous_video();
+            Ok(Value::Null)
+        }
+        RemoteCommand::Seek { seconds, absolute } => remote_player_command(app, |player| {
+            if absolute {
+                player.seek_absolute(seconds)
+            } else {
+                player.seek(seconds)
+            }
+        }),
+        RemoteCommand::Volume { level, relative } => remote_player_command(app, |player| {
+            let volume = if relative {
+                player.status.volume + level
+            } else {
+                level
+            };
+            player.set_volume(volume.clamp(0, 100))
+        }),
+        RemoteCommand::Enqueue(tracks) => {
+            app.status_message = None;
+            let added = app.enqueue_tracks(tracks);
+            // Starting an idle player reports its own failures.
+            if let Some(error) = app.status_message.clone() {
+                bail!("{error}");
+            }
+            app.status_message = Some(format!(
+                "Queued {} from remote control",
+                tracks_label(added)
+            ));
+            Ok(json!({ "queued": added }))
+        }
+        RemoteCommand::GetStatus => {
+            let player = app
+                .player_manager
+                .as_ref()
+                .filter(|player| player.current_video_id.is_some());
+            Ok(json!({
+                "playing": player.is_some(),
+                "video_id": player.and_then(|player| player.current_video_id.clone()),
+                "title": player.map(|player| player.status.title.clone()),
+                "paused": player.is_some_and(|player| player.status.paused),
+                "time_pos": player.map(|player| player.status.time_pos),
+                "duration": player.map(|player| player.status.duration),
+                "volume": player.map(|player| player.status.volume),
+                "queue_length": app.queue.len(),
+            }))
+        }
+        RemoteCommand::GetQueue => {
+            let tracks: Vec<&SearchResult> = app.queue.iter().collect();
+            Ok(serde_json::to_value(tracks)?)
+        }
+    }
+}
+
+/// Keep the preview pane's thumbnail on the selected result, fetching it in
+/// the background. Returns whether the preview changed.
+fn refresh_thumbnail(app: &mut App) -> bool {
+    let area = app.screen_areas.borrow().thumbnail;
+    let video_id = area
+        .and_then(|_| app.current_page_results().get(app.selected_index))
+        .map(|result| result.id.clone());
+    let size = area.map_or(Size::ZERO, Rect::as_size);
+    let picker = app.video.picker(app.config.video_render);
+    app.thumbnails.update(video_id.as_deref(), size, picker)
+}
+
+/// Keep radio tracks queued behind the playing one. Radio follows the last
+/// queued track, and the last played one when the queue is empty.
+fn continue_radio(app: &mut App, radio: &mut Radio) -> bool {
+    if let Some(video_id) = app
+        .player_manager
+        .as_ref()
+        .and_then(|player| player.current_video_id.as_deref())
+    {
+        radio.record_played(video_id);
+    }
+    let background_playback =
+        crate::player::supports_background_playback(app.config.player) && !app.config.download_mode;
+    if !app.config.radio || !background_playback {
+        return false;
+    }
+    let upcoming = app
+        .queue
+        .len()
+        .saturating_sub(app.first_movable_queue_index());
+    let Some(seed) = app
+        .queue
+        .iter()
+        .last()
+        .map(|track| track.id.as_str())
+        .or(radio.last_played())
+        .map(str::to_string)
+    else {
+        return false;
+    };
+    if upcoming >= radio::UPCOMING {
+        return false;
+    }
+
+    let queued: HashSet<&str> = app.queue.iter().map(|track| track.id.as_str()).collect();
+    match radio.next_tracks(&seed, radio::UPCOMING - upcoming, &queued) {
+        Ok(tracks) if tracks.is_empty() => false,
+        Ok(tracks) => {
+            app.enqueue_tracks(tracks);
+            true
+        }
+        Err(error) => {
+            app.status_message = Some(format!("Radio stopped: {error:#}"));
+            true
+        }
+    }
+}
+
+/// Pass a newly started queue track to the desktop when notifications are
+/// on and the terminal is in the background, starting the notifier on first
+/// use. Returns whether the status line changed.
+fn announce_started_track(app: &mut App, notifier: &mut Option<Notifier>) -> bool {
+    let mut changed = false;
+    if let Some(track) = app.started_track.take()
+        && app.config.notify
+        && !app.terminal_focused
+    {
+        if notifier.is_none() {
+            match Notifier::start(&app.config.notify_command) {
+                Ok(started) => *notifier = Some(started),
+                Err(error) => {
+                    // Turn them off rather than failing again on every track.
+                    app.config.notify = false;
+                    app.status_message = Some(format!("Notifications unavailable: {error:#}"));
+                    changed = true;
+                }
+            }
+        }
+        if let Some(notifier) = notifier.as_ref() {
+            notifier.track_started(&track);
+        }
+    }
+    if let Some(error) = notifier.as_ref().and_then(Notifier::try_recv_error) {
+        app.status_message = Some(format!("Notification failed: {error}"));
+        changed = true;
+    }
+    changed
+}
+
+#[cfg(target_os = "linux")]
+fn start_mpris(app: &mut App) -> Option<MprisService> {
+    if !app.config.mpris {
+        return None;
+    }
+    match MprisService::start() {
+        Ok(service) => service,
+        Err(error) => {
+            app.status_message = Some(format!("Media controls unavailable: {error:#}"));
+            None
+        }
+    }
+}
+
+/// What the MPRIS service shows, taken from the same player state as
+/// `get-status`.
+#[cfg(target_os = "linux")]
+fn mpris_state(app: &App) -> PlayerState {
+    let player = app
+        .player_manager
+        .as_ref()
+        .filter(|player| player.current_video_id.is_some());
+    PlayerState {
+        track: player.and_then(|player| {
+            let video_id = player.current_video_id.clone()?;
+            let queued = app.queue.get(0).filter(|track| track.id == video_id);
+            Some(Track {
+                title: queued
+                    .map_or_else(|| player.status.title.clone(), |track| track.title.clone()),
+                channel: queued
+                    .map(|track| track.channel.clone())
+                    .unwrap_or_default(),
+                length: player.status.duration,
+                video_id,
+            })
+        }),
+        position: player.map_or(0.0, |player| player.status.time_pos),
+        paused: player.is_some_and(|player| player.status.paused),
+        volume: player.map_or(0, |player| player.status.volume),
+        queue_length: app.queue.len(),
+        has_previous: app.queue.has_previous(),
+    }
+}
+
+fn player_is_loaded(app: &App) -> bool {
+    app.player_manager
+        .as_ref()
+        .is_some_and(|player| player.current_video_id.is_some())
+}
+
+/// Like a playback key: a failing player is dropped and the failure shown.
+fn remote_player_command(
+    app: &mut App,
+    command: impl FnOnce(&mut PlayerManager) -> Result<()>,
+) -> Result<Value> {
+    let Some(player) = app
+        .player_manager
+        .as_mut()
+        .filter(|player| player.current_video_id.is_some())
+    else {
+        bail!("Nothing is playing");
+    };
+    if let Err(error) = command(player) {
+        app.player_manager = None;
+        app.status_message = Some(format!("Playback stopped: {error}"));
+        return Err(error);
+    }
+    Ok(Value::Null)
+}
+
 fn poll_player(app: &mut App, terminal_size: (u16, u16)) -> bool {
     let Some(player) = app.player_manager.as_mut() else {
+        // Close the history entry of a track stopped by clearing the queue.
+        app.record_watch_progress(false);
+        app.record_resume_position(false);
         return false;
     };
 
     let update_error = player.update_status().err();
+    let skipped = player.take_skipped();
     let finished = player.is_eof();
+    app.record_watch_progress(finished);
+    app.record_resume_position(finished);
 
     if let Some(error) = update_error {
         // Consume EOF before dropping the broken manager so the current queue
@@ -625,6 +1163,12 @@ fn poll_player(app: &mut App, terminal_size: (u16, u16)) -> bool {
         // so Enter can retry it.
         app.player_manager = None;
         app.status_message = Some(format!("Playback connection lost: {error}"));
+    } else if let Some(segment) = skipped {
+        app.status_message = Some(format!(
+            "Skipped {} ({:.0}s)",
+            sponsorblock::category_label(&segment.category),
+            segment.end - segment.start
+        ));
     }
 
     if finished {
@@ -664,7 +1208,8 @@ fn sync_video(app: &mut App, (width, height): (u16, u16)) {
         None => (None, false, false, 0.0),
     };
     let has_player = app.player_manager.is_some();
-    let (cols, rows) = crate::ui::layout::video_pane_size(has_player, width, height);
+    let (cols, rows) =
+        crate::ui::layout::video_pane_size(has_player, app.config.subtitles, width, height);
     app.video.sync(
         playing,
         paused,
@@ -716,7 +1261,8 @@ mod tests {
 
     use super::*;
     use crate::config::Config;
-    use crate::search::SearchResult;
+    use crate::search::SortOrder;
+    use crate::worker::wait_until;
 
     fn result(id: &str) -> SearchResult {
         SearchResult {
@@ -741,6 +1287,7 @@ mod tests {
         apply_search_outcome(
             SearchOutcome {
                 generation: 1,
+                request: SearchRequest::New,
                 state: completed,
                 result: Ok(()),
             },
@@ -768,6 +1315,7 @@ mod tests {
         apply_search_outcome(
             SearchOutcome {
                 generation: 1,
+                request: SearchRequest::New,
                 state: search.clone(),
                 result: Err(anyhow!("network unavailable")),
             },
@@ -891,6 +1439,7 @@ mod tests {
         apply_search_outcome(
             SearchOutcome {
                 generation: 1,
+                request: SearchRequest::New,
                 state: completed,
                 result: Ok(()),
             },
@@ -915,6 +1464,7 @@ mod tests {
         apply_search_outcome(
             SearchOutcome {
                 generation: 1,
+                request: SearchRequest::New,
                 state: completed,
                 result: Ok(()),
             },
@@ -941,6 +1491,7 @@ mod tests {
         apply_search_outcome(
             SearchOutcome {
                 generation: 1,
+                request: SearchRequest::New,
                 state: completed,
                 result: Ok(()),
             },
@@ -954,6 +1505,165 @@ mod tests {
         assert!(app.search_phase.is_none());
     }
 
+    #[test]
+    fn started_tracks_are_announced_only_in_the_background_with_notifications_on() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let log = temp_dir.path().join("notified");
+        let mut app = App::new(String::new(), 10, Config::default());
+        app.config.notify_command = format!(r#"echo "$YOUTUI_VIDEO_ID" >> '{}'"#, log.display());
+        let mut notifier = None;
+
+        app.started_track = Some(result("off"));
+        app.terminal_focused = false;
+        assert!(!announce_started_track(&mut app, &mut notifier));
+        app.config.notify = true;
+        app.terminal_focused = true;
+        app.started_track = Some(result("focused"));
+        assert!(!announce_started_track(&mut app, &mut notifier));
+        assert!(app.started_track.is_none());
+        assert!(notifier.is_none());
+
+        app.terminal_focused = false;
+        app.started_track = Some(result("background"));
+        announce_started_track(&mut app, &mut notifier);
+        assert!(notifier.is_some());
+        let mut notified = String::new();
+        wait_until("the hook never ran", || {
+            notified = std::fs::read_to_string(&log).unwrap_or_default();
+            notified.ends_with('\n')
+        });
+        assert_eq!(notified, "background\n");
+    }
+
+    #[test]
+    fn remote_commands_report_state_and_enqueue_behind_the_playing_track() {
+        let mut app = App::new(String::new(), 10, Config::default());
+        let status = handle_remote_command(&mut app, RemoteCommand::GetStatus).unwrap();
+        assert_eq!(status["playing"], false);
+        assert_eq!(status["queue_length"], 0);
+        let error = handle_remote_command(
+            &mut app,
+            RemoteCommand::Seek {
+                seconds: 10.0,
+                absolute: false,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(error.to_string(), "Nothing is playing");
+        assert!(handle_remote_command(&mut app, RemoteCommand::Next).is_err());
+
+        let (client_stream, _server_stream) = std::os::unix::net::UnixStream::pair().unwrap();
+        app.player_manager = Some(PlayerManager::from_test_stream(client_stream));
+        app.queue.push_back(result("video-id"));
+        let queued = handle_remote_command(
+            &mut app,
+            RemoteCommand::Enqueue(vec![result("a"), result("b")]),
+        )
+        .unwrap();
+        assert_eq!(queued, json!({ "queued": 2 }));
+
+        let queue = handle_remote_command(&mut app, RemoteCommand::GetQueue).unwrap();
+        let ids: Vec<&str> = queue
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|track| track["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, ["video-id", "a", "b"]);
+        let status = handle_remote_command(&mut app, RemoteCommand::GetStatus).unwrap();
+        assert_eq!(status["playing"], true);
+        assert_eq!(status["video_id"], "video-id");
+        assert_eq!(status["queue_length"], 3);
+    }
+
+    #[test]
+    fn finished_enqueue_all_queues_every_fetched_entry_behind_the_playing_track() {
+        let (client_stream, _server_stream) = std::os::unix::net::UnixStream::pair().unwrap();
+        let query = "https://www.youtube.com/playlist?list=PLabc";
+        let mut app = App::new(query.to_string(), 10, Config::default());
+        app.player_manager = Some(PlayerManager::from_test_stream(client_stream));
+        app.queue.push_back(result("playing"));
+        app.loading = true;
+        app.search_phase = Some(SearchPhase::FetchingAll);
+        let mut search = PaginatedSearch::new(query, 10, false);
+        let mut completed = search.clone();
+        completed.results = (0..25).map(|index| result(&index.to_string())).collect();
+        completed.exhausted = true;
+        let (tx, rx) = mpsc::channel();
+        tx.send(SearchMessage::Finished(SearchOutcome {
+            generation: 7,
+            request: SearchRequest::EnqueueAll,
+            state: completed,
+            result: Ok(()),
+        }))
+        .unwrap();
+
+        drain_search_results(&rx, 7, &mut app, &mut search, &mut SearchCache::default()).unwrap();
+
+        assert!(!app.loading);
+        assert_eq!(app.results.len(), 25);
+        assert_eq!(app.queue.len(), 26);
+        assert_eq!(app.queue.get(1).unwrap().id, "0");
+        assert_eq!(app.status_message.as_deref(), Some("Queued all 25 tracks"));
+    }
+
+    #[test]
+    fn video_lookup_shows_the_single_video_and_plays_it_from_the_timestamp() {
+        let mut app = App::new(
+            "https://youtu.be/abc?t=90".to_string(),
+            10,
+            Config::default(),
+        );
+        app.loading = true;
+        app.search_phase = Some(SearchPhase::LookingUpVideo);
+        let mut search = PaginatedSearch::new("old", 10, false);
+
+        apply_video_lookup(
+            VideoLookup {
+                generation: 1,
+                start: Some(90.0),
+                fallback_query: None,
+                result: Ok(result("abc")),
+            },
+            &mut app,
+            &mut search,
+        );
+
+        assert_eq!(app.results, vec![result("abc")]);
+        assert!(app.exhausted && search.exhausted);
+        assert!(!app.loading);
+        assert_eq!(app.pending_action, AppAction::PlayFrom(0, 90.0));
+    }
+
+    #[test]
+    fn failed_video_lookup_searches_for_a_bare_id_and_reports_a_url() {
+        let mut app = App::new("abcdefghijk".to_string(), 10, Config::default());
+        app.loading = true;
+        let mut search = PaginatedSearch::new("old", 10, false);
+        let lookup = |fallback_query: Option<&str>| VideoLookup {
+            generation: 1,
+            start: None,
+            fallback_query: fallback_query.map(str::to_string),
+            result: Err(anyhow::anyhow!("Video unavailable")),
+        };
+
+        apply_video_lookup(lookup(Some("abcdefghijk")), &mut app, &mut search);
+        assert!(app.loading);
+        assert_eq!(
+            app.pending_action,
+            AppAction::NewSearch("abcdefghijk".to_string())
+        );
+
+        app.pending_action = AppAction::None;
+        apply_video_lookup(lookup(None), &mut app, &mut search);
+        assert!(!app.loading);
+        assert_eq!(app.pending_action, AppAction::None);
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Could not open video: Video unavailable")
+        );
+    }
+
     #[test]
     fn active_search_caps_event_polling_at_fifty_milliseconds() {
         assert_eq!(
@@ -977,12 +1687,39 @@ mod tests {
         state.results = vec![result("1"), result("2")];
         cache.insert(&state);
 
-        let cached = cache.get("Jubal SHOW", 2, true).unwrap();
+        let cached = cache
+            .get("Jubal SHOW", 2, true, SearchFilters::default())
+            .unwrap();
         assert_eq!(cached.results.len(), 2);
         assert_eq!(cached.query(), "  Jubal SHOW ");
-        assert!(cache.get("jubal show", 2, true).is_none());
-        assert!(cache.get("Jubal SHOW", 2, false).is_none());
-        assert!(cache.get("Jubal SHOW", 3, true).is_none());
+        assert!(
+            cache
+                .get("jubal show", 2, true, SearchFilters::default())
+                .is_none()
+        );
+        assert!(
+            cache
+                .get("Jubal SHOW", 2, false, SearchFilters::default())
+                .is_none()
+        );
+        assert!(
+            cache
+                .get("Jubal SHOW", 3, true, SearchFilters::default())
+                .is_none()
+        );
+
+        let newest = SearchFilters {
+            sort: SortOrder::Date,
+            ..SearchFilters::default()
+        };
+        assert!(cache.get("Jubal SHOW", 2, true, newest).is_none());
+        cache.insert(&state.clone().with_filters(newest));
+        assert!(cache.get("Jubal SHOW", 2, true, newest).is_some());
+        assert!(
+            cache
+                .get("Jubal SHOW", 2, true, SearchFilters::default())
+                .is_some()
+        );
     }
 
     #[test]
@@ -1001,8 +1738,14 @@ mod tests {
         }
 
         assert_eq!(cache.entries.len(), SEARCH_CACHE_CAPACITY);
-        assert!(cache.get("query 0", 1, false).is_none());
-        let cached = cache.get("query 8", 1, false).unwrap();
+        assert!(
+            cache
+                .get("query 0", 1, false, SearchFilters::default())
+                .is_none()
+        );
+        let cached = cache
+            .get("query 8", 1, false, SearchFilters::default())
+            .unwrap();
 
         let original_fetch = Instant::now() - Duration::from_secs(60);
         cache.entries.front_mut().unwrap().fetched_at = original_fetch;
@@ -1011,7 +1754,11 @@ mod tests {
 
         cache.entries.front_mut().unwrap().fetched_at =
             Instant::now() - SEARCH_CACHE_TTL - Duration::from_secs(1);
-        assert!(cache.get("query 8", 1, false).is_none());
+        assert!(
+            cache
+                .get("query 8", 1, false, SearchFilters::default())
+                .is_none()
+        );
     }
 
     #[test]
@@ -1056,4 +1803,54 @@ mod tests {
             AppAction::NewSearch("query".to_string())
         );
     }
+
+    #[test]
+    fn radio_tops_up_the_queue_behind_the_playing_track() {
+        fn mix(seed: &str, _: usize, _: &AtomicBool) -> Result<Vec<SearchResult>> {
+            Ok([seed, "played", "r1", "r2", "r3", "r4"]
+                .map(result)
+                .to_vec())
+        }
+
+        let (client_stream, _server_stream) = std::os::unix::net::UnixStream::pair().unwrap();
+        let mut app = App::new("query".to_string(), 10, Config::default());
+        app.player_manager = Some(PlayerManager::from_test_stream(client_stream));
+        app.queue.push_back(result("video-id"));
+        let mut radio = Radio::with_fetcher(mix);
+        radio.record_played("played");
+
+        assert!(!continue_radio(&mut app, &mut radio));
+        assert_eq!(app.queue.len(), 1);
+
+        app.config.radio = true;
+        wait_until("radio queued nothing", || {
+            continue_radio(&mut app, &mut radio)
+        });
+
+        let ids: Vec<&str> = app.queue.iter().map(|track| track.id.as_str()).collect();
+        assert_eq!(ids, ["video-id", "r1", "r2", "r3"]);
+        assert!(!continue_radio(&mut app, &mut radio));
+    }
+
+    #[test]
+    fn radio_follows_the_last_played_track_once_the_queue_runs_out() {
+        fn mix(seed: &str, _: usize, _: &AtomicBool) -> Result<Vec<SearchResult>> {
+            Ok([seed, "r1", "r2", "r3"].map(result).to_vec())
+        }
+
+        let (client_stream, _server_stream) = std::os::unix::net::UnixStream::pair().unwrap();
+        let mut app = App::new("query".to_string(), 10, Config::default());
+        app.player_manager = Some(PlayerManager::from_test_stream(client_stream));
+        app.config.radio = true;
+        let mut radio = Radio::with_fetcher(mix);
+        assert!(!continue_radio(&mut app, &mut radio));
+
+        radio.record_played("finished");
+        wait_until("radio queued nothing", || {
+            continue_radio(&mut app, &mut radio)
+        });
+
+        let ids: Vec<&str> = app.queue.iter().map(|track| track.id.as_str()).collect();
+        assert_eq!(ids, ["r1", "r2", "r3"]);
+    }
 }
@@ -2,6 +2,7 @@ use std::io;
 
 use anyhow::Result;
 use crossterm::{
+    event::{DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture},
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
@@ -12,10 +13,21 @@ pub type Tui = Terminal<CrosstermBackend<io::Stdout>>;
 pub fn init_terminal() -> Result<Tui> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    if let Err(error) = execute!(stdout, EnterAlternateScreen) {
+    // Focus reports tell the runner when youtui is in the background.
+    if let Err(error) = execute!(
+        stdout,
+        EnterAlternateScreen,
+        EnableFocusChange,
+        EnableMouseCapture
+    ) {
         // EnterAlternateScreen may have written part of its escape sequence
         // before returning an error, so make both rollback attempts.
-        let _ = execute!(io::stdout(), LeaveAlternateScreen);
+        let _ = execute!(
+            io::stdout(),
+            DisableMouseCapture,
+            DisableFocusChange,
+            LeaveAlternateScreen
+        );
         let _ = disable_raw_mode();
         return Err(error.into());
     }
@@ -25,7 +37,12 @@ pub fn init_terminal() -> Result<Tui> {
         Ok(terminal) => Ok(terminal),
         Err(error) => {
             // No TerminalGuard exists yet on this path.
-            let _ = execute!(io::stdout(), LeaveAlternateScreen);
+            let _ = execute!(
+                io::stdout(),
+                DisableMouseCapture,
+                DisableFocusChange,
+                LeaveAlternateScreen
+            );
             let _ = disable_raw_mode();
             Err(error.into())
         }
@@ -37,7 +54,12 @@ pub fn restore_terminal(terminal: &mut Tui) -> Result<()> {
     // failed raw-mode call must not prevent us from restoring the screen and
     // cursor (and vice versa).
     let cursor_result = terminal.show_cursor();
-    let screen_result = execute!(terminal.backend_mut(), LeaveAlternateScreen);
+    let screen_result = execute!(
+        terminal.backend_mut(),
+        DisableMouseCapture,
+        DisableFocusChange,
+        LeaveAlternateScreen
+    );
     let raw_mode_result = disable_raw_mode();
 
     cursor_result?;
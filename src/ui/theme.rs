@@ -0,0 +1,267 @@
+//! Color themes. Every color the UI draws comes from a named role here, so a
+//! theme only has to pick colors that work on its background.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{Context, Result, anyhow, bail};
+use ratatui::style::{Color, Modifier, Style};
+
+use crate::config::config_dir;
+
+/// Themes that need no file, in the order the settings panel cycles them.
+pub const BUILT_IN: [&str; 4] = ["dark", "light", "high-contrast", "no-color"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// Titles and other primary text.
+    pub text: Color,
+    /// Supporting details such as view counts and upload dates.
+    pub secondary: Color,
+    /// Hints, separators and unfocused borders.
+    pub muted: Color,
+    /// Focused borders and titles, channels and help keys.
+    pub accent: Color,
+    /// Footer keys, typed input and section headings.
+    pub highlight: Color,
+    /// The playing track, durations, watched progress and ticked boxes.
+    pub positive: Color,
+    /// The played part of the progress bar.
+    pub progress: Color,
+    pub error: Color,
+    /// Marked queue tracks.
+    pub marked: Color,
+    pub selection_fg: Color,
+    pub selection_bg: Color,
+    /// Behind popups and the player bar.
+    pub background: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::DARK
+    }
+}
+
+impl Theme {
+    pub const DARK: Self = Self {
+        text: Color::White,
+        secondary: Color::Gray,
+        muted: Color::DarkGray,
+        accent: Color::Cyan,
+        highlight: Color::Yellow,
+        positive: Color::Green,
+        progress: Color::Cyan,
+        error: Color::Red,
+        marked: Color::Magenta,
+        selection_fg: Color::White,
+        selection_bg: Color::Blue,
+        background: Color::Black,
+    };
+
+    pub const LIGHT: Self = Self {
+        text: Color::Black,
+        secondary: Color::Indexed(240),
+        muted: Color::Indexed(245),
+        accent: Color::Blue,
+        highlight: Color::Indexed(130),
+        positive: Color::Indexed(28),
+        progress: Color::Blue,
+        error: Color::Red,
+        marked: Color::Magenta,
+        selection_fg: Color::White,
+        selection_bg: Color::Blue,
+        background: Color::White,
+    };
+
+    pub const HIGH_CONTRAST: Self = Self {
+        text: Color::White,
+        secondary: Color::White,
+        muted: Color::Gray,
+        accent: Color::LightCyan,
+        highlight: Color::LightYellow,
+        positive: Color::LightGreen,
+        progress: Color::LightCyan,
+        error: Color::LightRed,
+        marked: Color::LightMagenta,
+        selection_fg: Color::Black,
+        selection_bg: Color::LightYellow,
+        background: Color::Black,
+    };
+
+    /// The terminal's own colors everywhere, for `NO_COLOR`. The selection
+    /// is shown in reverse video instead.
+    pub const NO_COLOR: Self = Self {
+        text: Color::Reset,
+        secondary: Color::Reset,
+        muted: Color::Reset,
+        accent: Color::Reset,
+        highlight: Color::Reset,
+        positive: Color::Reset,
+        progress: Color::Reset,
+        error: Color::Reset,
+        marked: Color::Reset,
+        selection_fg: Color::Reset,
+        selection_bg: Color::Reset,
+        background: Color::Reset,
+    };
+
+    pub fn built_in(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::DARK),
+            "light" => Some(Self::LIGHT),
+            "high-contrast" => Some(Self::HIGH_CONTRAST),
+            "no-color" => Some(Self::NO_COLOR),
+            _ => None,
+        }
+    }
+
+    /// The theme called `name`: a built-in one, or `themes/<name>.toml` in
+    /// the config directory. A non-empty `NO_COLOR` overrides either.
+    pub fn load(name: &str) -> Result<Self> {
+        if std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+            return Ok(Self::NO_COLOR);
+        }
+        match Self::built_in(name) {
+            Some(theme) => Ok(theme),
+            None => Self::load_from_dir(&config_dir()?.join("themes"), name),
+        }
+    }
+
+    fn load_from_dir(dir: &Path, name: &str) -> Result<Self> {
+        if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+            bail!("\"{name}\" is not a theme name");
+        }
+        let path = dir.join(format!("{name}.toml"));
+        let contents = fs::read_to_string(&path)
+            .with_context(|| format!("No theme \"{name}\" at {}", path.display()))?;
+        Self::from_toml(&contents).with_context(|| format!("Invalid theme {}", path.display()))
+    }
+
+    /// A theme file names the theme it starts from as `base` (dark unless
+    /// given) and overrides any roles, e.g. `accent = "#268bd2"`. Colors are
+    /// names like `lightblue`, `#rrggbb` hex or 256-color indexes.
+    fn from_toml(contents: &str) -> Result<Self> {
+        let mut entries: BTreeMap<String, String> = toml::from_str(contents)?;
+        let mut theme = match entries.remove("base") {
+            Some(base) => Self::built_in(&base)
+                .ok_or_else(|| anyhow!("base: \"{base}\" is not a built-in theme"))?,
+            None => Self::DARK,
+        };
+        for (role, value) in entries {
+            let slot = theme
+                .role_mut(&role)
+                .ok_or_else(|| anyhow!("unknown color role \"{role}\""))?;
+            *slot = Color::from_str(&value)
+                .map_err(|_| anyhow!("{role}: \"{value}\" is not a color"))?;
+        }
+        Ok(theme)
+    }
+
+    fn role_mut(&mut self, role: &str) -> Option<&mut Color> {
+        Some(match role {
+            "text" => &mut self.text,
+            "secondary" => &mut self.secondary,
+            "muted" => &mut self.muted,
+            "accent" => &mut self.accent,
+            "highlight" => &mut self.highlight,
+            "positive" => &mut self.positive,
+            "progress" => &mut self.progress,
+            "error" => &mut self.error,
+            "marked" => &mut self.marked,
+            "selection_fg" => &mut self.selection_fg,
+            "selection_bg" => &mut self.selection_bg,
+            "background" => &mut self.background,
+            _ => return None,
+        })
+    }
+
+    /// The selected row of a list. Without a selection color it falls back
+    /// to reverse video so the row still stands out.
+    pub fn selection(&self) -> Style {
+        let style = Style::default().fg(self.selection_fg).bg(self.selection_bg);
+        if self.selection_bg == Color::Reset {
+            style.add_modifier(Modifier::REVERSED)
+        } else {
+            style
+        }
+    }
+}
+
+/// The built-in theme after `name` in `BUILT_IN`; custom themes go back to
+/// the first.
+pub fn next_built_in(name: &str) -> &'static str {
+    let next = BUILT_IN
+        .iter()
+        .position(|&built_in| built_in == name)
+        .map_or(0, |index| index + 1);
+    BUILT_IN[next % BUILT_IN.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn theme_files_override_roles_of_their_base() {
+        let theme = Theme::from_toml(
+            "base = \"light\"\naccent = \"#268bd2\"\nmuted = \"244\"\nerror = \"light red\"\n",
+        )
+        .unwrap();
+        assert_eq!(theme.accent, Color::Rgb(0x26, 0x8b, 0xd2));
+        assert_eq!(theme.muted, Color::Indexed(244));
+        assert_eq!(theme.error, Color::LightRed);
+        assert_eq!(theme.text, Theme::LIGHT.text);
+
+        let theme = Theme::from_toml("highlight = \"magenta\"").unwrap();
+        assert_eq!(theme.background, Theme::DARK.background);
+    }
+
+    #[test]
+    fn theme_file_mistakes_are_reported() {
+        for (contents, message) in [
+            (
+                "base = \"sepia\"",
+                "base: \"sepia\" is not a built-in theme",
+            ),
+            ("acent = \"blue\"", "unknown color role \"acent\""),
+            ("accent = \"bluish\"", "accent: \"bluish\" is not a color"),
+        ] {
+            assert_eq!(Theme::from_toml(contents).unwrap_err().to_string(), message);
+        }
+    }
+
+    #[test]
+    fn themes_load_from_the_themes_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("paper.toml"), "base = \"light\"\n").unwrap();
+
+        assert_eq!(
+            Theme::load_from_dir(dir.path(), "paper").unwrap(),
+            Theme::LIGHT
+        );
+        assert!(Theme::load_from_dir(dir.path(), "missing").is_err());
+        assert!(Theme::load_from_dir(dir.path(), "../paper").is_err());
+    }
+
+    #[test]
+    fn without_colors_the_selection_is_reversed() {
+        let selection = Theme::NO_COLOR.selection();
+        assert!(selection.add_modifier.contains(Modifier::REVERSED));
+        assert!(
+            !Theme::DARK
+                .selection()
+                .add_modifier
+                .contains(Modifier::REVERSED)
+        );
+    }
+
+    #[test]
+    fn the_settings_panel_cycles_through_the_built_in_themes() {
+        assert_eq!(next_built_in("dark"), "light");
+        assert_eq!(next_built_in("no-color"), "dark");
+        assert_eq!(next_built_in("solarized"), "dark");
+    }
+}
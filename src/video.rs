@@ -387,6 +387,12 @@ impl VideoState {
         self.picker = Some(picker);
     }
 
+    /// The detected graphics support, if the pixel renderer may use it in
+    /// `mode`.
+    pub fn picker(&self, mode: VideoRenderMode) -> Option<&Picker> {
+        self.picker.as_ref().filter(|_| self.pixels_active(mode))
+    }
+
     /// Whether the pixel renderer is in effect for the given mode: `Blocks`
     /// never, `Pixels`/`Auto` whenever the terminal supports it.
     pub fn pixels_active(&self, mode: VideoRenderMode) -> bool {
@@ -0,0 +1,307 @@
+//! Tracks that have been played, newest first, with how far each one got.
+//! Persisted beside `config.toml`.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{config_dir, write_atomically};
+use crate::search::SearchResult;
+
+/// Older plays are dropped beyond this many.
+const CAPACITY: usize = 1000;
+/// Progress of the playing track is written at most this often; starts and
+/// finishes are written on the next tick.
+const PROGRESS_SAVE_INTERVAL: Duration = Duration::from_secs(15);
+/// A track counts as watched once this much of it has played, so skipping
+/// the last seconds of credits still marks it.
+const WATCHED_FRACTION: f64 = 0.9;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatchEntry {
+    pub track: SearchResult,
+    /// Unix time, in seconds, when playback started.
+    pub started_at: u64,
+    /// Furthest position reached, in seconds.
+    #[serde(default)]
+    pub time_pos: f64,
+    #[serde(default)]
+    pub duration: f64,
+    /// Whether playback reached the end of the track.
+    #[serde(default)]
+    pub finished: bool,
+}
+
+impl WatchEntry {
+    /// How much of the track was played, from 0 to 1.
+    pub fn progress(&self) -> f64 {
+        if self.finished {
+            1.0
+        } else if self.duration > 0.0 {
+            (self.time_pos / self.duration).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// How a search result relates to the history, for marking it in the list.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WatchMark {
+    Watched,
+    /// Started but left at this fraction.
+    Partial(f64),
+}
+
+#[derive(Debug, Default)]
+pub struct WatchHistory {
+    entries: Vec<WatchEntry>,
+    /// Whether the newest entry is the track currently loaded in the player.
+    active: bool,
+    dirty: bool,
+    saved_at: Option<Instant>,
+}
+
+impl WatchHistory {
+    /// Load the saved history; a missing file is an empty history.
+    pub fn load() -> Result<Self> {
+        Self::load_from_path(&Self::path()?)
+    }
+
+    fn load_from_path(path: &Path) -> Result<Self> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(error) => return Err(error).context("Failed to read watch history"),
+        };
+        let mut entries: Vec<WatchEntry> =
+            serde_json::from_str(&contents).context("Failed to parse watch history")?;
+        entries.truncate(CAPACITY);
+        Ok(Self {
+            entries,
+            ..Self::default()
+        })
+    }
+
+    #[cfg(not(test))]
+    fn save(&self) -> Result<()> {
+        self.save_to_path(&Self::path()?)
+    }
+
+    /// Like `Config::save`, unit tests persist through `save_to_path` only.
+    #[cfg(test)]
+    fn save(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn save_to_path(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(&self.entries)?;
+        write_atomically(path, json.as_bytes()).context("Failed to save watch history")
+    }
+
+    fn path() -> Result<PathBuf> {
+        Ok(config_dir()?.join("watch_history.json"))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&WatchEntry> {
+        self.entries.get(index)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &WatchEntry> {
+        self.entries.iter()
+    }
+
+    /// Whether the player's current track is already being recorded.
+    pub fn is_recording(&self, video_id: &str) -> bool {
+        self.active
+            && self
+                .entries
+                .first()
+                .is_some_and(|entry| entry.track.id == video_id)
+    }
+
+    /// Record that `track` started playing now.
+    pub fn start(&mut self, track: SearchResult) {
+        let started_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        self.entries.insert(
+            0,
+            WatchEntry {
+                track,
+                started_at,
+                time_pos: 0.0,
+                duration: 0.0,
+                finished: false,
+            },
+        );
+        self.entries.truncate(CAPACITY);
+        self.active = true;
+        self.mark_changed();
+    }
+
+    /// Update how far the current track has got.
+    pub fn update_progress(&mut self, time_pos: f64, duration: f64) {
+        if !self.active {
+            return;
+        }
+        let Some(entry) = self.entries.first_mut() else {
+            return;
+        };
+        if time_pos.is_finite() && time_pos > entry.time_pos {
+            entry.time_pos = time_pos;
+            self.dirty = true;
+        }
+        if duration.is_finite() && duration > 0.0 && duration != entry.duration {
+            entry.duration = duration;
+            self.dirty = true;
+        }
+    }
+
+    /// Stop recording the current track, marking it finished when it ran to
+    /// the end.
+    pub fn stop(&mut self, finished: bool) {
+        if !self.active {
+            return;
+        }
+        self.active = false;
+        if finished && let Some(entry) = self.entries.first_mut() {
+            entry.finished = true;
+            entry.time_pos = entry.time_pos.max(entry.duration);
+        }
+        self.mark_changed();
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<WatchEntry> {
+        if index >= self.entries.len() {
+            return None;
+        }
+        if index == 0 {
+            self.active = false;
+        }
+        self.mark_changed();
+        Some(self.entries.remove(index))
+    }
+
+    /// A change that is saved on the next tick rather than throttled.
+    fn mark_changed(&mut self) {
+        self.dirty = true;
+        self.saved_at = None;
+    }
+
+    /// Whether `video_id` has been watched, or how far it got if not.
+    pub fn mark(&self, video_id: &str) -> Option<WatchMark> {
+        let progress = self
+            .entries
+            .iter()
+            .filter(|entry| entry.track.id == video_id)
+            .map(WatchEntry::progress)
+            .reduce(f64::max)?;
+        Some(if progress >= WATCHED_FRACTION {
+            WatchMark::Watched
+        } else {
+            WatchMark::Partial(progress)
+        })
+    }
+
+    /// Save pending changes, holding progress-only updates back to every few
+    /// seconds.
+    pub fn save_if_due(&mut self) -> Result<()> {
+        let due = self.dirty
+            && self
+                .saved_at
+                .is_none_or(|saved_at| saved_at.elapsed() >= PROGRESS_SAVE_INTERVAL);
+        if due { self.flush() } else { Ok(()) }
+    }
+
+    /// Save pending changes now.
+    pub fn flush(&mut self) -> Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+        self.dirty = false;
+        self.saved_at = Some(Instant::now());
+        self.save()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plays_are_recorded_newest_first_with_progress_and_completion() {
+        let mut history = WatchHistory::default();
+        history.start(SearchResult::test_track("a"));
+        history.update_progress(30.0, 180.0);
+        history.update_progress(12.0, 180.0);
+        history.stop(false);
+        history.update_progress(90.0, 180.0);
+        history.start(SearchResult::test_track("b"));
+        history.update_progress(170.0, 180.0);
+        history.stop(true);
+
+        let ids: Vec<&str> = history
+            .iter()
+            .map(|entry| entry.track.id.as_str())
+            .collect();
+        assert_eq!(ids, ["b", "a"]);
+        assert_eq!(history.get(1).unwrap().time_pos, 30.0);
+        assert!(history.get(0).unwrap().finished);
+        assert_eq!(history.mark("a"), Some(WatchMark::Partial(30.0 / 180.0)));
+        assert_eq!(history.mark("b"), Some(WatchMark::Watched));
+        assert_eq!(history.mark("c"), None);
+        assert!(!history.is_recording("b"));
+    }
+
+    #[test]
+    fn history_round_trips_and_is_bounded() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("youtui/watch_history.json");
+        assert!(WatchHistory::load_from_path(&path).unwrap().is_empty());
+
+        let mut history = WatchHistory::default();
+        for index in 0..=CAPACITY {
+            history.start(SearchResult::test_track(&index.to_string()));
+        }
+        assert_eq!(history.len(), CAPACITY);
+        history.update_progress(42.0, 100.0);
+        history.save_to_path(&path).unwrap();
+
+        let loaded = WatchHistory::load_from_path(&path).unwrap();
+        assert_eq!(loaded.entries, history.entries);
+        // A reloaded history never claims to be recording.
+        assert!(!loaded.is_recording(&CAPACITY.to_string()));
+
+        fs::write(&path, "[{").unwrap();
+        assert!(WatchHistory::load_from_path(&path).is_err());
+    }
+
+    #[test]
+    fn progress_saves_are_throttled_but_starts_and_stops_are_not() {
+        let mut history = WatchHistory::default();
+        history.start(SearchResult::test_track("a"));
+        history.save_if_due().unwrap();
+        assert!(!history.dirty);
+
+        history.update_progress(10.0, 100.0);
+        history.save_if_due().unwrap();
+        assert!(history.dirty);
+
+        history.stop(false);
+        history.save_if_due().unwrap();
+        assert!(!history.dirty);
+    }
+}
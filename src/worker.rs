@@ -0,0 +1,166 @@
+//! One-shot background jobs for the UI loop. A job runs on its own thread and
+//! hands back a single result; dropping the job tells the worker to give up,
+//! so a view that closes or a track that changes never waits on yt-dlp or
+//! curl.
+
+use std::io::Read;
+use std::process::{Command, Output, Stdio};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow, bail};
+
+/// How often a running curl checks whether its job was cancelled.
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+pub struct Job<T> {
+    name: &'static str,
+    cancelled: Arc<AtomicBool>,
+    result: Receiver<Result<T>>,
+}
+
+impl<T: Send + 'static> Job<T> {
+    /// Run `work` on a thread named after the job. The flag it gets is set
+    /// once the job is dropped.
+    pub fn spawn(
+        name: &'static str,
+        work: impl FnOnce(&AtomicBool) -> Result<T> + Send + 'static,
+    ) -> Result<Self> {
+        let (tx, rx) = mpsc::channel();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let worker_cancelled = Arc::clone(&cancelled);
+        thread::Builder::new()
+            .name(format!("youtui-{}", name.to_ascii_lowercase()))
+            .spawn(move || {
+                let _ = tx.send(work(&worker_cancelled));
+            })
+            .with_context(|| format!("Failed to start the {name} worker"))?;
+        Ok(Self {
+            name,
+            cancelled,
+            result: rx,
+        })
+    }
+}
+
+impl<T> Job<T> {
+    /// The result once the worker is done.
+    pub fn poll(&self) -> Option<Result<T>> {
+        match self.result.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => {
+                Some(Err(anyhow!("the {} worker stopped", self.name)))
+            }
+        }
+    }
+}
+
+impl<T> Drop for Job<T> {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Collect curl's output like `Command::output`, but kill it as soon as
+/// `cancelled` is set.
+pub fn run_curl(mut curl: Command, cancelled: &AtomicBool) -> Result<Output> {
+    let mut child = curl
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Could not run curl")?;
+    let (Some(mut stdout), Some(mut stderr)) = (child.stdout.take(), child.stderr.take()) else {
+        bail!("Could not read curl's output");
+    };
+    // Drain both pipes while waiting so a large reply cannot stall curl.
+    thread::scope(|scope| {
+        let stdout = scope.spawn(move || {
+            let mut bytes = Vec::new();
+            stdout.read_to_end(&mut bytes).map(|_| bytes)
+        });
+        let stderr = scope.spawn(move || {
+            let mut bytes = Vec::new();
+            stderr.read_to_end(&mut bytes).map(|_| bytes)
+        });
+        let status = loop {
+            if cancelled.load(Ordering::Relaxed) {
+                let _ = child.kill();
+                let _ = child.wait();
+                bail!("curl was cancelled");
+            }
+            if let Some(status) = child.try_wait().context("Could not wait for curl")? {
+                break status;
+            }
+            thread::sleep(CANCEL_POLL_INTERVAL);
+        };
+        let read = |reader: thread::ScopedJoinHandle<'_, std::io::Result<Vec<u8>>>| {
+            reader
+                .join()
+                .map_err(|_| anyhow!("the curl output reader panicked"))?
+                .context("Could not read curl's output")
+        };
+        Ok(Output {
+            status,
+            stdout: read(stdout)?,
+            stderr: read(stderr)?,
+        })
+    })
+}
+
+/// Check `done` every few milliseconds until it holds, failing the test with
+/// `message` after five seconds.
+#[cfg(test)]
+pub(crate) fn wait_until(message: &str, mut done: impl FnMut() -> bool) {
+    use std::time::Instant;
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !done() {
+        assert!(Instant::now() < deadline, "{message}");
+        thread::sleep(Duration::from_millis(5));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::bail;
+
+    use super::*;
+
+    #[test]
+    fn jobs_deliver_their_result_and_are_cancelled_when_dropped() {
+        let job = Job::spawn("test", |_| Ok(7)).unwrap();
+        let mut result = None;
+        wait_until("the job never finished", || {
+            result = job.poll();
+            result.is_some()
+        });
+        assert_eq!(result.unwrap().unwrap(), 7);
+
+        let job = Job::spawn("test", |_| -> Result<()> { bail!("offline") }).unwrap();
+        let mut result = None;
+        wait_until("the job never failed", || {
+            result = job.poll();
+            result.is_some()
+        });
+        assert_eq!(result.unwrap().unwrap_err().to_string(), "offline");
+
+        let seen = Arc::new(AtomicBool::new(false));
+        let worker_seen = Arc::clone(&seen);
+        let job = Job::spawn("test", move |cancelled| {
+            while !cancelled.load(Ordering::Relaxed) {
+                thread::sleep(Duration::from_millis(5));
+            }
+            worker_seen.store(true, Ordering::Relaxed);
+            Ok(())
+        })
+        .unwrap();
+        assert!(job.poll().is_none());
+        drop(job);
+        wait_until("the job was not cancelled", || seen.load(Ordering::Relaxed));
+    }
+}
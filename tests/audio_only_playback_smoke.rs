@@ -91,6 +91,20 @@ fn audio_only_playback_survives_a_partial_ipc_timeout() {
         report.loaded_url,
         format!("https://www.youtube.com/watch?v={VIDEO_ID}")
     );
+
+    // Quitting with a queued track keeps the session for the next launch.
+    let saved_queue = [
+        fixture.xdg_config.join("youtui/queue.json"),
+        fixture
+            .home
+            .join("Library/Application Support/youtui/queue.json"),
+    ]
+    .into_iter()
+    .find_map(|path| fs::read_to_string(path).ok())
+    .expect("queue was not saved on quit");
+    let saved_queue: Value = serde_json::from_str(&saved_queue).expect("invalid saved queue");
+    assert_eq!(saved_queue["tracks"][0]["id"], VIDEO_ID);
+    assert_eq!(saved_queue["time_pos"], 12.0);
 }
 
 struct SmokeFixture {
@@ -188,6 +202,7 @@ exec sleep 30
             .env("HOME", &self.home)
             .env("XDG_CONFIG_HOME", &self.xdg_config)
             .env("TMPDIR", &self.runtime_tmp)
+            .env("XDG_RUNTIME_DIR", &self.runtime_tmp)
             .env("TERM", "xterm-256color")
             .env("YOUTUI_SMOKE_MPV_ARGS", &self.mpv_args)
             .env("YOUTUI_SMOKE_YTDLP_ARGS", &self.yt_dlp_args);
@@ -527,8 +542,8 @@ fn read_request(reader: &mut BufReader<UnixStream>, timeout: Duration) -> Result
 }
 
 fn read_property_batch(reader: &mut BufReader<UnixStream>) -> Result<Vec<Value>, String> {
-    let mut requests = Vec::with_capacity(5);
-    for _ in 0..5 {
+    let mut requests = Vec::with_capacity(6);
+    for _ in 0..6 {
         let request = read_request(reader, WAIT_TIMEOUT)?;
         let command = command_parts(&request)?;
         if command.first().and_then(Value::as_str) != Some("get_property") {
@@ -578,6 +593,7 @@ fn reply_to_property(stream: &mut UnixStream, request: &Value) -> Result<(), Str
         "pause" => json!(false),
         "volume" => json!(77.0),
         "eof-reached" => json!(false),
+        "chapter" => Value::Null,
         _ => return Err(format!("unexpected property {property}")),
     };
     reply(stream, request_id(request)?, data)
@@ -0,0 +1,284 @@
+#![cfg(unix)]
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+#[test]
+fn search_prints_limited_json_without_starting_the_tui() {
+    let fixture = CliFixture::new();
+
+    let output = fixture.run(&["search", "cli", "smoke", "--json", "--limit", "2"]);
+
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    let results: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("search --json printed invalid JSON");
+    let ids: Vec<&str> = results
+        .as_array()
+        .expect("search --json did not print an array")
+        .iter()
+        .map(|result| result["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, ["cliResult01", "cliResult02"]);
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("\x1b["));
+
+    let invocation = fixture.invocations();
+    assert!(invocation.contains("ytsearch500:cli smoke"), "{invocation}");
+}
+
+#[test]
+fn plain_search_output_is_tab_separated() {
+    let fixture = CliFixture::new();
+
+    let output = fixture.run(&["search", "cli smoke", "--limit=1"]);
+
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "cliResult01\t4:00\tFixture Channel\tCLI Result 01\n"
+    );
+}
+
+#[test]
+fn playlist_urls_stream_the_playlist_instead_of_searching() {
+    let fixture = CliFixture::new();
+
+    let output = fixture.run(&[
+        "search",
+        "https://www.youtube.com/playlist?list=PLcliSmoke&si=share",
+        "--limit",
+        "10",
+    ]);
+
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "cliAlbum001\t0:45\tFixture Artist\tIntro\ncliAlbum003\t5:00\tFixture Artist\tSong\n"
+    );
+    let invocation = fixture.invocations();
+    assert!(
+        invocation.contains("https://www.youtube.com/playlist?list=PLcliSmoke"),
+        "{invocation}"
+    );
+    assert!(!invocation.contains("ytsearch"), "{invocation}");
+}
+
+#[test]
+fn exit_codes_distinguish_usage_errors_and_empty_searches() {
+    let fixture = CliFixture::new();
+
+    let empty = fixture.run(&["search", "nothing here"]);
+    assert_eq!(empty.status.code(), Some(3), "{}", stderr(&empty));
+    assert!(stderr(&empty).contains("No results"));
+
+    let usage = fixture.run(&["search", "--limit", "zero", "query"]);
+    assert_eq!(usage.status.code(), Some(2));
+    assert!(stderr(&usage).contains("--limit"));
+
+    let bad_target = fixture.run(&["play", "not a video"]);
+    assert_eq!(bad_target.status.code(), Some(2));
+}
+
+#[test]
+fn download_passes_the_video_url_to_yt_dlp_and_reports_failure() {
+    let fixture = CliFixture::new();
+
+    let output = fixture.run(&["download", "https://youtu.be/cliResult01"]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    // The download is announced by its title, not its URL.
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Fixture Video cliResult01"), "{stdout}");
+    assert!(!stdout.contains("watch?v="), "{stdout}");
+    let invocation = fixture.invocations();
+    assert!(
+        invocation.contains("https://www.youtube.com/watch?v=cliResult01"),
+        "{invocation}"
+    );
+    assert!(invocation.contains(&fixture.downloads.display().to_string()));
+
+    let failed = fixture.run(&["download", "failDownld1"]);
+    assert_eq!(failed.status.code(), Some(1), "{}", stderr(&failed));
+}
+
+#[test]
+fn remote_talks_to_the_control_socket_and_fails_when_nothing_listens() {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::fs::MetadataExt;
+    use std::os::unix::net::UnixListener;
+
+    let fixture = CliFixture::new();
+
+    let missing = fixture.run(&["remote", "toggle-pause"]);
+    assert_eq!(missing.status.code(), Some(1));
+    assert!(
+        stderr(&missing).contains("not running"),
+        "{}",
+        stderr(&missing)
+    );
+
+    let usage = fixture.run(&["remote"]);
+    assert_eq!(usage.status.code(), Some(2));
+
+    // Stand in for the TUI on the socket path the client computes.
+    let socket = if cfg!(target_os = "linux") {
+        fixture.runtime.join("youtui.sock")
+    } else {
+        let uid = fs::metadata(&fixture.runtime).unwrap().uid();
+        let dir = fixture.runtime.join(format!("youtui-{uid}"));
+        fs::create_dir(&dir).unwrap();
+        dir.join("youtui.sock")
+    };
+    let listener = UnixListener::bind(&socket).unwrap();
+    let server = std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut request = String::new();
+        BufReader::new(&stream).read_line(&mut request).unwrap();
+        writeln!(
+            &stream,
+            r#"{{"request_id": 1, "error": "success", "data": {{"paused": true}}}}"#
+        )
+        .unwrap();
+        request
+    });
+
+    let output = fixture.run(&["remote", "seek", "-10"]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    let request: serde_json::Value = serde_json::from_str(&server.join().unwrap()).unwrap();
+    assert_eq!(request["command"], serde_json::json!(["seek", "-10"]));
+    let data: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(data["paused"], true);
+}
+
+struct CliFixture {
+    _root: tempfile::TempDir,
+    fake_bin: PathBuf,
+    home: PathBuf,
+    xdg_config: PathBuf,
+    downloads: PathBuf,
+    runtime: PathBuf,
+    invocation_log: PathBuf,
+}
+
+impl CliFixture {
+    fn new() -> Self {
+        // Keep executable shims off /tmp because hardened Linux systems may
+        // mount it noexec.
+        let fixture_parent = Path::new(env!("CARGO_MANIFEST_DIR")).join("target");
+        fs::create_dir_all(&fixture_parent).expect("failed to create fixture parent");
+        let root = tempfile::Builder::new()
+            .prefix("ytc-cli-")
+            .tempdir_in(fixture_parent)
+            .expect("failed to create CLI fixture");
+        let root_path = root.path().to_path_buf();
+        let fake_bin = root_path.join("bin");
+        let home = root_path.join("home");
+        let xdg_config = root_path.join("config");
+        let downloads = root_path.join("downloads");
+        let runtime = root_path.join("run");
+        let invocation_log = root_path.join("invocations.log");
+
+        for directory in [&fake_bin, &home, &xdg_config, &downloads, &runtime] {
+            fs::create_dir_all(directory).expect("failed to create fixture directory");
+        }
+
+        let config = format!(
+            "include_shorts = true\ncustom_format = \"best\"\ndownload_dir = \"{}\"\n",
+            downloads.display()
+        );
+        write_file(&xdg_config.join("youtui/config.toml"), &config);
+        write_file(
+            &home.join("Library/Application Support/youtui/config.toml"),
+            &config,
+        );
+        write_executable(&fake_bin.join("yt-dlp"), FAKE_YT_DLP);
+
+        Self {
+            _root: root,
+            fake_bin,
+            home,
+            xdg_config,
+            downloads,
+            runtime,
+            invocation_log,
+        }
+    }
+
+    fn run(&self, args: &[&str]) -> Output {
+        let inherited_path = std::env::var_os("PATH").unwrap_or_default();
+        let path = std::env::join_paths(
+            std::iter::once(self.fake_bin.clone()).chain(std::env::split_paths(&inherited_path)),
+        )
+        .expect("failed to construct fixture PATH");
+
+        Command::new(env!("CARGO_BIN_EXE_youtui"))
+            .args(args)
+            .env("PATH", path)
+            .env("HOME", &self.home)
+            .env("XDG_CONFIG_HOME", &self.xdg_config)
+            .env("XDG_RUNTIME_DIR", &self.runtime)
+            .env("TMPDIR", &self.runtime)
+            .env("YOUTUI_CLI_INVOCATIONS", &self.invocation_log)
+            .output()
+            .expect("failed to run youtui")
+    }
+
+    fn invocations(&self) -> String {
+        fs::read_to_string(&self.invocation_log).unwrap_or_default()
+    }
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+const FAKE_YT_DLP: &str = r#"#!/bin/sh
+set -eu
+printf '%s\n' "$*" >> "$YOUTUI_CLI_INVOCATIONS"
+case "$*" in
+    *--dump-json*watch?v=*)
+        for url in "$@"; do :; done
+        id=${url##*=}
+        printf '{"id":"%s","title":"Fixture Video %s","duration":60,"duration_string":"1:00","channel":"Fixture Channel"}\n' \
+            "$id" "$id"
+        ;;
+    *"ytsearch500:cli smoke"*)
+        for index in 01 02 03 04 05; do
+            printf '{"id":"cliResult%s","title":"CLI Result %s","duration":240,"duration_string":"4:00","channel":"Fixture Channel","view_count":10,"playlist_index":%d}\n' \
+                "$index" "$index" "${index#0}"
+        done
+        ;;
+    *"ytsearch500:nothing here"*)
+        ;;
+    *"playlist?list=PLcliSmoke"*)
+        printf '%s\n' \
+            '{"id":"cliAlbum001","title":"Intro","duration":45,"duration_string":"0:45","channel":"Fixture Artist","playlist_index":1}' \
+            '{"id":"cliAlbum002","title":"[Private video]","duration":null,"channel":null,"playlist_index":2}' \
+            '{"id":"cliAlbum003","title":"Song","duration":300,"duration_string":"5:00","channel":"Fixture Artist","playlist_index":3}'
+        ;;
+    *failDownld1)
+        exit 1
+        ;;
+    *https://www.youtube.com/watch*)
+        ;;
+    *)
+        echo "unexpected fixture invocation: $*" >&2
+        exit 2
+        ;;
+esac
+"#;
+
+fn write_file(path: &Path, contents: &str) {
+    fs::create_dir_all(path.parent().expect("path has no parent"))
+        .expect("failed to create parent directory");
+    fs::write(path, contents).expect("failed to write fixture file");
+}
+
+fn write_executable(path: &Path, contents: &str) {
+    write_file(path, contents);
+    let mut permissions = fs::metadata(path)
+        .expect("failed to stat fake executable")
+        .permissions();
+    permissions.set_mode(0o755);
+    fs::set_permissions(path, permissions).expect("failed to make fixture executable");
+}
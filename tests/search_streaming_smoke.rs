@@ -293,6 +293,7 @@ impl SearchFixture {
             .env("HOME", &self.home)
             .env("XDG_CONFIG_HOME", &self.xdg_config)
             .env("TMPDIR", &self.runtime_tmp)
+            .env("XDG_RUNTIME_DIR", &self.runtime_tmp)
             .env("TERM", "xterm-256color")
             .env("YOUTUI_SEARCH_CONTROL", &self.control)
             .env("YOUTUI_SEARCH_INVOCATIONS", &self.invocation_log);